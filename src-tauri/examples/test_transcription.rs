@@ -48,6 +48,30 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         TranscriptEvent::Error { message } => {
             println!("\n[错误] {}", message);
         }
+        TranscriptEvent::Reconnecting { attempt } => {
+            println!("\n[重新连接] 第 {} 次尝试", attempt);
+        }
+        TranscriptEvent::Reconnected => {
+            println!("\n[已重新连接]");
+        }
+        TranscriptEvent::SpeechStarted => {
+            println!("\n[开始说话]");
+        }
+        TranscriptEvent::SpeechEnded => {
+            println!("\n[说话结束]");
+        }
+        TranscriptEvent::SilenceTimeout { silence_ms } => {
+            println!("\n[持续静音] {} ms", silence_ms);
+        }
+        TranscriptEvent::AudioLevel { .. } => {
+            // 每秒约 30 次，不打印
+        }
+        TranscriptEvent::DeviceLost { message } => {
+            println!("\n[设备断开] {}", message);
+        }
+        TranscriptEvent::DeviceSwitched { device, .. } => {
+            println!("\n[切换设备] {}", device);
+        }
         TranscriptEvent::Closed => {
             println!("\n[会话关闭]");
         }
@@ -61,7 +85,9 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("停止录音...");
 
     // 停止会话
-    session.stop().await?;
+    if let Some(text) = session.stop().await? {
+        println!("最终文本: {}", text);
+    }
 
     println!("\n测试完成!");
 
@@ -0,0 +1,70 @@
+//! 音频文件转写
+//!
+//! 转写已有的 WAV 录音, 或从标准输入读取 16kHz 单声道 s16le PCM (需要 ElevenLabs API Key)
+//!
+//! 运行:
+//!   set ELEVENLABS_API_KEY=your-api-key
+//!   cargo run --example transcribe_file -- recording.wav
+//!   arecord -f S16_LE -r 16000 -c 1 | cargo run --example transcribe_file -- -
+
+use raflow_lib::audio::{AudioSource, FileSource, Pacing, PcmFormat};
+use raflow_lib::transcription::{TranscriptEvent, TranscriptionOptions, TranscriptionSession};
+use tokio::sync::mpsc;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // 初始化日志
+    tracing_subscriber::fmt()
+        .with_max_level(tracing::Level::INFO)
+        .init();
+    println!("=== 音频文件转写 ===\n");
+
+    let path = std::env::args()
+        .nth(1)
+        .ok_or("用法: transcribe_file <WAV 文件 | - (标准输入)>")?;
+
+    // 获取 API Key
+    let api_key =
+        std::env::var("ELEVENLABS_API_KEY").map_err(|_| "请设置 ELEVENLABS_API_KEY 环境变量")?;
+
+    // 文件尽快发送, 标准输入按实际速度读取
+    let source: Box<dyn AudioSource> = if path == "-" {
+        Box::new(FileSource::stdin(PcmFormat::new(16000, 1)))
+    } else {
+        Box::new(FileSource::open_wav(&path)?.with_pacing(Pacing::Fast))
+    };
+
+    println!("输入: {} ({} Hz)", path, source.sample_rate());
+    println!("{}", "-".repeat(50));
+
+    let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+    let options = TranscriptionOptions::new(api_key);
+
+    let mut session =
+        TranscriptionSession::start_with_source(&options, source, move |event| match event {
+            TranscriptEvent::Partial { text } => {
+                print!("\r[部分转写] {}                    ", text);
+            }
+            TranscriptEvent::Committed { text } => {
+                println!("\n[最终结果] {}", text);
+            }
+            TranscriptEvent::Error { message } => {
+                println!("\n[错误] {}", message);
+            }
+            TranscriptEvent::Closed => {
+                let _ = closed_tx.send(());
+            }
+            _ => {}
+        })
+        .await?;
+
+    // 输入结束后会话自动关闭
+    closed_rx.recv().await;
+
+    println!("\n{}", "-".repeat(50));
+    if let Some(text) = session.stop().await? {
+        println!("全文: {}", text);
+    }
+
+    Ok(())
+}
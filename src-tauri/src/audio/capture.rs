@@ -1,7 +1,11 @@
 use crate::audio::error::{AudioError, AudioResult};
 use crate::audio::device::find_device_by_id;
+use crate::audio::{ChannelMode, DeviceEvent};
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
-use cpal::{Device, Stream, StreamConfig};
+use cpal::{
+    Device, FromSample, Sample, SampleFormat, SizedSample, Stream, StreamConfig,
+    SupportedStreamConfig,
+};
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info, warn};
@@ -9,7 +13,14 @@ use tracing::{debug, error, info, warn};
 /// Audio capture manager
 ///
 /// Manages audio input stream and provides methods to start/stop capture.
-/// Audio data is sent through a channel as Vec<f32> samples.
+/// The device is opened with its native channel count and sample format;
+/// each callback buffer is converted to normalized f32, reduced to mono (see
+/// [`ChannelMode`]) and sent through a channel as Vec<f32> samples.
+///
+/// Errors reported by the stream (such as the device being unplugged) are
+/// sent as [`DeviceEvent::Error`] to the channel set with
+/// [`set_device_events`](Self::set_device_events). Capture does not recover
+/// by itself; see [`SupervisedCapture`](crate::audio::SupervisedCapture).
 pub struct AudioCapture {
     /// The audio input stream (None when stopped)
     stream: Option<Stream>,
@@ -19,8 +30,16 @@ pub struct AudioCapture {
     channels: u16,
     /// Device being used for capture
     device: Device,
+    /// Name of the device
+    device_name: String,
     /// Stream configuration
     config: StreamConfig,
+    /// Sample format delivered by the device
+    sample_format: SampleFormat,
+    /// How the device channels are reduced to mono
+    channel_mode: ChannelMode,
+    /// Channel for stream errors
+    device_events: Option<mpsc::Sender<DeviceEvent>>,
 }
 
 impl AudioCapture {
@@ -59,13 +78,14 @@ impl AudioCapture {
         let device_name = device.name().unwrap_or_else(|_| "Unknown".to_string());
         info!("Using audio input device: {}", device_name);
 
-        let config = device.default_input_config()?;
+        let config = negotiate_input_config(&device, device.default_input_config()?);
         let sample_rate = config.sample_rate().0;
         let channels = config.channels();
+        let sample_format = config.sample_format();
 
         info!(
-            "Device config: {} Hz, {} channels",
-            sample_rate, channels
+            "Device config: {} Hz, {} channels, {}",
+            sample_rate, channels, sample_format
         );
 
         let stream_config = StreamConfig {
@@ -79,10 +99,47 @@ impl AudioCapture {
             sample_rate,
             channels,
             device,
+            device_name,
             config: stream_config,
+            sample_format,
+            channel_mode: ChannelMode::Downmix,
+            device_events: None,
         })
     }
 
+    /// Set how the device channels are reduced to mono
+    ///
+    /// A selected channel the device does not have falls back to downmixing.
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raflow_lib::audio::{capture::AudioCapture, ChannelMode};
+    ///
+    /// // Microphone on the second input of a USB interface
+    /// let capture = AudioCapture::new(None)
+    ///     .unwrap()
+    ///     .with_channel_mode(ChannelMode::Select(1));
+    /// ```
+    pub fn with_channel_mode(mut self, mode: ChannelMode) -> Self {
+        self.set_channel_mode(mode);
+        self
+    }
+
+    /// Set how the device channels are reduced to mono
+    ///
+    /// Takes effect from the next [`start`](Self::start).
+    pub fn set_channel_mode(&mut self, mode: ChannelMode) {
+        self.channel_mode = mode.resolve(self.channels);
+    }
+
+    /// Set the channel that receives stream errors
+    ///
+    /// Takes effect from the next [`start`](Self::start). Errors are dropped
+    /// if the channel is full.
+    pub fn set_device_events(&mut self, events: mpsc::Sender<DeviceEvent>) {
+        self.device_events = Some(events);
+    }
+
     /// Start capturing audio
     ///
     /// # Arguments
@@ -118,41 +175,72 @@ impl AudioCapture {
             return Ok(());
         }
 
-        info!("Starting audio capture");
+        info!(
+            "Starting audio capture ({} channels, {}, {:?})",
+            self.channels, self.sample_format, self.channel_mode
+        );
+
+        let build = stream_builder(self.sample_format).ok_or_else(|| {
+            AudioError::StreamBuildFailed(format!(
+                "Unsupported sample format: {}",
+                self.sample_format
+            ))
+        })?;
+        let stream = build(self, sender)?;
 
+        // Start the stream
+        stream
+            .play()
+            .map_err(|e| AudioError::StreamError(e.to_string()))?;
+
+        self.stream = Some(stream);
+        info!("Audio capture started successfully");
+
+        Ok(())
+    }
+
+    /// Build an input stream for samples of type `T`
+    ///
+    /// Each callback buffer is converted to normalized f32 and reduced to mono
+    /// before anything downstream sees it.
+    fn build_stream<T>(&self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<Stream>
+    where
+        T: SizedSample,
+        f32: FromSample<T>,
+    {
         // Create an Arc to share the sender across the audio callback
         let sender = Arc::new(sender);
         let sender_clone = Arc::clone(&sender);
+        let channels = self.channels;
+        let channel_mode = self.channel_mode;
+        let device_name = self.device_name.clone();
+        let device_events = self.device_events.clone();
 
-        // Build the input stream
-        let stream = self
-            .device
+        self.device
             .build_input_stream(
                 &self.config,
-                move |data: &[f32], _: &cpal::InputCallbackInfo| {
+                move |data: &[T], _: &cpal::InputCallbackInfo| {
+                    let samples = samples_to_f32(data);
+                    let mono = channel_mode.to_mono(&samples, channels);
+
                     // Use try_send to avoid blocking the audio thread
                     // If the channel is full, we'll just drop this batch
-                    if let Err(_) = sender_clone.try_send(data.to_vec()) {
+                    if let Err(_) = sender_clone.try_send(mono) {
                         // Silently drop if channel is full to avoid blocking
                         // This is expected behavior under high load
                     }
                 },
                 move |err| {
-                    error!("Audio stream error: {}", err);
+                    error!("Audio stream error on {}: {}", device_name, err);
+
+                    if let Some(events) = &device_events {
+                        let error = stream_error(&device_name, err);
+                        let _ = events.try_send(DeviceEvent::Error(error));
+                    }
                 },
                 None,
             )
-            .map_err(|e| AudioError::StreamBuildFailed(e.to_string()))?;
-
-        // Start the stream
-        stream
-            .play()
-            .map_err(|e| AudioError::StreamError(e.to_string()))?;
-
-        self.stream = Some(stream);
-        info!("Audio capture started successfully");
-
-        Ok(())
+            .map_err(|e| AudioError::StreamBuildFailed(e.to_string()))
     }
 
     /// Stop capturing audio
@@ -199,11 +287,29 @@ impl AudioCapture {
     /// Get the number of channels
     ///
     /// # Returns
-    /// The number of audio channels
+    /// The number of audio channels the device is opened with. Captured
+    /// samples are always mono.
     pub fn channels(&self) -> u16 {
         self.channels
     }
 
+    /// Get the name of the capture device
+    pub fn device_name(&self) -> &str {
+        &self.device_name
+    }
+
+    /// Get the sample format delivered by the device
+    ///
+    /// Captured samples are always converted to normalized f32.
+    pub fn sample_format(&self) -> SampleFormat {
+        self.sample_format
+    }
+
+    /// Get how the device channels are reduced to mono
+    pub fn channel_mode(&self) -> ChannelMode {
+        self.channel_mode
+    }
+
     /// Check if capture is currently active
     ///
     /// # Returns
@@ -219,6 +325,96 @@ impl Drop for AudioCapture {
     }
 }
 
+/// Map a cpal stream error to an [`AudioError`]
+fn stream_error(device_name: &str, err: cpal::StreamError) -> AudioError {
+    match err {
+        cpal::StreamError::DeviceNotAvailable => {
+            AudioError::DeviceDisconnected(device_name.to_string())
+        }
+        cpal::StreamError::BackendSpecific { err } => AudioError::StreamError(err.description),
+    }
+}
+
+/// Builds an input stream for one sample format
+type StreamBuilder = fn(&AudioCapture, mpsc::Sender<Vec<f32>>) -> AudioResult<Stream>;
+
+/// The stream builder for samples of this format, or None if
+/// [`AudioCapture`] cannot convert them
+fn stream_builder(format: SampleFormat) -> Option<StreamBuilder> {
+    let build: StreamBuilder = match format {
+        SampleFormat::F32 => AudioCapture::build_stream::<f32>,
+        SampleFormat::F64 => AudioCapture::build_stream::<f64>,
+        SampleFormat::I8 => AudioCapture::build_stream::<i8>,
+        SampleFormat::I16 => AudioCapture::build_stream::<i16>,
+        SampleFormat::I32 => AudioCapture::build_stream::<i32>,
+        SampleFormat::I64 => AudioCapture::build_stream::<i64>,
+        SampleFormat::U8 => AudioCapture::build_stream::<u8>,
+        SampleFormat::U16 => AudioCapture::build_stream::<u16>,
+        SampleFormat::U32 => AudioCapture::build_stream::<u32>,
+        SampleFormat::U64 => AudioCapture::build_stream::<u64>,
+        _ => return None,
+    };
+    Some(build)
+}
+
+/// Whether [`AudioCapture`] can convert samples of this format
+fn is_supported_format(format: SampleFormat) -> bool {
+    stream_builder(format).is_some()
+}
+
+/// Pick the input config to open the device with
+///
+/// The default config is used when its sample format can be converted.
+/// Otherwise an f32 config (or any convertible one) at the default sample
+/// rate is taken from the device's supported configs.
+fn negotiate_input_config(
+    device: &Device,
+    default: SupportedStreamConfig,
+) -> SupportedStreamConfig {
+    if is_supported_format(default.sample_format()) {
+        return default;
+    }
+
+    let rate = default.sample_rate();
+    let candidates: Vec<_> = match device.supported_input_configs() {
+        Ok(configs) => configs
+            .filter(|c| is_supported_format(c.sample_format()))
+            .filter(|c| c.min_sample_rate() <= rate && rate <= c.max_sample_rate())
+            .collect(),
+        Err(e) => {
+            warn!("Failed to query supported input configs: {}", e);
+            return default;
+        }
+    };
+
+    let chosen = candidates
+        .iter()
+        .find(|c| c.sample_format() == SampleFormat::F32)
+        .or_else(|| candidates.first());
+
+    match chosen {
+        Some(range) => {
+            let config = range.with_sample_rate(rate);
+            info!(
+                "Default sample format {} not supported, using {}",
+                default.sample_format(),
+                config.sample_format()
+            );
+            config
+        }
+        None => default,
+    }
+}
+
+/// Convert device samples to normalized f32 in [-1.0, 1.0)
+fn samples_to_f32<T>(data: &[T]) -> Vec<f32>
+where
+    T: Sample,
+    f32: FromSample<T>,
+{
+    data.iter().map(|&s| s.to_sample::<f32>()).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -321,6 +517,34 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_samples_to_f32_integer_formats() {
+        assert_eq!(samples_to_f32(&[i16::MIN, 0i16]), vec![-1.0, 0.0]);
+        assert!((samples_to_f32(&[i16::MAX])[0] - 1.0).abs() < 1e-4);
+
+        assert_eq!(samples_to_f32(&[i32::MIN, 0i32]), vec![-1.0, 0.0]);
+        assert!((samples_to_f32(&[i32::MAX])[0] - 1.0).abs() < 1e-6);
+
+        // Unsigned formats are centred on the mid value
+        assert_eq!(samples_to_f32(&[0u16, 32768u16]), vec![-1.0, 0.0]);
+        assert!((samples_to_f32(&[u16::MAX])[0] - 1.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_samples_to_f32_float_passthrough() {
+        let data = vec![0.25f32, -0.5, 0.0];
+        assert_eq!(samples_to_f32(&data), data);
+        assert_eq!(samples_to_f32(&[0.5f64, -0.25]), vec![0.5f32, -0.25]);
+    }
+
+    #[test]
+    fn test_supported_formats() {
+        assert!(is_supported_format(SampleFormat::F32));
+        assert!(is_supported_format(SampleFormat::I16));
+        assert!(is_supported_format(SampleFormat::U16));
+        assert!(is_supported_format(SampleFormat::I32));
+    }
+
     #[tokio::test]
     async fn test_audio_capture_with_specific_device() {
         // This test tries to use a specific device
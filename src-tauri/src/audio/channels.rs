@@ -0,0 +1,157 @@
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+
+/// How interleaved multi-channel input is reduced to mono
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::ChannelMode;
+///
+/// // Stereo frames: (L, R)
+/// let stereo = [0.2, 0.4, -0.2, 0.0];
+///
+/// assert_eq!(ChannelMode::Downmix.to_mono(&stereo, 2), vec![0.3f32, -0.1]);
+/// assert_eq!(ChannelMode::Select(1).to_mono(&stereo, 2), vec![0.4f32, 0.0]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum ChannelMode {
+    /// Average all channels
+    #[default]
+    Downmix,
+
+    /// Use only the channel with this (zero-based) index
+    Select(u16),
+}
+
+impl ChannelMode {
+    /// Build a mode from an optional channel index (None downmixes)
+    pub fn from_channel(channel: Option<u16>) -> Self {
+        match channel {
+            Some(index) => Self::Select(index),
+            None => Self::Downmix,
+        }
+    }
+
+    /// Check the mode against a device's channel count
+    ///
+    /// A selected channel that the device does not have falls back to
+    /// downmixing.
+    pub fn resolve(self, channels: u16) -> Self {
+        match self {
+            Self::Select(index) if index >= channels => {
+                warn!(
+                    "Channel {} not available on a {}-channel device, downmixing instead",
+                    index, channels
+                );
+                Self::Downmix
+            }
+            mode => mode,
+        }
+    }
+
+    /// Convert interleaved samples to mono
+    ///
+    /// Incomplete trailing frames are ignored. A selected channel outside the
+    /// frame is treated as [`ChannelMode::Downmix`].
+    pub fn to_mono(self, data: &[f32], channels: u16) -> Vec<f32> {
+        let channels = channels as usize;
+        if channels <= 1 {
+            return data.to_vec();
+        }
+
+        let frames = data.chunks_exact(channels);
+        match self {
+            Self::Select(index) if (index as usize) < channels => {
+                frames.map(|frame| frame[index as usize]).collect()
+            }
+            _ => {
+                let scale = 1.0 / channels as f32;
+                frames
+                    .map(|frame| frame.iter().sum::<f32>() * scale)
+                    .collect()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Interleave per-channel buffers into frames
+    fn interleave(channels: &[Vec<f32>]) -> Vec<f32> {
+        let frames = channels[0].len();
+        (0..frames)
+            .flat_map(|i| channels.iter().map(move |c| c[i]))
+            .collect()
+    }
+
+    fn tone(freq: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| (2.0 * std::f32::consts::PI * freq * i as f32 / 48000.0).sin() * 0.5)
+            .collect()
+    }
+
+    #[test]
+    fn test_mono_passthrough() {
+        let data = vec![0.1, 0.2, 0.3];
+        assert_eq!(ChannelMode::Downmix.to_mono(&data, 1), data);
+        assert_eq!(ChannelMode::Select(0).to_mono(&data, 1), data);
+    }
+
+    #[test]
+    fn test_stereo_downmix_keeps_length_and_speed() {
+        // 10ms of stereo audio at 48kHz
+        let left = tone(440.0, 480);
+        let right = tone(440.0, 480);
+        let data = interleave(&[left.clone(), right]);
+
+        let mono = ChannelMode::Downmix.to_mono(&data, 2);
+
+        // One mono sample per frame, not per interleaved sample
+        assert_eq!(mono.len(), 480);
+        for (a, b) in mono.iter().zip(&left) {
+            assert!((a - b).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_stereo_downmix_averages() {
+        let data = interleave(&[vec![1.0, 0.5], vec![0.0, -0.5]]);
+        assert_eq!(ChannelMode::Downmix.to_mono(&data, 2), vec![0.5, 0.0]);
+    }
+
+    #[test]
+    fn test_select_channel_of_four() {
+        let channels: Vec<Vec<f32>> = (0..4).map(|c| vec![c as f32; 100]).collect();
+        let data = interleave(&channels);
+
+        for index in 0..4u16 {
+            let mono = ChannelMode::Select(index).to_mono(&data, 4);
+            assert_eq!(mono.len(), 100);
+            assert!(mono.iter().all(|&s| s == index as f32));
+        }
+    }
+
+    #[test]
+    fn test_select_out_of_range_downmixes() {
+        let data = interleave(&[vec![1.0; 10], vec![0.0; 10]]);
+
+        assert_eq!(ChannelMode::Select(5).resolve(2), ChannelMode::Downmix);
+        assert_eq!(ChannelMode::Select(1).resolve(2), ChannelMode::Select(1));
+        assert_eq!(ChannelMode::Select(5).to_mono(&data, 2), vec![0.5; 10]);
+    }
+
+    #[test]
+    fn test_incomplete_frame_is_ignored() {
+        let data = vec![0.1, 0.2, 0.3, 0.4, 0.5];
+        assert_eq!(ChannelMode::Select(0).to_mono(&data, 2), vec![0.1, 0.3]);
+    }
+
+    #[test]
+    fn test_from_channel() {
+        assert_eq!(ChannelMode::from_channel(None), ChannelMode::Downmix);
+        assert_eq!(ChannelMode::from_channel(Some(2)), ChannelMode::Select(2));
+        assert_eq!(ChannelMode::default(), ChannelMode::Downmix);
+    }
+}
@@ -4,7 +4,7 @@ use cpal::{Device, Host, StreamConfig};
 use serde::{Deserialize, Serialize};
 
 /// Information about an audio device
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AudioDevice {
     /// Device identifier (unique name)
     pub id: String,
@@ -15,10 +15,30 @@ pub enum AudioError {
     #[error("Audio stream error: {0}")]
     StreamError(String),
 
+    /// Capture device was unplugged or became unavailable
+    #[error("Audio device disconnected: {0}")]
+    DeviceDisconnected(String),
+
+    /// Capture device stopped delivering audio without reporting an error
+    #[error("No audio from {device} for {silent_ms} ms")]
+    DeviceStalled { device: String, silent_ms: u64 },
+
     /// Resampling failed
     #[error("Resampling failed: {0}")]
     ResampleFailed(String),
 
+    /// Noise suppression failed
+    #[error("Noise suppression failed: {0}")]
+    NoiseSuppressionFailed(String),
+
+    /// Audio data is in a format that cannot be read
+    #[error("Unsupported audio format: {0}")]
+    UnsupportedFormat(String),
+
+    /// Reading or writing audio data failed
+    #[error("Audio I/O error: {0}")]
+    Io(#[from] std::io::Error),
+
     /// Device name is invalid
     #[error("Device name is invalid UTF-8")]
     InvalidDeviceName,
@@ -40,5 +60,16 @@ pub enum AudioError {
     SupportedConfigError(#[from] cpal::SupportedStreamConfigsError),
 }
 
+impl AudioError {
+    /// Whether the error means the capture device is gone or no longer
+    /// delivers audio, so capture cannot continue on it
+    pub fn is_device_lost(&self) -> bool {
+        matches!(
+            self,
+            AudioError::DeviceDisconnected(_) | AudioError::DeviceStalled { .. }
+        )
+    }
+}
+
 /// Result type for audio operations
 pub type AudioResult<T> = Result<T, AudioError>;
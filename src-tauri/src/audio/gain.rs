@@ -0,0 +1,350 @@
+use tracing::debug;
+
+/// Default RMS level the automatic gain control aims for
+pub const DEFAULT_AGC_TARGET_RMS: f32 = 0.1;
+
+/// Default upper bound for the automatic gain
+pub const DEFAULT_AGC_MAX_GAIN: f32 = 10.0;
+
+/// Default level above which the limiter starts compressing peaks
+pub const DEFAULT_LIMITER_THRESHOLD: f32 = 0.9;
+
+/// Blocks quieter than this are treated as background noise and do not
+/// change the automatic gain
+const AGC_NOISE_FLOOR: f32 = 0.003;
+
+/// Time constant for lowering the gain (loud input), in milliseconds
+const AGC_ATTACK_MS: f32 = 50.0;
+
+/// Time constant for raising the gain (quiet input), in milliseconds
+const AGC_RELEASE_MS: f32 = 800.0;
+
+/// Gain settings for the audio pipeline
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::GainConfig;
+///
+/// let config = GainConfig::new(1.5).with_agc(0.1);
+///
+/// assert_eq!(config.gain, 1.5);
+/// assert!(config.agc_enabled);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GainConfig {
+    /// Fixed gain applied to every sample
+    pub gain: f32,
+
+    /// Whether automatic gain control is enabled
+    pub agc_enabled: bool,
+
+    /// RMS level (0.0 - 1.0) the automatic gain control aims for
+    pub agc_target_rms: f32,
+
+    /// Upper bound for the automatic gain
+    pub agc_max_gain: f32,
+
+    /// Level (0.0 - 1.0) above which peaks are softly compressed
+    pub limiter_threshold: f32,
+}
+
+impl Default for GainConfig {
+    fn default() -> Self {
+        Self::new(1.0)
+    }
+}
+
+impl GainConfig {
+    /// Create a configuration with a fixed gain and AGC disabled
+    pub fn new(gain: f32) -> Self {
+        Self {
+            gain: gain.max(0.0),
+            agc_enabled: false,
+            agc_target_rms: DEFAULT_AGC_TARGET_RMS,
+            agc_max_gain: DEFAULT_AGC_MAX_GAIN,
+            limiter_threshold: DEFAULT_LIMITER_THRESHOLD,
+        }
+    }
+
+    /// Enable automatic gain control towards `target_rms`
+    pub fn with_agc(mut self, target_rms: f32) -> Self {
+        self.agc_enabled = true;
+        self.agc_target_rms = target_rms.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the upper bound for the automatic gain
+    pub fn with_max_gain(mut self, max_gain: f32) -> Self {
+        self.agc_max_gain = max_gain.max(1.0);
+        self
+    }
+
+    /// Set the limiter threshold
+    pub fn with_limiter_threshold(mut self, threshold: f32) -> Self {
+        self.limiter_threshold = threshold.clamp(0.1, 1.0);
+        self
+    }
+
+    /// Whether the stage leaves samples unchanged
+    pub fn is_passthrough(&self) -> bool {
+        !self.agc_enabled && self.gain == 1.0
+    }
+}
+
+/// Fixed gain, automatic gain control and peak limiter for f32 samples
+///
+/// The fixed gain is applied first. With AGC enabled, the block RMS is then
+/// tracked and an extra gain (up to `agc_max_gain`) pulls it towards the
+/// target: quickly down for loud input, slowly up for quiet input, and not at
+/// all for blocks below the noise floor so silence is not pumped up. Finally
+/// a soft limiter keeps every sample inside (-1.0, 1.0).
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::{GainConfig, GainStage};
+///
+/// let mut stage = GainStage::new(GainConfig::new(2.0), 16000);
+/// let mut samples = vec![0.1, -0.2];
+/// stage.process(&mut samples);
+///
+/// assert!((samples[0] - 0.2).abs() < 1e-6);
+/// assert!((samples[1] + 0.4).abs() < 1e-6);
+/// ```
+pub struct GainStage {
+    /// Gain settings
+    config: GainConfig,
+    /// Sample rate of the processed samples
+    sample_rate: u32,
+    /// Current automatic gain
+    agc_gain: f32,
+}
+
+impl GainStage {
+    /// Create a new gain stage
+    ///
+    /// # Arguments
+    /// * `config` - Gain settings
+    /// * `sample_rate` - Sample rate of the samples passed to [`process`](Self::process)
+    pub fn new(config: GainConfig, sample_rate: u32) -> Self {
+        Self {
+            config,
+            sample_rate,
+            agc_gain: 1.0,
+        }
+    }
+
+    /// Apply gain, AGC and limiter to `samples` in place
+    pub fn process(&mut self, samples: &mut [f32]) {
+        if samples.is_empty() || self.config.is_passthrough() {
+            return;
+        }
+
+        let gain = self.config.gain;
+        for sample in samples.iter_mut() {
+            *sample *= gain;
+        }
+
+        if self.config.agc_enabled {
+            self.apply_agc(samples);
+        }
+
+        let threshold = self.config.limiter_threshold;
+        for sample in samples.iter_mut() {
+            *sample = soft_limit(*sample, threshold);
+        }
+    }
+
+    /// Current automatic gain (1.0 when AGC is disabled)
+    pub fn agc_gain(&self) -> f32 {
+        self.agc_gain
+    }
+
+    /// Get the gain settings
+    pub fn config(&self) -> &GainConfig {
+        &self.config
+    }
+
+    /// Reset the automatic gain
+    pub fn reset(&mut self) {
+        self.agc_gain = 1.0;
+    }
+
+    /// Update the automatic gain from the block level and apply it
+    fn apply_agc(&mut self, samples: &mut [f32]) {
+        let previous = self.agc_gain;
+        let level = block_rms(samples);
+
+        if level > AGC_NOISE_FLOOR {
+            let desired = (self.config.agc_target_rms / level).clamp(0.1, self.config.agc_max_gain);
+            let time_constant = if desired < previous {
+                AGC_ATTACK_MS
+            } else {
+                AGC_RELEASE_MS
+            };
+
+            let block_ms = samples.len() as f32 * 1000.0 / self.sample_rate.max(1) as f32;
+            let alpha = 1.0 - (-block_ms / time_constant).exp();
+            self.agc_gain = previous + (desired - previous) * alpha;
+
+            debug!(
+                "AGC: level {:.4}, gain {:.2} -> {:.2}",
+                level, previous, self.agc_gain
+            );
+        }
+
+        // Ramp across the block to avoid audible steps
+        let step = (self.agc_gain - previous) / samples.len() as f32;
+        for (i, sample) in samples.iter_mut().enumerate() {
+            *sample *= previous + step * (i + 1) as f32;
+        }
+    }
+}
+
+/// Compress values above `threshold` so the output never reaches ±1.0
+fn soft_limit(sample: f32, threshold: f32) -> f32 {
+    let magnitude = sample.abs();
+    if magnitude <= threshold {
+        return sample;
+    }
+
+    let headroom = 1.0 - threshold;
+    if headroom <= 0.0 {
+        return sample.clamp(-1.0, 1.0);
+    }
+
+    let excess = (magnitude - threshold) / headroom;
+    let limited = threshold + headroom * excess / (1.0 + excess);
+    limited.copysign(sample)
+}
+
+/// RMS of f32 samples
+fn block_rms(samples: &[f32]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum: f32 = samples.iter().map(|s| s * s).sum();
+    (sum / samples.len() as f32).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BLOCK: usize = 160; // 10ms @ 16kHz
+
+    fn tone(amplitude: f32, len: usize) -> Vec<f32> {
+        (0..len)
+            .map(|i| amplitude * (2.0 * std::f32::consts::PI * 440.0 * i as f32 / 16000.0).sin())
+            .collect()
+    }
+
+    #[test]
+    fn test_passthrough() {
+        let mut stage = GainStage::new(GainConfig::default(), 16000);
+        let original = vec![0.5, -0.99, 1.5];
+        let mut samples = original.clone();
+
+        stage.process(&mut samples);
+
+        assert_eq!(samples, original);
+    }
+
+    #[test]
+    fn test_fixed_gain() {
+        let mut stage = GainStage::new(GainConfig::new(0.5), 16000);
+        let mut samples = vec![0.4, -0.8];
+
+        stage.process(&mut samples);
+
+        assert!((samples[0] - 0.2).abs() < 1e-6);
+        assert!((samples[1] + 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_negative_gain_is_clamped() {
+        assert_eq!(GainConfig::new(-1.0).gain, 0.0);
+    }
+
+    #[test]
+    fn test_limiter_prevents_clipping() {
+        let mut stage = GainStage::new(GainConfig::new(2.0), 16000);
+        let mut samples = tone(0.9, 1600);
+
+        stage.process(&mut samples);
+
+        let peak = samples.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!(peak < 1.0, "peak {} should stay below full scale", peak);
+        assert!(peak > DEFAULT_LIMITER_THRESHOLD);
+    }
+
+    #[test]
+    fn test_soft_limit_below_threshold_is_identity() {
+        assert_eq!(soft_limit(0.5, 0.9), 0.5);
+        assert_eq!(soft_limit(-0.9, 0.9), -0.9);
+        assert!(soft_limit(5.0, 0.9) < 1.0);
+        assert!(soft_limit(-5.0, 0.9) > -1.0);
+    }
+
+    #[test]
+    fn test_agc_raises_quiet_input() {
+        let mut stage = GainStage::new(GainConfig::default().with_agc(0.1), 16000);
+
+        // Quiet laptop mic: RMS ~0.014
+        let mut last = Vec::new();
+        for _ in 0..400 {
+            let mut block = tone(0.02, BLOCK);
+            stage.process(&mut block);
+            last = block;
+        }
+
+        let level = block_rms(&last);
+        assert!(stage.agc_gain() > 5.0);
+        assert!(
+            (level - 0.1).abs() < 0.02,
+            "level {} should approach target",
+            level
+        );
+    }
+
+    #[test]
+    fn test_agc_lowers_loud_input_quickly() {
+        let mut stage = GainStage::new(GainConfig::default().with_agc(0.1), 16000);
+
+        for _ in 0..50 {
+            let mut block = tone(0.8, BLOCK);
+            stage.process(&mut block);
+        }
+
+        // 500ms is ten attack time constants
+        assert!(stage.agc_gain() < 0.25);
+    }
+
+    #[test]
+    fn test_agc_ignores_silence() {
+        let mut stage = GainStage::new(GainConfig::default().with_agc(0.1), 16000);
+
+        for _ in 0..100 {
+            let mut block = tone(0.001, BLOCK);
+            stage.process(&mut block);
+        }
+
+        assert_eq!(stage.agc_gain(), 1.0);
+    }
+
+    #[test]
+    fn test_agc_respects_max_gain() {
+        let config = GainConfig::default().with_agc(0.5).with_max_gain(3.0);
+        let mut stage = GainStage::new(config, 16000);
+
+        for _ in 0..1000 {
+            let mut block = tone(0.01, BLOCK);
+            stage.process(&mut block);
+        }
+
+        assert!(stage.agc_gain() <= 3.0 + 1e-3);
+
+        stage.reset();
+        assert_eq!(stage.agc_gain(), 1.0);
+    }
+}
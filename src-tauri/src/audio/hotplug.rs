@@ -0,0 +1,819 @@
+use crate::audio::device::{list_input_devices, AudioDevice};
+use crate::audio::error::{AudioError, AudioResult};
+use crate::audio::{
+    AudioCapture, AudioResampler, AudioSource, ChannelMode, DeviceSelector, MatchKind,
+};
+use std::sync::mpsc::{self as std_mpsc, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::sync::oneshot;
+use tracing::{debug, error, info, warn};
+
+/// Default time without audio after which a capture device counts as lost
+pub const DEFAULT_STALL_TIMEOUT_MS: u64 = 2000;
+
+/// Default interval between device list checks
+pub const DEFAULT_DEVICE_POLL_MS: u64 = 2000;
+
+/// Errors and device changes of a running audio source
+#[derive(Debug)]
+pub enum DeviceEvent {
+    /// The stream reported an error; audio may or may not keep coming
+    Error(AudioError),
+
+    /// The device was lost and no replacement could be opened; the source
+    /// has ended
+    Lost(AudioError),
+
+    /// The device was lost and capture continues on `device`
+    Switched {
+        /// Why the previous device was given up
+        error: AudioError,
+        /// Name of the device now in use
+        device: String,
+    },
+}
+
+/// Devices to switch to when the capture device is lost
+///
+/// The listed devices (IDs or names, the same priority list used by
+/// [`DeviceSelector`]) are tried in order, then the system default input
+/// device if `use_default` is set. The default policy never switches.
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::FailoverPolicy;
+///
+/// // Prefer the built-in microphone, then whatever the system default is
+/// let policy = FailoverPolicy::to_default().with_devices(vec!["Built-in Microphone".to_string()]);
+/// assert!(policy.is_enabled());
+/// assert!(!FailoverPolicy::default().is_enabled());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct FailoverPolicy {
+    /// Devices (IDs or names) to try first, in order of preference
+    pub devices: Vec<String>,
+
+    /// Whether to fall back to the system default input device
+    pub use_default: bool,
+}
+
+impl FailoverPolicy {
+    /// Fail over to the system default input device
+    pub fn to_default() -> Self {
+        Self {
+            devices: Vec::new(),
+            use_default: true,
+        }
+    }
+
+    /// Try `devices` in order before the default device
+    pub fn with_devices(mut self, devices: Vec<String>) -> Self {
+        self.devices = devices;
+        self
+    }
+
+    /// Whether any replacement device is configured
+    pub fn is_enabled(&self) -> bool {
+        self.use_default || !self.devices.is_empty()
+    }
+
+    /// Devices to try after losing `current`, None standing for the default
+    ///
+    /// A lost named device is skipped; the default device is resolved again
+    /// and may now be a different one.
+    fn candidates(&self, current: Option<&str>) -> Vec<Option<String>> {
+        let mut candidates: Vec<Option<String>> = self
+            .devices
+            .iter()
+            .filter(|id| Some(id.as_str()) != current)
+            .map(|id| Some(id.clone()))
+            .collect();
+        if self.use_default {
+            candidates.push(None);
+        }
+        candidates
+    }
+}
+
+/// ID of the device a failover entry names, if it is given by name rather than ID
+fn resolve_by_name(entry: &str) -> Option<String> {
+    match DeviceSelector::new(Some(entry)).resolve() {
+        Ok(Some(found)) if found.kind == MatchKind::Name => Some(found.device.id),
+        _ => None,
+    }
+}
+
+/// Opens a capture device by ID (None for the default device)
+type Opener =
+    Arc<dyn Fn(Option<&str>, ChannelMode) -> AudioResult<Box<dyn AudioSource>> + Send + Sync>;
+
+/// Microphone capture that notices when its device goes away
+///
+/// Wraps an [`AudioCapture`] running on its own thread. The device counts
+/// as lost when its stream reports that it was disconnected, or when no
+/// audio arrives for the stall timeout (some backends stop calling back
+/// without reporting an error). A lost device is then replaced according to
+/// the [`FailoverPolicy`]: audio from the new device is resampled to the
+/// original sample rate, so the pipeline downstream is unaffected, and
+/// [`DeviceEvent::Switched`] is sent. Without a replacement
+/// [`DeviceEvent::Lost`] is sent and the stream ends, like a finite source
+/// running out.
+///
+/// # Example
+/// ```no_run
+/// use raflow_lib::audio::{AudioPipeline, FailoverPolicy, SupervisedCapture};
+///
+/// let capture = SupervisedCapture::new(Some("USB Headset"))
+///     .unwrap()
+///     .with_failover(FailoverPolicy::to_default());
+/// let pipeline = AudioPipeline::from_source(Box::new(capture));
+/// ```
+pub struct SupervisedCapture {
+    /// Device to open on start (None for the default device)
+    device_id: Option<String>,
+    /// Name of the device currently in use
+    device_name: Arc<Mutex<String>>,
+    /// How the device channels are reduced to mono
+    channel_mode: ChannelMode,
+    /// Replacement devices
+    failover: FailoverPolicy,
+    /// Time without audio after which the device counts as lost
+    stall_timeout: Duration,
+    /// Sample rate of the delivered audio (that of the first device)
+    sample_rate: u32,
+    /// Opens devices
+    opener: Opener,
+    /// Channel for device errors and changes
+    device_events: Option<mpsc::Sender<DeviceEvent>>,
+    /// Stop signal for the capture thread
+    stop_signal: Option<oneshot::Sender<()>>,
+    /// Capture thread (None when stopped)
+    thread: Option<JoinHandle<()>>,
+}
+
+impl SupervisedCapture {
+    /// Prepare capture from a device (None for the default input device)
+    ///
+    /// The device is opened to learn its sample rate; capture starts with
+    /// [`start`](AudioSource::start).
+    ///
+    /// # Errors
+    /// Returns `AudioError::DeviceNotFound` if the device is not available.
+    pub fn new(device_id: Option<&str>) -> AudioResult<Self> {
+        Self::with_opener(
+            device_id,
+            Arc::new(|device_id, channel_mode| {
+                let resolved = device_id.and_then(resolve_by_name);
+                let capture = AudioCapture::new(resolved.as_deref().or(device_id))?
+                    .with_channel_mode(channel_mode);
+                Ok(Box::new(capture) as Box<dyn AudioSource>)
+            }),
+        )
+    }
+
+    /// Prepare capture from devices opened by `opener`
+    fn with_opener(device_id: Option<&str>, opener: Opener) -> AudioResult<Self> {
+        let probe = opener(device_id, ChannelMode::Downmix)?;
+        let sample_rate = probe.sample_rate();
+        let device_name = probe
+            .device_name()
+            .unwrap_or_else(|| describe_device(device_id));
+
+        Ok(Self {
+            device_id: device_id.map(str::to_string),
+            device_name: Arc::new(Mutex::new(device_name)),
+            channel_mode: ChannelMode::Downmix,
+            failover: FailoverPolicy::default(),
+            stall_timeout: Duration::from_millis(DEFAULT_STALL_TIMEOUT_MS),
+            sample_rate,
+            opener,
+            device_events: None,
+            stop_signal: None,
+            thread: None,
+        })
+    }
+
+    /// Set the devices to switch to when the device is lost
+    pub fn with_failover(mut self, policy: FailoverPolicy) -> Self {
+        self.failover = policy;
+        self
+    }
+
+    /// Set how multi-channel devices are reduced to mono
+    pub fn with_channel_mode(mut self, mode: ChannelMode) -> Self {
+        self.channel_mode = mode;
+        self
+    }
+
+    /// Set how long the device may deliver no audio before it counts as lost
+    ///
+    /// Defaults to [`DEFAULT_STALL_TIMEOUT_MS`].
+    pub fn with_stall_timeout(mut self, timeout: Duration) -> Self {
+        self.stall_timeout = timeout;
+        self
+    }
+}
+
+impl AudioSource for SupervisedCapture {
+    fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+        if self
+            .thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+        {
+            warn!("Supervised capture already started");
+            return Ok(());
+        }
+        // Clean up after a device that was lost
+        self.stop();
+
+        let supervisor = Supervisor {
+            device_id: self.device_id.clone(),
+            device_name: Arc::clone(&self.device_name),
+            channel_mode: self.channel_mode,
+            failover: self.failover.clone(),
+            stall_timeout: self.stall_timeout,
+            sample_rate: self.sample_rate,
+            opener: Arc::clone(&self.opener),
+            events: self.device_events.clone(),
+            output: sender,
+        };
+
+        let (ready_tx, ready_rx) = std_mpsc::sync_channel(1);
+        let (stop_tx, stop_rx) = oneshot::channel();
+
+        // cpal streams cannot move between threads, so every device is
+        // opened, watched and closed on this one
+        let thread = thread::Builder::new()
+            .name("raflow-capture".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread()
+                    .enable_time()
+                    .build()
+                {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(AudioError::StreamBuildFailed(e.to_string())));
+                        return;
+                    }
+                };
+                runtime.block_on(supervisor.run(ready_tx, stop_rx));
+            })
+            .map_err(|e| AudioError::StreamBuildFailed(e.to_string()))?;
+
+        let started = ready_rx.recv().unwrap_or_else(|_| {
+            Err(AudioError::StreamBuildFailed(
+                "Capture thread exited".to_string(),
+            ))
+        });
+        if let Err(e) = started {
+            let _ = thread.join();
+            return Err(e);
+        }
+
+        self.stop_signal = Some(stop_tx);
+        self.thread = Some(thread);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(stop) = self.stop_signal.take() {
+            let _ = stop.send(());
+        }
+
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            warn!("Capture thread panicked");
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn is_active(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    fn set_channel_mode(&mut self, mode: ChannelMode) {
+        self.channel_mode = mode;
+    }
+
+    fn set_device_events(&mut self, events: mpsc::Sender<DeviceEvent>) {
+        self.device_events = Some(events);
+    }
+
+    fn device_name(&self) -> Option<String> {
+        self.device_name.lock().ok().map(|name| name.clone())
+    }
+}
+
+impl Drop for SupervisedCapture {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// State of the capture thread
+struct Supervisor {
+    device_id: Option<String>,
+    device_name: Arc<Mutex<String>>,
+    channel_mode: ChannelMode,
+    failover: FailoverPolicy,
+    stall_timeout: Duration,
+    sample_rate: u32,
+    opener: Opener,
+    events: Option<mpsc::Sender<DeviceEvent>>,
+    output: mpsc::Sender<Vec<f32>>,
+}
+
+/// A started device and its channels
+struct OpenDevice {
+    source: Box<dyn AudioSource>,
+    name: String,
+    audio: mpsc::Receiver<Vec<f32>>,
+    events: mpsc::Receiver<DeviceEvent>,
+    /// Converts to the original sample rate after a switch
+    resampler: Option<(AudioResampler, Vec<f32>)>,
+}
+
+impl Supervisor {
+    /// Capture until stopped, the output closes or the device is lost for good
+    async fn run(
+        mut self,
+        ready: std_mpsc::SyncSender<AudioResult<()>>,
+        mut stop_rx: oneshot::Receiver<()>,
+    ) {
+        let mut device = match self.open(self.device_id.clone()) {
+            Ok(device) => {
+                let _ = ready.send(Ok(()));
+                device
+            }
+            Err(e) => {
+                let _ = ready.send(Err(e));
+                return;
+            }
+        };
+
+        info!("Supervised capture started on {}", device.name);
+
+        let mut last_audio = Instant::now();
+        let mut last_error = None;
+        let mut check =
+            tokio::time::interval((self.stall_timeout / 4).max(Duration::from_millis(10)));
+        check.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            let error = tokio::select! {
+                _ = &mut stop_rx => break,
+                block = device.audio.recv() => match block {
+                    Some(block) => {
+                        last_audio = Instant::now();
+                        if !self.forward(&mut device, block) {
+                            debug!("Capture output closed");
+                            break;
+                        }
+                        continue;
+                    }
+                    // The stream only ends when the device goes away
+                    None => last_error
+                        .take()
+                        .unwrap_or_else(|| AudioError::DeviceDisconnected(device.name.clone())),
+                },
+                Some(event) = device.events.recv() => match event {
+                    DeviceEvent::Error(error) if !error.is_device_lost() => {
+                        warn!("Capture error on {}: {}", device.name, error);
+                        last_error = Some(error);
+                        continue;
+                    }
+                    DeviceEvent::Error(error) | DeviceEvent::Lost(error) => error,
+                    DeviceEvent::Switched { .. } => continue,
+                },
+                _ = check.tick() => {
+                    let silent = last_audio.elapsed();
+                    if silent < self.stall_timeout {
+                        continue;
+                    }
+                    AudioError::DeviceStalled {
+                        device: device.name.clone(),
+                        silent_ms: silent.as_millis() as u64,
+                    }
+                }
+            };
+
+            warn!("Capture device {} lost: {}", device.name, error);
+            device.source.stop();
+
+            match self.fail_over() {
+                Some(next) => {
+                    info!("Capture switched to {}", next.name);
+                    self.notify(DeviceEvent::Switched {
+                        error,
+                        device: next.name.clone(),
+                    });
+                    device = next;
+                    last_audio = Instant::now();
+                    last_error = None;
+                }
+                None => {
+                    error!("No input device to switch to, capture ended");
+                    self.notify(DeviceEvent::Lost(error));
+                    break;
+                }
+            }
+        }
+
+        device.source.stop();
+        debug!("Supervised capture stopped");
+    }
+
+    /// Open and start a device
+    fn open(&self, device_id: Option<String>) -> AudioResult<OpenDevice> {
+        let mut source = (self.opener)(device_id.as_deref(), self.channel_mode)?;
+
+        let (events_tx, events) = mpsc::channel(8);
+        source.set_device_events(events_tx);
+        let (audio_tx, audio) = mpsc::channel(100);
+        source.start(audio_tx)?;
+
+        let resampler = if source.sample_rate() != self.sample_rate {
+            debug!(
+                "Resampling replacement device: {} Hz -> {} Hz",
+                source.sample_rate(),
+                self.sample_rate
+            );
+            Some((
+                AudioResampler::new(source.sample_rate(), self.sample_rate)?,
+                Vec::new(),
+            ))
+        } else {
+            None
+        };
+
+        let name = source
+            .device_name()
+            .unwrap_or_else(|| describe_device(device_id.as_deref()));
+        if let Ok(mut device_name) = self.device_name.lock() {
+            *device_name = name.clone();
+        }
+
+        Ok(OpenDevice {
+            source,
+            name,
+            audio,
+            events,
+            resampler,
+        })
+    }
+
+    /// Open the first available replacement device
+    fn fail_over(&mut self) -> Option<OpenDevice> {
+        for candidate in self.failover.candidates(self.device_id.as_deref()) {
+            match self.open(candidate.clone()) {
+                Ok(device) => {
+                    self.device_id = candidate;
+                    return Some(device);
+                }
+                Err(e) => debug!(
+                    "Cannot switch to {}: {}",
+                    describe_device(candidate.as_deref()),
+                    e
+                ),
+            }
+        }
+        None
+    }
+
+    /// Pass a block on; returns false once the output is closed
+    fn forward(&self, device: &mut OpenDevice, block: Vec<f32>) -> bool {
+        let block = match device.resampler.as_mut() {
+            Some((resampler, buffer)) => match resampler.process_buffered(&block, buffer) {
+                Ok(resampled) => resampled,
+                Err(e) => {
+                    error!("Resampling error: {}", e);
+                    return true;
+                }
+            },
+            None => block,
+        };
+        if block.is_empty() {
+            return true;
+        }
+
+        // Same policy as the capture callback: drop rather than block
+        !matches!(self.output.try_send(block), Err(TrySendError::Closed(_)))
+    }
+
+    fn notify(&self, event: DeviceEvent) {
+        if let Some(events) = &self.events
+            && events.try_send(event).is_err()
+        {
+            warn!("Dropping device event: channel full or closed");
+        }
+    }
+}
+
+/// Human-readable name of a device ID
+fn describe_device(device_id: Option<&str>) -> String {
+    device_id.unwrap_or("default input device").to_string()
+}
+
+/// Watches the list of input devices
+///
+/// Polls the device list on a background thread and calls back with the
+/// new list whenever a device is added or removed or the default changes.
+/// Stops when dropped.
+///
+/// # Example
+/// ```no_run
+/// use raflow_lib::audio::DeviceMonitor;
+/// use std::time::Duration;
+///
+/// let monitor = DeviceMonitor::start(Duration::from_secs(2), |devices| {
+///     println!("{} input devices", devices.len());
+/// })
+/// .unwrap();
+/// ```
+pub struct DeviceMonitor {
+    /// Dropped to stop the thread
+    stop_signal: Option<std_mpsc::Sender<()>>,
+    /// Polling thread
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DeviceMonitor {
+    /// Check the device list every `interval`
+    ///
+    /// # Errors
+    /// Returns error if the thread cannot be spawned.
+    pub fn start<F>(interval: Duration, on_change: F) -> AudioResult<Self>
+    where
+        F: Fn(Vec<AudioDevice>) + Send + 'static,
+    {
+        Self::with_lister(interval, list_input_devices, on_change)
+    }
+
+    /// Check the list returned by `list` every `interval`
+    fn with_lister<L, F>(interval: Duration, list: L, on_change: F) -> AudioResult<Self>
+    where
+        L: Fn() -> AudioResult<Vec<AudioDevice>> + Send + 'static,
+        F: Fn(Vec<AudioDevice>) + Send + 'static,
+    {
+        // No devices at all is a valid state, not an error
+        let list = move || match list() {
+            Ok(devices) => Some(devices),
+            Err(AudioError::DeviceNotFound) => Some(Vec::new()),
+            Err(e) => {
+                debug!("Cannot list input devices: {}", e);
+                None
+            }
+        };
+
+        let (stop_tx, stop_rx) = std_mpsc::channel::<()>();
+        let thread = thread::Builder::new()
+            .name("raflow-device-monitor".to_string())
+            .spawn(move || {
+                let mut known = list().unwrap_or_default();
+
+                while let Err(RecvTimeoutError::Timeout) = stop_rx.recv_timeout(interval) {
+                    let Some(devices) = list() else {
+                        continue;
+                    };
+                    if devices != known {
+                        info!("Input devices changed: {} available", devices.len());
+                        known = devices.clone();
+                        on_change(devices);
+                    }
+                }
+                debug!("Device monitor stopped");
+            })
+            .map_err(|e| AudioError::StreamBuildFailed(e.to_string()))?;
+
+        Ok(Self {
+            stop_signal: Some(stop_tx),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for DeviceMonitor {
+    fn drop(&mut self) {
+        drop(self.stop_signal.take());
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            warn!("Device monitor thread panicked");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{Pacing, Signal, SyntheticSource};
+
+    /// Device that starts but never delivers audio, optionally reporting
+    /// that it was unplugged
+    struct DeadDevice {
+        unplug: bool,
+        events: Option<mpsc::Sender<DeviceEvent>>,
+        sender: Option<mpsc::Sender<Vec<f32>>>,
+    }
+
+    impl AudioSource for DeadDevice {
+        fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+            if self.unplug
+                && let Some(events) = &self.events
+            {
+                let error = AudioError::DeviceDisconnected("USB Headset".to_string());
+                events.try_send(DeviceEvent::Error(error)).unwrap();
+            }
+            self.sender = Some(sender);
+            Ok(())
+        }
+
+        fn stop(&mut self) {
+            self.sender = None;
+        }
+
+        fn sample_rate(&self) -> u32 {
+            48000
+        }
+
+        fn is_active(&self) -> bool {
+            self.sender.is_some()
+        }
+
+        fn set_device_events(&mut self, events: mpsc::Sender<DeviceEvent>) {
+            self.events = Some(events);
+        }
+
+        fn device_name(&self) -> Option<String> {
+            Some("USB Headset".to_string())
+        }
+    }
+
+    fn dead_device(unplug: bool) -> Opener {
+        Arc::new(move |device_id, _| match device_id {
+            Some("usb") => Ok(Box::new(DeadDevice {
+                unplug,
+                events: None,
+                sender: None,
+            }) as Box<dyn AudioSource>),
+            // The default device: 16kHz, in real time
+            None => Ok(Box::new(
+                SyntheticSource::new(16000).with_segment(Signal::Silence, Duration::from_secs(5)),
+            )),
+            Some(_) => Err(AudioError::DeviceNotFound),
+        })
+    }
+
+    #[test]
+    fn test_failover_candidates() {
+        let policy =
+            FailoverPolicy::to_default().with_devices(vec!["a".to_string(), "b".to_string()]);
+
+        assert_eq!(
+            policy.candidates(Some("a")),
+            vec![Some("b".to_string()), None]
+        );
+        assert_eq!(
+            policy.candidates(None),
+            vec![Some("a".to_string()), Some("b".to_string()), None]
+        );
+        assert!(FailoverPolicy::default().candidates(None).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unplugged_device_is_lost() {
+        let mut capture = SupervisedCapture::with_opener(Some("usb"), dead_device(true)).unwrap();
+        let (events_tx, mut events) = mpsc::channel(4);
+        capture.set_device_events(events_tx);
+
+        let (tx, mut rx) = mpsc::channel(100);
+        capture.start(tx).unwrap();
+
+        // Without failover the stream ends
+        assert!(rx.recv().await.is_none());
+        match events.recv().await.unwrap() {
+            DeviceEvent::Lost(AudioError::DeviceDisconnected(name)) => {
+                assert_eq!(name, "USB Headset")
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stalled_device_is_lost() {
+        let mut capture = SupervisedCapture::with_opener(Some("usb"), dead_device(false))
+            .unwrap()
+            .with_stall_timeout(Duration::from_millis(100));
+        let (events_tx, mut events) = mpsc::channel(4);
+        capture.set_device_events(events_tx);
+
+        let (tx, mut rx) = mpsc::channel(100);
+        capture.start(tx).unwrap();
+
+        assert!(rx.recv().await.is_none());
+        assert!(matches!(
+            events.recv().await.unwrap(),
+            DeviceEvent::Lost(AudioError::DeviceStalled { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_failover_to_default_device() {
+        let policy = FailoverPolicy::to_default().with_devices(vec!["missing".to_string()]);
+        let mut capture = SupervisedCapture::with_opener(Some("usb"), dead_device(true))
+            .unwrap()
+            .with_failover(policy);
+        let (events_tx, mut events) = mpsc::channel(4);
+        capture.set_device_events(events_tx);
+        assert_eq!(capture.sample_rate(), 48000);
+
+        let (tx, mut rx) = mpsc::channel(100);
+        capture.start(tx).unwrap();
+
+        match events.recv().await.unwrap() {
+            DeviceEvent::Switched { error, device } => {
+                assert!(error.is_device_lost());
+                assert_eq!(device, "default input device");
+            }
+            other => panic!("unexpected event: {:?}", other),
+        }
+        assert_eq!(
+            capture.device_name(),
+            Some("default input device".to_string())
+        );
+
+        // 16kHz audio from the default device still arrives at 48kHz
+        let mut samples = 0;
+        while samples < 4800 {
+            samples += rx.recv().await.unwrap().len();
+        }
+        assert!(capture.is_active());
+
+        capture.stop();
+        assert!(!capture.is_active());
+    }
+
+    #[test]
+    fn test_supervised_capture_forwards_audio() {
+        let opener: Opener = Arc::new(|_, _| {
+            Ok(Box::new(
+                SyntheticSource::new(16000)
+                    .with_segment(Signal::Silence, Duration::from_millis(100))
+                    .with_pacing(Pacing::Fast),
+            ) as Box<dyn AudioSource>)
+        });
+        let mut capture = SupervisedCapture::with_opener(None, opener).unwrap();
+
+        let (tx, mut rx) = mpsc::channel(100);
+        capture.start(tx).unwrap();
+
+        // All audio arrives before the finite source counts as unplugged
+        let mut samples = 0;
+        while let Some(block) = rx.blocking_recv() {
+            samples += block.len();
+        }
+        assert_eq!(samples, 1600);
+    }
+
+    #[test]
+    fn test_device_monitor_reports_changes() {
+        let device = |id: &str| AudioDevice {
+            id: id.to_string(),
+            name: id.to_string(),
+            is_default: false,
+            sample_rates: vec![48000],
+        };
+        let devices = Arc::new(Mutex::new(vec![device("Built-in")]));
+        let listed = Arc::clone(&devices);
+        let (tx, rx) = std_mpsc::channel();
+
+        let monitor = DeviceMonitor::with_lister(
+            Duration::from_millis(10),
+            move || Ok(listed.lock().unwrap().clone()),
+            move |devices| tx.send(devices).unwrap(),
+        )
+        .unwrap();
+
+        // No report while nothing changes
+        assert!(rx.recv_timeout(Duration::from_millis(50)).is_err());
+
+        devices.lock().unwrap().push(device("USB Headset"));
+        let reported = rx.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(reported.len(), 2);
+
+        devices.lock().unwrap().clear();
+        assert!(rx.recv_timeout(Duration::from_secs(1)).unwrap().is_empty());
+
+        drop(monitor);
+    }
+}
@@ -0,0 +1,206 @@
+use serde::Serialize;
+use std::time::{Duration, Instant};
+
+/// Default interval between level updates (about 30 Hz)
+pub const DEFAULT_LEVEL_INTERVAL_MS: u64 = 33;
+
+/// Sample magnitude treated as clipped (just below full scale)
+pub const CLIPPING_THRESHOLD: f32 = 0.999;
+
+/// Level shown as silent on the meter, in dBFS
+const METER_FLOOR_DB: f32 = -60.0;
+
+/// Input level over a short window of audio
+///
+/// Serialized as the payload of the `audio:level` event.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+pub struct AudioLevel {
+    /// RMS amplitude, 0.0 - 1.0
+    pub rms: f32,
+    /// Peak absolute amplitude, 0.0 - 1.0
+    pub peak: f32,
+    /// RMS mapped from -60..0 dBFS to 0.0 - 1.0, for drawing a meter
+    pub level: f32,
+    /// Whether any sample reached full scale
+    pub clipping: bool,
+}
+
+impl AudioLevel {
+    /// Measure a block of f32 samples (range: -1.0 to 1.0)
+    pub fn measure(samples: &[f32]) -> Self {
+        let mut stats = LevelStats::default();
+        stats.add(samples);
+        stats.level()
+    }
+
+    /// RMS level in dBFS (-inf for digital silence)
+    pub fn rms_db(&self) -> f32 {
+        20.0 * self.rms.log10()
+    }
+}
+
+/// Running sums for one metering window
+#[derive(Debug, Default)]
+struct LevelStats {
+    sum_squares: f64,
+    count: usize,
+    peak: f32,
+    clipping: bool,
+}
+
+impl LevelStats {
+    fn add(&mut self, samples: &[f32]) {
+        for &sample in samples {
+            let magnitude = sample.abs();
+            self.sum_squares += (sample as f64) * (sample as f64);
+            self.peak = self.peak.max(magnitude);
+            self.clipping |= magnitude >= CLIPPING_THRESHOLD;
+        }
+        self.count += samples.len();
+    }
+
+    fn level(&self) -> AudioLevel {
+        if self.count == 0 {
+            return AudioLevel::default();
+        }
+
+        let rms = ((self.sum_squares / self.count as f64).sqrt() as f32).min(1.0);
+        let db = 20.0 * rms.max(f32::MIN_POSITIVE).log10();
+
+        AudioLevel {
+            rms,
+            peak: self.peak.min(1.0),
+            level: (1.0 - db / METER_FLOOR_DB).clamp(0.0, 1.0),
+            clipping: self.clipping,
+        }
+    }
+}
+
+/// Throttled level meter
+///
+/// Accumulates every block it is given and reports the combined level at
+/// most once per interval, so a UI meter is not flooded by the ~100 blocks
+/// per second a microphone delivers. A clipped sample anywhere in the window
+/// sets the clipping flag of the report.
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::LevelMeter;
+/// use std::time::Duration;
+///
+/// let mut meter = LevelMeter::new(Duration::ZERO);
+/// let level = meter.process(&[0.5, -0.5]).unwrap();
+///
+/// assert_eq!(level.peak, 0.5);
+/// assert!(!level.clipping);
+/// ```
+#[derive(Debug)]
+pub struct LevelMeter {
+    interval: Duration,
+    stats: LevelStats,
+    last_report: Option<Instant>,
+}
+
+impl LevelMeter {
+    /// Create a meter reporting at most once per `interval`
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            stats: LevelStats::default(),
+            last_report: None,
+        }
+    }
+
+    /// Add a block of samples
+    ///
+    /// Returns the level of everything added since the last report once the
+    /// interval has passed, otherwise None. The first block is reported
+    /// immediately.
+    pub fn process(&mut self, samples: &[f32]) -> Option<AudioLevel> {
+        self.stats.add(samples);
+
+        let now = Instant::now();
+        if let Some(last) = self.last_report
+            && now.duration_since(last) < self.interval
+        {
+            return None;
+        }
+
+        let level = self.stats.level();
+        self.stats = LevelStats::default();
+        self.last_report = Some(now);
+        Some(level)
+    }
+}
+
+impl Default for LevelMeter {
+    fn default() -> Self {
+        Self::new(Duration::from_millis(DEFAULT_LEVEL_INTERVAL_MS))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_measure_silence() {
+        let level = AudioLevel::measure(&[0.0; 160]);
+
+        assert_eq!(level.rms, 0.0);
+        assert_eq!(level.peak, 0.0);
+        assert_eq!(level.level, 0.0);
+        assert!(!level.clipping);
+        assert_eq!(AudioLevel::measure(&[]), AudioLevel::default());
+    }
+
+    #[test]
+    fn test_measure_square_wave() {
+        let samples: Vec<f32> = (0..160)
+            .map(|i| if i % 2 == 0 { 0.5 } else { -0.5 })
+            .collect();
+        let level = AudioLevel::measure(&samples);
+
+        assert!((level.rms - 0.5).abs() < 1e-6);
+        assert_eq!(level.peak, 0.5);
+        assert!((level.rms_db() + 6.02).abs() < 0.01);
+        // -6 dBFS is 90% of the way up a 60 dB meter
+        assert!((level.level - 0.9).abs() < 0.01);
+        assert!(!level.clipping);
+    }
+
+    #[test]
+    fn test_measure_clipping() {
+        let level = AudioLevel::measure(&[0.1, 1.0, -0.2]);
+        assert!(level.clipping);
+        assert_eq!(level.peak, 1.0);
+
+        let level = AudioLevel::measure(&[0.1, -1.0]);
+        assert!(level.clipping);
+    }
+
+    #[test]
+    fn test_meter_throttles_reports() {
+        let mut meter = LevelMeter::new(Duration::from_secs(60));
+
+        // The first block is reported at once, later ones wait for the interval
+        assert!(meter.process(&[0.1; 10]).is_some());
+        assert!(meter.process(&[1.0; 10]).is_none());
+        assert!(meter.process(&[0.0; 10]).is_none());
+    }
+
+    #[test]
+    fn test_meter_accumulates_window() {
+        let mut meter = LevelMeter::new(Duration::from_millis(20));
+        meter.process(&[0.0; 10]);
+
+        // A clipped block in the middle of the window is not lost
+        assert!(meter.process(&[1.0; 10]).is_none());
+        std::thread::sleep(Duration::from_millis(30));
+        let level = meter.process(&[0.0; 10]).unwrap();
+
+        assert!(level.clipping);
+        assert_eq!(level.peak, 1.0);
+        assert!((level.rms - 0.5f32.sqrt()).abs() < 1e-6);
+    }
+}
@@ -16,10 +16,56 @@ pub mod pipeline;
 /// High-performance audio buffer (ring buffer)
 pub mod buffer;
 
+/// Voice activity detection
+pub mod vad;
+
+/// Gain, automatic gain control and limiter
+pub mod gain;
+
+/// Spectral subtraction noise suppression
+pub mod denoise;
+
+/// Multi-channel to mono conversion
+pub mod channels;
+
+/// Audio sources: microphone, WAV/PCM files and generated signals
+pub mod source;
+
+/// Microphone kept open between sessions with a pre-roll buffer
+pub mod warm;
+
+/// Input level metering (RMS, peak, clipping)
+pub mod level;
+
+/// WAV recording of processed audio
+pub mod recorder;
+
+/// Device loss detection, failover and device list monitoring
+pub mod hotplug;
+
+/// Input device matching by ID, name and preferred devices
+pub mod selection;
+
 // Re-export commonly used types
 pub use buffer::{AudioBufferConsumer, AudioBufferProducer, AudioRingBuffer, BufferPool, PcmBuffer};
 pub use capture::AudioCapture;
+pub use channels::ChannelMode;
+pub use denoise::NoiseSuppressor;
 pub use device::{get_default_input_device, get_device_config, list_input_devices, AudioDevice};
 pub use error::{AudioError, AudioResult};
+pub use gain::{GainConfig, GainStage, DEFAULT_AGC_TARGET_RMS};
+pub use hotplug::{
+    DeviceEvent, DeviceMonitor, FailoverPolicy, SupervisedCapture, DEFAULT_DEVICE_POLL_MS,
+    DEFAULT_STALL_TIMEOUT_MS,
+};
+pub use level::{AudioLevel, LevelMeter, DEFAULT_LEVEL_INTERVAL_MS};
 pub use pipeline::AudioPipeline;
+pub use recorder::WavWriter;
 pub use resampler::AudioResampler;
+pub use selection::{DeviceMatch, DeviceSelector, MatchKind, FUZZY_MATCH_THRESHOLD};
+pub use source::{AudioSource, FileSource, Pacing, PcmFormat, Signal, SyntheticSource};
+pub use warm::{PreRollSource, WarmMicrophone, DEFAULT_WARM_PRE_ROLL_MS};
+pub use vad::{
+    rms_level, VadConfig, VadEvent, VadOutput, VoiceActivityDetector, DEFAULT_VAD_HANGOVER_MS,
+    DEFAULT_VAD_PRE_ROLL_MS,
+};
@@ -1,5 +1,8 @@
 use crate::audio::error::{AudioError, AudioResult};
-use crate::audio::{AudioCapture, AudioResampler};
+use crate::audio::{
+    AudioCapture, AudioLevel, AudioResampler, AudioSource, ChannelMode, DeviceEvent, GainConfig,
+    GainStage, LevelMeter, NoiseSuppressor, VadConfig, VadEvent, VoiceActivityDetector, WavWriter,
+};
 use base64::{engine::general_purpose::STANDARD, Engine};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
@@ -8,12 +11,16 @@ use tracing::{debug, error, info, warn};
 /// Audio processing pipeline that integrates capture, resampling, and encoding
 ///
 /// This pipeline:
-/// 1. Captures audio from microphone (via AudioCapture)
-/// 2. Resamples to 16kHz (via AudioResampler)
-/// 3. Converts f32 samples to i16 PCM
-/// 4. Accumulates audio chunks (100ms batches)
-/// 5. Base64 encodes the PCM data
-/// 6. Sends encoded data through output channel
+/// 1. Captures audio as mono from an [`AudioSource`] (the microphone by default)
+///    and meters its input level (optional, via [`LevelMeter`])
+/// 2. Suppresses background noise (optional, via [`NoiseSuppressor`])
+/// 3. Resamples to 16kHz (via AudioResampler)
+/// 4. Applies gain, optional AGC and a limiter (via [`GainStage`])
+/// 5. Converts f32 samples to i16 PCM (and records it, optionally, via [`WavWriter`])
+/// 6. Accumulates audio chunks (100ms batches)
+/// 7. Drops or thins out silent chunks (optional, via [`VoiceActivityDetector`])
+/// 8. Base64 encodes the PCM data
+/// 9. Sends encoded data through output channel
 ///
 /// # Example
 /// ```no_run
@@ -33,15 +40,31 @@ use tracing::{debug, error, info, warn};
 ///     }
 /// }
 /// ```
+///
+/// When a finite source (such as a [`FileSource`](crate::audio::FileSource))
+/// runs out of audio, the remaining samples are flushed and the output
+/// channel is closed.
 pub struct AudioPipeline {
-    /// Audio capture instance
-    capture: AudioCapture,
+    /// Audio source (microphone, file or generator)
+    source: Box<dyn AudioSource>,
     /// Processing task handle
     processing_task: Option<JoinHandle<()>>,
     /// Stop signal sender
     stop_signal: Option<tokio::sync::oneshot::Sender<()>>,
     /// Whether the pipeline is currently running
     is_running: bool,
+    /// Whether noise suppression is applied before resampling
+    noise_suppression: bool,
+    /// Gain settings
+    gain: GainConfig,
+    /// Voice activity detection settings
+    vad: VadConfig,
+    /// Channel for speech start/end and silence timeout events
+    vad_events: Option<mpsc::Sender<VadEvent>>,
+    /// Channel for throttled input level reports
+    level_events: Option<mpsc::Sender<AudioLevel>>,
+    /// Recording of the 16kHz PCM stream
+    recorder: Option<WavWriter>,
 }
 
 impl AudioPipeline {
@@ -60,27 +83,126 @@ impl AudioPipeline {
 
         // Create audio capture
         let capture = AudioCapture::new(device_id)?;
-        let input_rate = capture.sample_rate();
+        info!("Audio capture created: {} Hz", capture.sample_rate());
 
-        info!("Audio capture created: {} Hz", input_rate);
+        Ok(Self::from_source(Box::new(capture)))
+    }
 
-        Ok(Self {
-            capture,
+    /// Create a pipeline that processes audio from `source`
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raflow_lib::audio::{AudioPipeline, FileSource, Pacing};
+    ///
+    /// let source = FileSource::open_wav("recording.wav")
+    ///     .unwrap()
+    ///     .with_pacing(Pacing::Fast);
+    /// let pipeline = AudioPipeline::from_source(Box::new(source));
+    /// ```
+    pub fn from_source(source: Box<dyn AudioSource>) -> Self {
+        Self {
+            source,
             processing_task: None,
             stop_signal: None,
             is_running: false,
-        })
+            noise_suppression: false,
+            gain: GainConfig::default(),
+            vad: VadConfig::disabled(),
+            vad_events: None,
+            level_events: None,
+            recorder: None,
+        }
+    }
+
+    /// Set how multi-channel devices are reduced to mono
+    ///
+    /// Defaults to averaging all channels. Takes effect from the next
+    /// [`start`](Self::start).
+    pub fn with_channel_mode(mut self, mode: ChannelMode) -> Self {
+        self.source.set_channel_mode(mode);
+        self
+    }
+
+    /// Enable or disable noise suppression
+    ///
+    /// Disabled by default. Takes effect from the next [`start`](Self::start).
+    pub fn with_noise_suppression(mut self, enabled: bool) -> Self {
+        self.noise_suppression = enabled;
+        self
+    }
+
+    /// Set the gain settings
+    ///
+    /// The default is unity gain without AGC. Settings apply from the next
+    /// [`start`](Self::start).
+    pub fn with_gain(mut self, config: GainConfig) -> Self {
+        self.gain = config;
+        self
+    }
+
+    /// Set the voice activity detection settings
+    ///
+    /// VAD is disabled by default, so every chunk is streamed.
+    pub fn with_vad(mut self, config: VadConfig) -> Self {
+        self.vad = config;
+        self
+    }
+
+    /// Set the channel that receives VAD events
+    ///
+    /// Events are dropped (with a warning) if the channel is full. The sender
+    /// is handed to the next run and dropped when it ends, so the receiver
+    /// sees the channel close once the pipeline has stopped.
+    pub fn with_vad_events(mut self, events: mpsc::Sender<VadEvent>) -> Self {
+        self.vad_events = Some(events);
+        self
+    }
+
+    /// Set the channel that receives input level reports
+    ///
+    /// Levels are measured on the raw source audio, before noise suppression
+    /// and gain, so they show what the microphone hears and whether the input
+    /// itself clips. Reports come at most every
+    /// [`DEFAULT_LEVEL_INTERVAL_MS`](crate::audio::DEFAULT_LEVEL_INTERVAL_MS)
+    /// and are dropped if the channel is full. Like the VAD sender, it is
+    /// handed to the next run only.
+    pub fn with_level_events(mut self, events: mpsc::Sender<AudioLevel>) -> Self {
+        self.level_events = Some(events);
+        self
+    }
+
+    /// Set the channel that receives device errors and changes
+    ///
+    /// Handed to the source, which reports stream errors and, for a
+    /// [`SupervisedCapture`](crate::audio::SupervisedCapture), device loss and
+    /// failover. Sources that cannot fail once started ignore it.
+    pub fn with_device_events(mut self, events: mpsc::Sender<DeviceEvent>) -> Self {
+        self.source.set_device_events(events);
+        self
+    }
+
+    /// Record the 16kHz PCM stream to a WAV file
+    ///
+    /// Every sample is recorded, including silence that voice activity
+    /// detection does not send. The file is finalized when the run ends;
+    /// like the event senders, the writer is used for the next run only.
+    pub fn with_recorder(mut self, recorder: WavWriter) -> Self {
+        self.recorder = Some(recorder);
+        self
     }
 
     /// Start the audio pipeline
     ///
-    /// This starts audio capture and processing. Audio will be:
-    /// 1. Captured from microphone
-    /// 2. Resampled to 16kHz
-    /// 3. Converted to i16 PCM
-    /// 4. Accumulated to 100ms chunks
-    /// 5. Base64 encoded
-    /// 6. Sent through the output channel
+    /// This starts the audio source and processing. Audio will be:
+    /// 1. Captured from the source
+    /// 2. Denoised (if noise suppression is enabled)
+    /// 3. Resampled to 16kHz
+    /// 4. Amplified (fixed gain, optional AGC, limiter)
+    /// 5. Converted to i16 PCM (and recorded, if a recorder is set)
+    /// 6. Accumulated to 100ms chunks
+    /// 7. Filtered by voice activity detection (if enabled)
+    /// 8. Base64 encoded
+    /// 9. Sent through the output channel
     ///
     /// # Arguments
     /// * `output` - Channel to send Base64-encoded audio chunks
@@ -102,26 +224,55 @@ impl AudioPipeline {
         // Create stop signal
         let (stop_tx, stop_rx) = tokio::sync::oneshot::channel();
 
-        // Start audio capture
-        self.capture.start(internal_tx)?;
-
-        // Spawn processing task
+        // Build the fallible stages first so a failure leaves the source stopped
         let mut resampler = AudioResampler::new(
-            self.capture.sample_rate(),
+            self.source.sample_rate(),
             16000,
         )?;
 
+        let mut denoiser = if self.noise_suppression {
+            info!("Noise suppression enabled");
+            Some(NoiseSuppressor::new(self.source.sample_rate())?)
+        } else {
+            None
+        };
+
+        // Start the audio source
+        self.source.start(internal_tx)?;
+
+        // Spawn processing task
+        let mut gain = GainStage::new(self.gain.clone(), 16000);
+        let mut vad = VoiceActivityDetector::new(self.vad.clone(), 16000);
+        let vad_events = self.vad_events.take();
+        let mut meter = self
+            .level_events
+            .take()
+            .map(|events| (LevelMeter::default(), events));
+        let mut recorder = self.recorder.take();
+
         let processing_task = tokio::spawn(async move {
             if let Err(e) = Self::processing_loop(
                 internal_rx,
                 output,
                 stop_rx,
+                &mut meter,
+                &mut denoiser,
                 &mut resampler,
+                &mut gain,
+                &mut vad,
+                vad_events,
+                &mut recorder,
             )
             .await
             {
                 error!("Processing loop error: {}", e);
             }
+
+            if let Some(recorder) = recorder
+                && let Err(e) = recorder.finalize()
+            {
+                error!("Failed to finalize recording: {}", e);
+            }
         });
 
         self.processing_task = Some(processing_task);
@@ -134,7 +285,7 @@ impl AudioPipeline {
 
     /// Stop the audio pipeline
     ///
-    /// This stops audio capture and processing tasks.
+    /// This stops the audio source and processing tasks.
     pub async fn stop(&mut self) {
         if !self.is_running {
             return;
@@ -142,8 +293,8 @@ impl AudioPipeline {
 
         info!("Stopping audio pipeline");
 
-        // Stop audio capture
-        self.capture.stop();
+        // Stop the audio source
+        self.source.stop();
 
         // Send stop signal to processing task
         if let Some(stop_tx) = self.stop_signal.take() {
@@ -166,7 +317,7 @@ impl AudioPipeline {
 
     /// Get input sample rate
     pub fn input_sample_rate(&self) -> u32 {
-        self.capture.sample_rate()
+        self.source.sample_rate()
     }
 
     /// Get output sample rate (always 16000 Hz)
@@ -175,11 +326,18 @@ impl AudioPipeline {
     }
 
     /// Processing loop that handles audio data flow
+    #[allow(clippy::too_many_arguments)]
     async fn processing_loop(
         mut input_rx: mpsc::Receiver<Vec<f32>>,
         output_tx: mpsc::Sender<String>,
         mut stop_rx: tokio::sync::oneshot::Receiver<()>,
+        meter: &mut Option<(LevelMeter, mpsc::Sender<AudioLevel>)>,
+        denoiser: &mut Option<NoiseSuppressor>,
         resampler: &mut AudioResampler,
+        gain: &mut GainStage,
+        vad: &mut VoiceActivityDetector,
+        vad_events: Option<mpsc::Sender<VadEvent>>,
+        recorder: &mut Option<WavWriter>,
     ) -> AudioResult<()> {
         // Buffer for accumulating resampled audio
         let mut resample_buffer = Vec::new();
@@ -198,18 +356,59 @@ impl AudioPipeline {
         loop {
             tokio::select! {
                 // Receive audio data
-                Some(audio_data) = input_rx.recv() => {
+                audio_data = input_rx.recv() => {
+                    let Some(audio_data) = audio_data else {
+                        // The source ran out of audio: flush what is left
+                        info!("Input channel closed");
+                        if !pcm_buffer.is_empty() {
+                            let chunk = std::mem::take(&mut pcm_buffer);
+                            Self::send_chunk(chunk, vad, &vad_events, &output_tx).await;
+                        }
+                        break;
+                    };
+
+                    // Meter the input level (throttled)
+                    if let Some((meter, events)) = meter.as_mut()
+                        && let Some(level) = meter.process(&audio_data)
+                    {
+                        let _ = events.try_send(level);
+                    }
+
+                    // Suppress background noise at the capture rate
+                    let audio_data = match denoiser.as_mut() {
+                        Some(denoiser) => match denoiser.process(&audio_data) {
+                            Ok(denoised) => denoised,
+                            Err(e) => {
+                                error!("Noise suppression error: {}", e);
+                                audio_data
+                            }
+                        },
+                        None => audio_data,
+                    };
+
                     // Resample audio using buffered processing
                     match resampler.process_buffered(&audio_data, &mut resample_buffer) {
-                        Ok(resampled) => {
+                        Ok(mut resampled) => {
                             if resampled.is_empty() {
                                 continue;
                             }
 
                             debug!("Resampled {} samples to {} samples", audio_data.len(), resampled.len());
 
+                            // Apply gain, AGC and limiter
+                            gain.process(&mut resampled);
+
                             // Convert f32 to i16 PCM
                             let pcm_samples = Self::f32_to_i16_pcm(&resampled);
+
+                            // Tee into the recording; stop recording on the first error
+                            if let Some(writer) = recorder.as_mut()
+                                && let Err(e) = writer.write_samples(&pcm_samples)
+                            {
+                                error!("Recording failed, continuing without it: {}", e);
+                                *recorder = None;
+                            }
+
                             pcm_buffer.extend(pcm_samples);
 
                             // Check if we have accumulated enough samples (100ms)
@@ -217,16 +416,7 @@ impl AudioPipeline {
                                 // Take exactly target_samples
                                 let chunk: Vec<i16> = pcm_buffer.drain(..target_samples).collect();
 
-                                // Convert i16 to bytes
-                                let pcm_bytes = Self::i16_to_bytes(&chunk);
-
-                                // Base64 encode
-                                let encoded = Self::encode_base64(&pcm_bytes);
-
-                                debug!("Sending {} bytes (Base64: {} chars)", pcm_bytes.len(), encoded.len());
-
-                                // Send to output channel
-                                if output_tx.send(encoded).await.is_err() {
+                                if !Self::send_chunk(chunk, vad, &vad_events, &output_tx).await {
                                     warn!("Output channel closed, stopping processing loop");
                                     return Ok(());
                                 }
@@ -243,12 +433,6 @@ impl AudioPipeline {
                     info!("Stop signal received");
                     break;
                 }
-
-                // Input channel closed
-                else => {
-                    info!("Input channel closed");
-                    break;
-                }
             }
         }
 
@@ -256,6 +440,48 @@ impl AudioPipeline {
         Ok(())
     }
 
+    /// Run a PCM chunk through VAD, then encode and send what it lets through
+    ///
+    /// Returns false if the output channel is closed.
+    async fn send_chunk(
+        chunk: Vec<i16>,
+        vad: &mut VoiceActivityDetector,
+        vad_events: &Option<mpsc::Sender<VadEvent>>,
+        output_tx: &mpsc::Sender<String>,
+    ) -> bool {
+        // Skip or thin out silence
+        let vad_output = vad.process(chunk);
+
+        for event in vad_output.events {
+            if let Some(events) = vad_events
+                && events.try_send(event).is_err()
+            {
+                warn!("Dropping VAD event {:?}: channel full or closed", event);
+            }
+        }
+
+        for chunk in vad_output.chunks {
+            // Convert i16 to bytes
+            let pcm_bytes = Self::i16_to_bytes(&chunk);
+
+            // Base64 encode
+            let encoded = Self::encode_base64(&pcm_bytes);
+
+            debug!(
+                "Sending {} bytes (Base64: {} chars)",
+                pcm_bytes.len(),
+                encoded.len()
+            );
+
+            // Send to output channel
+            if output_tx.send(encoded).await.is_err() {
+                return false;
+            }
+        }
+
+        true
+    }
+
     /// Convert f32 samples (range: -1.0 to 1.0) to i16 PCM (range: -32768 to 32767)
     fn f32_to_i16_pcm(samples: &[f32]) -> Vec<i16> {
         samples
@@ -287,8 +513,8 @@ impl AudioPipeline {
 impl Drop for AudioPipeline {
     fn drop(&mut self) {
         if self.is_running {
-            // Note: We can't call async stop() in Drop, but we can stop capture
-            self.capture.stop();
+            // Note: We can't call async stop() in Drop, but we can stop the source
+            self.source.stop();
 
             // Send stop signal
             if let Some(stop_tx) = self.stop_signal.take() {
@@ -375,6 +601,158 @@ mod tests {
         assert!(input_rate > 0);
     }
 
+    #[tokio::test]
+    async fn test_pipeline_from_synthetic_source() {
+        use crate::audio::{Pacing, Signal, SyntheticSource};
+        use std::time::Duration;
+
+        let source = SyntheticSource::new(48000)
+            .with_segment(
+                Signal::Tone {
+                    frequency: 440.0,
+                    amplitude: 0.5,
+                },
+                Duration::from_secs(1),
+            )
+            .with_pacing(Pacing::Fast);
+
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut pipeline = AudioPipeline::from_source(Box::new(source));
+        assert_eq!(pipeline.input_sample_rate(), 48000);
+        pipeline.start(tx).await.unwrap();
+
+        // The output channel closes once the source is exhausted
+        let mut samples = 0;
+        let mut chunks = 0;
+        while let Some(encoded) = rx.recv().await {
+            samples += STANDARD.decode(&encoded).unwrap().len() / 2;
+            chunks += 1;
+        }
+
+        // One second at 16kHz, minus what the resampler still holds
+        assert!(chunks >= 10, "got {} chunks", chunks);
+        assert!(
+            (15000..=16000).contains(&samples),
+            "got {} samples",
+            samples
+        );
+
+        pipeline.stop().await;
+        assert!(!pipeline.is_running());
+    }
+
+    /// Source that reports a sample rate no processing stage accepts
+    struct ZeroRateSource {
+        active: bool,
+    }
+
+    impl AudioSource for ZeroRateSource {
+        fn start(&mut self, _sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+            self.active = true;
+            Ok(())
+        }
+
+        fn stop(&mut self) {
+            self.active = false;
+        }
+
+        fn sample_rate(&self) -> u32 {
+            0
+        }
+
+        fn is_active(&self) -> bool {
+            self.active
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_failed_start_leaves_source_stopped() {
+        let source = ZeroRateSource { active: false };
+        let mut pipeline =
+            AudioPipeline::from_source(Box::new(source)).with_noise_suppression(true);
+        let (tx, _rx) = mpsc::channel(10);
+
+        assert!(pipeline.start(tx).await.is_err());
+        assert!(!pipeline.is_running());
+        assert!(!pipeline.source.is_active());
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_records_wav() {
+        use crate::audio::source::read_wav_header;
+        use crate::audio::{Pacing, Signal, SyntheticSource};
+        use std::time::Duration;
+
+        let path = std::env::temp_dir().join(format!("raflow-pipeline-{}.wav", std::process::id()));
+        let source = SyntheticSource::new(16000)
+            .with_segment(Signal::Silence, Duration::from_millis(500))
+            .with_pacing(Pacing::Fast);
+
+        // VAD drops the silence, but the recording keeps it
+        let (tx, mut rx) = mpsc::channel(10);
+        let mut pipeline = AudioPipeline::from_source(Box::new(source))
+            .with_vad(VadConfig::default())
+            .with_recorder(WavWriter::create(&path, 16000).unwrap());
+        pipeline.start(tx).await.unwrap();
+        let mut sent = 0;
+        while let Some(encoded) = rx.recv().await {
+            sent += STANDARD.decode(&encoded).unwrap().len() / 2;
+        }
+        pipeline.stop().await;
+
+        let mut file = std::fs::File::open(&path).unwrap();
+        let (format, length) = read_wav_header(&mut file).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(format.sample_rate, 16000);
+
+        // Half a second at 16kHz, minus what the resampler still holds
+        let recorded = length.unwrap() as usize / 2;
+        assert!((7500..=8000).contains(&recorded), "recorded {}", recorded);
+        assert!(sent < recorded, "sent {} of {}", sent, recorded);
+    }
+
+    #[tokio::test]
+    async fn test_pipeline_level_events() {
+        use crate::audio::{Pacing, Signal, SyntheticSource};
+        use std::time::Duration;
+
+        // 300ms of a half-scale tone, in real time
+        let source = SyntheticSource::new(16000)
+            .with_segment(
+                Signal::Tone {
+                    frequency: 440.0,
+                    amplitude: 0.5,
+                },
+                Duration::from_millis(300),
+            )
+            .with_pacing(Pacing::Realtime);
+
+        let (tx, mut rx) = mpsc::channel(10);
+        let (level_tx, mut level_rx) = mpsc::channel(100);
+        let mut pipeline = AudioPipeline::from_source(Box::new(source)).with_level_events(level_tx);
+        pipeline.start(tx).await.unwrap();
+
+        while rx.recv().await.is_some() {}
+        pipeline.stop().await;
+
+        let mut levels = Vec::new();
+        while let Some(level) = level_rx.recv().await {
+            levels.push(level);
+        }
+
+        // Throttled to about 30 Hz rather than one report per 10ms block
+        assert!(
+            (3..=15).contains(&levels.len()),
+            "got {} levels",
+            levels.len()
+        );
+        for level in &levels {
+            assert!((level.peak - 0.5).abs() < 0.01);
+            assert!(!level.clipping);
+        }
+    }
+
     #[tokio::test]
     async fn test_pipeline_start_stop() {
         let (tx, mut rx) = mpsc::channel(10);
@@ -0,0 +1,158 @@
+use crate::audio::error::AudioResult;
+use std::fs::File;
+use std::io::{BufWriter, Seek, SeekFrom, Write};
+use std::path::Path;
+use tracing::debug;
+
+/// Size of the RIFF/WAVE header written by [`WavWriter`]
+const HEADER_LEN: u32 = 44;
+
+/// Streaming writer for mono 16-bit PCM WAV files
+///
+/// The header is written up front with empty sizes and patched by
+/// [`finalize`](Self::finalize). A file that was never finalized (for example
+/// after a crash) still holds valid audio but reports zero length.
+///
+/// # Example
+/// ```no_run
+/// use raflow_lib::audio::WavWriter;
+///
+/// let mut writer = WavWriter::create("session.wav", 16000).unwrap();
+/// writer.write_samples(&[0, 1000, -1000]).unwrap();
+/// writer.finalize().unwrap();
+/// ```
+pub struct WavWriter<W: Write + Seek = BufWriter<File>> {
+    writer: W,
+    sample_rate: u32,
+    samples_written: u64,
+}
+
+impl WavWriter<BufWriter<File>> {
+    /// Create (or truncate) a WAV file at `path`
+    ///
+    /// # Errors
+    /// Returns `AudioError::Io` if the file cannot be created.
+    pub fn create(path: impl AsRef<Path>, sample_rate: u32) -> AudioResult<Self> {
+        let path = path.as_ref();
+        debug!("Recording to {}", path.display());
+        Self::new(BufWriter::new(File::create(path)?), sample_rate)
+    }
+}
+
+impl<W: Write + Seek> WavWriter<W> {
+    /// Start a WAV stream on `writer`
+    ///
+    /// # Errors
+    /// Returns `AudioError::Io` if the header cannot be written.
+    pub fn new(mut writer: W, sample_rate: u32) -> AudioResult<Self> {
+        writer.write_all(&Self::header(sample_rate, 0))?;
+        Ok(Self {
+            writer,
+            sample_rate,
+            samples_written: 0,
+        })
+    }
+
+    /// Append samples
+    ///
+    /// # Errors
+    /// Returns `AudioError::Io` if writing fails.
+    pub fn write_samples(&mut self, samples: &[i16]) -> AudioResult<()> {
+        let bytes: Vec<u8> = samples.iter().flat_map(|s| s.to_le_bytes()).collect();
+        self.writer.write_all(&bytes)?;
+        self.samples_written += samples.len() as u64;
+        Ok(())
+    }
+
+    /// Number of samples written so far
+    pub fn samples_written(&self) -> u64 {
+        self.samples_written
+    }
+
+    /// Duration of the audio written so far, in milliseconds
+    pub fn duration_ms(&self) -> u64 {
+        self.samples_written * 1000 / self.sample_rate.max(1) as u64
+    }
+
+    /// Sample rate of the file
+    pub fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    /// Patch the header sizes and flush
+    ///
+    /// # Errors
+    /// Returns `AudioError::Io` if seeking or writing fails.
+    pub fn finalize(mut self) -> AudioResult<W> {
+        let data_len = (self.samples_written * 2).min((u32::MAX - HEADER_LEN) as u64) as u32;
+
+        self.writer.seek(SeekFrom::Start(0))?;
+        self.writer
+            .write_all(&Self::header(self.sample_rate, data_len))?;
+        self.writer.seek(SeekFrom::End(0))?;
+        self.writer.flush()?;
+
+        debug!("Recording finalized: {} ms", self.duration_ms());
+        Ok(self.writer)
+    }
+
+    /// RIFF/WAVE header for mono 16-bit PCM with `data_len` bytes of audio
+    fn header(sample_rate: u32, data_len: u32) -> [u8; HEADER_LEN as usize] {
+        let mut header = [0u8; HEADER_LEN as usize];
+        header[0..4].copy_from_slice(b"RIFF");
+        header[4..8].copy_from_slice(&(HEADER_LEN - 8 + data_len).to_le_bytes());
+        header[8..12].copy_from_slice(b"WAVE");
+        header[12..16].copy_from_slice(b"fmt ");
+        header[16..20].copy_from_slice(&16u32.to_le_bytes());
+        header[20..22].copy_from_slice(&1u16.to_le_bytes()); // PCM
+        header[22..24].copy_from_slice(&1u16.to_le_bytes()); // mono
+        header[24..28].copy_from_slice(&sample_rate.to_le_bytes());
+        header[28..32].copy_from_slice(&(sample_rate * 2).to_le_bytes()); // byte rate
+        header[32..34].copy_from_slice(&2u16.to_le_bytes()); // block align
+        header[34..36].copy_from_slice(&16u16.to_le_bytes()); // bits per sample
+        header[36..40].copy_from_slice(b"data");
+        header[40..44].copy_from_slice(&data_len.to_le_bytes());
+        header
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::source::read_wav_header;
+    use std::io::{Cursor, Read};
+
+    #[test]
+    fn test_wav_round_trip() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), 16000).unwrap();
+        writer.write_samples(&[0, 1000, -1000]).unwrap();
+        writer.write_samples(&[i16::MAX, i16::MIN]).unwrap();
+        assert_eq!(writer.samples_written(), 5);
+
+        let mut cursor = writer.finalize().unwrap();
+        assert_eq!(cursor.get_ref().len(), 44 + 10);
+
+        cursor.set_position(0);
+        let (format, length) = read_wav_header(&mut cursor).unwrap();
+        assert_eq!(format.sample_rate, 16000);
+        assert_eq!(format.channels, 1);
+        assert_eq!(length, Some(10));
+
+        let mut data = Vec::new();
+        cursor.read_to_end(&mut data).unwrap();
+        let samples: Vec<i16> = data
+            .chunks_exact(2)
+            .map(|b| i16::from_le_bytes([b[0], b[1]]))
+            .collect();
+        assert_eq!(samples, vec![0, 1000, -1000, i16::MAX, i16::MIN]);
+    }
+
+    #[test]
+    fn test_duration() {
+        let mut writer = WavWriter::new(Cursor::new(Vec::new()), 16000).unwrap();
+        writer.write_samples(&[0; 8000]).unwrap();
+
+        assert_eq!(writer.duration_ms(), 500);
+        assert_eq!(writer.sample_rate(), 16000);
+    }
+}
@@ -0,0 +1,408 @@
+use crate::audio::device::{list_input_devices, AudioDevice};
+use crate::audio::error::{AudioError, AudioResult};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Minimum token similarity (0.0 - 1.0) for a fuzzy name match
+pub const FUZZY_MATCH_THRESHOLD: f32 = 0.6;
+
+/// Words shared by many unrelated devices, ignored by fuzzy matching so that
+/// `"Microphone (Realtek Audio)"` does not match `"Microphone (USB Audio)"`
+const GENERIC_TOKENS: &[&str] = &[
+    "microphone",
+    "mic",
+    "audio",
+    "usb",
+    "headset",
+    "input",
+    "device",
+];
+
+/// How a device was chosen by [`DeviceSelector`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchKind {
+    /// The saved device ID matched exactly
+    Exact,
+    /// The saved name matched after normalization (case, punctuation, indices)
+    Name,
+    /// The saved name was similar enough to a device name
+    Fuzzy,
+    /// The saved device was missing; an entry of the preferred list matched
+    Preferred,
+    /// Nothing was configured or matched; the system default device is used
+    Default,
+}
+
+/// The input device chosen by [`DeviceSelector`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeviceMatch {
+    /// The chosen device
+    pub device: AudioDevice,
+    /// Which matching step chose it
+    pub kind: MatchKind,
+}
+
+impl DeviceMatch {
+    /// The ID to open the device with
+    ///
+    /// None for [`MatchKind::Default`], so the system default is opened
+    /// directly rather than by a name that may change.
+    pub fn device_id(&self) -> Option<&str> {
+        match self.kind {
+            MatchKind::Default => None,
+            _ => Some(&self.device.id),
+        }
+    }
+}
+
+/// Chooses an input device from a saved identity, tolerating renames and
+/// re-indexing by the OS
+///
+/// Devices are matched in order:
+/// 1. exact device ID
+/// 2. normalized name (case, punctuation and numeric indices such as
+///    `"Microphone (2- USB Audio)"` are ignored)
+/// 3. fuzzy name match (word overlap of at least [`FUZZY_MATCH_THRESHOLD`],
+///    ignoring generic words such as "microphone" or "usb")
+/// 4. the first available entry of the preferred list (by ID or normalized name)
+/// 5. the system default device
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::{AudioDevice, DeviceSelector, MatchKind};
+///
+/// let devices = vec![AudioDevice {
+///     id: "Microphone (2- USB Audio)".to_string(),
+///     name: "Microphone (2- USB Audio)".to_string(),
+///     is_default: false,
+///     sample_rates: vec![48000],
+/// }];
+///
+/// let selected = DeviceSelector::new(Some("Microphone (USB Audio)"))
+///     .select(&devices)
+///     .unwrap();
+/// assert_eq!(selected.kind, MatchKind::Name);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceSelector {
+    device_id: Option<String>,
+    device_name: Option<String>,
+    preferred: Vec<String>,
+}
+
+impl DeviceSelector {
+    /// Create a selector for the given saved device ID (None for the default device)
+    pub fn new(device_id: Option<&str>) -> Self {
+        Self {
+            device_id: device_id.filter(|id| !id.is_empty()).map(str::to_string),
+            ..Self::default()
+        }
+    }
+
+    /// Set the saved display name, matched when the ID no longer exists
+    pub fn with_name(mut self, device_name: Option<&str>) -> Self {
+        self.device_name = device_name
+            .filter(|name| !name.is_empty())
+            .map(str::to_string);
+        self
+    }
+
+    /// Set the devices (IDs or names) to try, in order, when the saved device is missing
+    pub fn with_preferred(mut self, preferred: Vec<String>) -> Self {
+        self.preferred = preferred.into_iter().filter(|p| !p.is_empty()).collect();
+        self
+    }
+
+    /// Choose a device from the currently available input devices
+    ///
+    /// Returns `Ok(None)` if there are no input devices at all.
+    ///
+    /// # Errors
+    /// Returns `AudioError::CpalError` if the devices cannot be listed.
+    pub fn resolve(&self) -> AudioResult<Option<DeviceMatch>> {
+        match list_input_devices() {
+            Ok(devices) => Ok(self.select(&devices)),
+            Err(AudioError::DeviceNotFound) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Choose a device from `devices`
+    ///
+    /// Returns None only if nothing matched and no device is marked as default.
+    pub fn select(&self, devices: &[AudioDevice]) -> Option<DeviceMatch> {
+        let chosen = |device: &AudioDevice, kind| {
+            Some(DeviceMatch {
+                device: device.clone(),
+                kind,
+            })
+        };
+
+        if let Some(id) = &self.device_id
+            && let Some(device) = devices.iter().find(|d| &d.id == id)
+        {
+            return chosen(device, MatchKind::Exact);
+        }
+
+        let targets: Vec<&str> = self
+            .device_name
+            .iter()
+            .chain(self.device_id.iter())
+            .map(String::as_str)
+            .collect();
+
+        if let Some(device) = targets.iter().find_map(|t| find_by_name(devices, t)) {
+            return chosen(device, MatchKind::Name);
+        }
+
+        if let Some(device) = find_fuzzy(devices, &targets) {
+            return chosen(device, MatchKind::Fuzzy);
+        }
+
+        for preferred in &self.preferred {
+            let device = devices
+                .iter()
+                .find(|d| &d.id == preferred)
+                .or_else(|| find_by_name(devices, preferred));
+            if let Some(device) = device {
+                return chosen(device, MatchKind::Preferred);
+            }
+        }
+
+        devices
+            .iter()
+            .find(|d| d.is_default)
+            .and_then(|device| chosen(device, MatchKind::Default))
+    }
+}
+
+/// Find a device whose normalized name equals the normalized `name`
+fn find_by_name<'a>(devices: &'a [AudioDevice], name: &str) -> Option<&'a AudioDevice> {
+    let tokens = name_tokens(name);
+    if tokens.is_empty() {
+        return None;
+    }
+
+    devices.iter().find(|d| name_tokens(&d.name) == tokens)
+}
+
+/// Find the device whose name is most similar to any of `targets`
+fn find_fuzzy<'a>(devices: &'a [AudioDevice], targets: &[&str]) -> Option<&'a AudioDevice> {
+    let mut best: Option<(&AudioDevice, f32)> = None;
+
+    for device in devices {
+        let tokens = distinctive_tokens(&device.name);
+        let score = targets
+            .iter()
+            .map(|t| similarity(&distinctive_tokens(t), &tokens))
+            .fold(0.0, f32::max);
+
+        if score >= FUZZY_MATCH_THRESHOLD && best.is_none_or(|(_, s)| score > s) {
+            best = Some((device, score));
+        }
+    }
+
+    best.map(|(device, _)| device)
+}
+
+/// Split a device name into lowercase words, dropping punctuation and
+/// purely numeric tokens (OS-assigned indices such as `"2-"` or `"#3"`)
+fn name_tokens(name: &str) -> Vec<String> {
+    name.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty() && !token.chars().all(|c| c.is_ascii_digit()))
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Name tokens without [`GENERIC_TOKENS`]
+fn distinctive_tokens(name: &str) -> Vec<String> {
+    name_tokens(name)
+        .into_iter()
+        .filter(|token| !GENERIC_TOKENS.contains(&token.as_str()))
+        .collect()
+}
+
+/// Jaccard similarity of two token lists
+fn similarity(a: &[String], b: &[String]) -> f32 {
+    let a: HashSet<&String> = a.iter().collect();
+    let b: HashSet<&String> = b.iter().collect();
+
+    let union = a.union(&b).count();
+    if union == 0 {
+        return 0.0;
+    }
+
+    a.intersection(&b).count() as f32 / union as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn device(name: &str, is_default: bool) -> AudioDevice {
+        AudioDevice {
+            id: name.to_string(),
+            name: name.to_string(),
+            is_default,
+            sample_rates: vec![48000],
+        }
+    }
+
+    fn devices() -> Vec<AudioDevice> {
+        vec![
+            device("MacBook Pro Microphone", true),
+            device("Microphone (2- USB Audio Device)", false),
+            device("Blue Yeti Stereo Microphone", false),
+        ]
+    }
+
+    #[test]
+    fn test_name_tokens() {
+        assert_eq!(
+            name_tokens("Microphone (2- USB Audio)"),
+            vec!["microphone", "usb", "audio"]
+        );
+        assert_eq!(name_tokens("USB  audio #3"), vec!["usb", "audio"]);
+        assert_eq!(name_tokens("麦克风 (Realtek)"), vec!["麦克风", "realtek"]);
+        assert!(name_tokens("(2)").is_empty());
+    }
+
+    #[test]
+    fn test_exact_id_match() {
+        let selected = DeviceSelector::new(Some("Blue Yeti Stereo Microphone"))
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Exact);
+        assert_eq!(selected.device.name, "Blue Yeti Stereo Microphone");
+        assert_eq!(selected.device_id(), Some("Blue Yeti Stereo Microphone"));
+    }
+
+    #[test]
+    fn test_reindexed_device_matches_by_name() {
+        let selected = DeviceSelector::new(Some("Microphone (USB Audio Device)"))
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Name);
+        assert_eq!(selected.device.id, "Microphone (2- USB Audio Device)");
+    }
+
+    #[test]
+    fn test_saved_name_used_when_id_changed() {
+        let selected = DeviceSelector::new(Some("{0.0.1.00000000}.{f3a1}"))
+            .with_name(Some("blue yeti stereo microphone"))
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Name);
+        assert_eq!(selected.device.name, "Blue Yeti Stereo Microphone");
+    }
+
+    #[test]
+    fn test_renamed_device_matches_fuzzy() {
+        let selected = DeviceSelector::new(Some("Yeti Stereo Microphone"))
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Fuzzy);
+        assert_eq!(selected.device.name, "Blue Yeti Stereo Microphone");
+    }
+
+    #[test]
+    fn test_fuzzy_prefers_best_score() {
+        let devices = vec![device("Rode NT", false), device("Rode NT Mini Pro", false)];
+
+        let selected = DeviceSelector::new(Some("Rode NT Mini"))
+            .select(&devices)
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Fuzzy);
+        assert_eq!(selected.device.name, "Rode NT Mini Pro");
+    }
+
+    #[test]
+    fn test_fuzzy_ignores_generic_words() {
+        let devices = vec![
+            device("Microphone (USB Audio)", false),
+            device("Headset (Conexant Audio)", true),
+        ];
+
+        assert_eq!(
+            distinctive_tokens("Microphone (2- USB Audio Device)"),
+            Vec::<String>::new()
+        );
+
+        let selected = DeviceSelector::new(Some("Microphone (Realtek Audio)"))
+            .select(&devices)
+            .unwrap();
+        assert_eq!(selected.kind, MatchKind::Default);
+
+        let selected = DeviceSelector::new(Some("USB Headset"))
+            .select(&devices)
+            .unwrap();
+        assert_eq!(selected.kind, MatchKind::Default);
+    }
+
+    #[test]
+    fn test_fuzzy_before_preferred() {
+        let selected = DeviceSelector::new(Some("Yeti Stereo Microphone"))
+            .with_preferred(vec!["MacBook Pro Microphone".to_string()])
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Fuzzy);
+        assert_eq!(selected.device.name, "Blue Yeti Stereo Microphone");
+    }
+
+    #[test]
+    fn test_preferred_list_in_order() {
+        let selected = DeviceSelector::new(Some("Studio Condenser"))
+            .with_preferred(vec![
+                "Missing Device".to_string(),
+                "microphone (usb audio device)".to_string(),
+                "Blue Yeti Stereo Microphone".to_string(),
+            ])
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Preferred);
+        assert_eq!(selected.device.id, "Microphone (2- USB Audio Device)");
+    }
+
+    #[test]
+    fn test_falls_back_to_default_device() {
+        let selected = DeviceSelector::new(Some("Studio Condenser"))
+            .select(&devices())
+            .unwrap();
+
+        assert_eq!(selected.kind, MatchKind::Default);
+        assert_eq!(selected.device.name, "MacBook Pro Microphone");
+        assert_eq!(selected.device_id(), None);
+
+        let selected = DeviceSelector::new(None).select(&devices()).unwrap();
+        assert_eq!(selected.kind, MatchKind::Default);
+    }
+
+    #[test]
+    fn test_no_match_without_default_device() {
+        let devices = vec![device("USB Audio", false)];
+
+        assert!(
+            DeviceSelector::new(Some("Studio Condenser"))
+                .select(&devices)
+                .is_none()
+        );
+        assert!(DeviceSelector::new(None).select(&[]).is_none());
+    }
+
+    #[test]
+    fn test_empty_strings_are_unset() {
+        let selector = DeviceSelector::new(Some(""))
+            .with_name(Some(""))
+            .with_preferred(vec![String::new()]);
+
+        assert_eq!(selector, DeviceSelector::default());
+    }
+}
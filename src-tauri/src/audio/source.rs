@@ -0,0 +1,1065 @@
+use crate::audio::error::{AudioError, AudioResult};
+use crate::audio::{AudioCapture, ChannelMode, DeviceEvent};
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread::{self, JoinHandle};
+use std::time::{Duration, Instant};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, error, info, warn};
+
+/// Duration of one block delivered by file and synthetic sources, in milliseconds
+const BLOCK_MS: u32 = 10;
+
+/// Largest WAV `fmt ` chunk accepted; `WAVE_FORMAT_EXTENSIBLE` needs 40 bytes
+const MAX_FMT_CHUNK_SIZE: u32 = 64;
+
+/// A producer of mono f32 audio for [`AudioPipeline`](crate::audio::AudioPipeline)
+///
+/// Implemented by the microphone ([`AudioCapture`]), by [`FileSource`] for
+/// WAV files and raw PCM streams, and by [`SyntheticSource`] for generated
+/// test signals. A source sends blocks of mono samples in the range
+/// [-1.0, 1.0] at [`sample_rate`](Self::sample_rate). Finite sources drop
+/// the sender when they run out of audio, which ends the pipeline's stream.
+pub trait AudioSource {
+    /// Start sending audio blocks to `sender`
+    fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()>;
+
+    /// Stop sending audio; safe to call multiple times
+    fn stop(&mut self);
+
+    /// Sample rate of the produced audio in Hz
+    fn sample_rate(&self) -> u32;
+
+    /// Whether the source is currently started
+    fn is_active(&self) -> bool;
+
+    /// Set how multi-channel input is reduced to mono
+    ///
+    /// Sources that only produce mono ignore this.
+    fn set_channel_mode(&mut self, _mode: ChannelMode) {}
+
+    /// Set the channel that receives errors and device changes while running
+    ///
+    /// Sources that cannot fail once started ignore this.
+    fn set_device_events(&mut self, _events: mpsc::Sender<DeviceEvent>) {}
+
+    /// Name of the capture device, for sources backed by one
+    fn device_name(&self) -> Option<String> {
+        None
+    }
+}
+
+impl AudioSource for AudioCapture {
+    fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+        AudioCapture::start(self, sender)
+    }
+
+    fn stop(&mut self) {
+        AudioCapture::stop(self)
+    }
+
+    fn sample_rate(&self) -> u32 {
+        AudioCapture::sample_rate(self)
+    }
+
+    fn is_active(&self) -> bool {
+        self.is_capturing()
+    }
+
+    fn set_channel_mode(&mut self, mode: ChannelMode) {
+        AudioCapture::set_channel_mode(self, mode)
+    }
+
+    fn set_device_events(&mut self, events: mpsc::Sender<DeviceEvent>) {
+        AudioCapture::set_device_events(self, events)
+    }
+
+    fn device_name(&self) -> Option<String> {
+        Some(AudioCapture::device_name(self).to_string())
+    }
+}
+
+/// How fast file and synthetic sources deliver audio
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Pacing {
+    /// One second of audio per second, like a microphone
+    #[default]
+    Realtime,
+
+    /// As fast as the pipeline consumes it
+    Fast,
+}
+
+/// Encoding of a single little-endian PCM sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SampleEncoding {
+    /// Unsigned 8-bit, centred on 128
+    U8,
+    /// Signed 16-bit
+    I16,
+    /// Signed 24-bit, packed in 3 bytes
+    I24,
+    /// Signed 32-bit
+    I32,
+    /// 32-bit float
+    F32,
+}
+
+impl SampleEncoding {
+    /// Number of bytes per sample
+    pub fn bytes_per_sample(self) -> usize {
+        match self {
+            Self::U8 => 1,
+            Self::I16 => 2,
+            Self::I24 => 3,
+            Self::I32 | Self::F32 => 4,
+        }
+    }
+
+    /// Decode one sample to f32 in [-1.0, 1.0]
+    ///
+    /// `bytes` must hold exactly [`bytes_per_sample`](Self::bytes_per_sample) bytes.
+    fn decode(self, bytes: &[u8]) -> f32 {
+        match self {
+            Self::U8 => (bytes[0] as f32 - 128.0) / 128.0,
+            Self::I16 => i16::from_le_bytes([bytes[0], bytes[1]]) as f32 / 32768.0,
+            Self::I24 => {
+                // Place the 24 bits at the top of an i32 to sign-extend them
+                let value = i32::from_le_bytes([0, bytes[0], bytes[1], bytes[2]]) >> 8;
+                value as f32 / 8_388_608.0
+            }
+            Self::I32 => {
+                i32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]) as f32
+                    / 2_147_483_648.0
+            }
+            Self::F32 => f32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// Layout of interleaved PCM data
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::source::{PcmFormat, SampleEncoding};
+///
+/// // Output of `arecord -f S16_LE -r 16000 -c 1`
+/// let format = PcmFormat::new(16000, 1);
+/// assert_eq!(format.encoding, SampleEncoding::I16);
+/// assert_eq!(format.frame_bytes(), 2);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PcmFormat {
+    /// Sample rate in Hz
+    pub sample_rate: u32,
+
+    /// Number of interleaved channels
+    pub channels: u16,
+
+    /// Encoding of each sample
+    pub encoding: SampleEncoding,
+}
+
+impl PcmFormat {
+    /// Create a 16-bit PCM format
+    pub fn new(sample_rate: u32, channels: u16) -> Self {
+        Self {
+            sample_rate,
+            channels: channels.max(1),
+            encoding: SampleEncoding::I16,
+        }
+    }
+
+    /// Set the sample encoding
+    pub fn with_encoding(mut self, encoding: SampleEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+
+    /// Number of bytes per interleaved frame
+    pub fn frame_bytes(&self) -> usize {
+        self.encoding.bytes_per_sample() * self.channels as usize
+    }
+
+    /// Decode interleaved bytes to f32 samples
+    ///
+    /// Trailing bytes that do not form a whole sample are ignored.
+    pub fn decode(&self, bytes: &[u8]) -> Vec<f32> {
+        bytes
+            .chunks_exact(self.encoding.bytes_per_sample())
+            .map(|sample| self.encoding.decode(sample))
+            .collect()
+    }
+}
+
+/// Read a WAV header up to the start of the sample data
+///
+/// Supports PCM (8, 16, 24 and 32 bit), 32-bit float and their
+/// `WAVE_FORMAT_EXTENSIBLE` variants. Returns the format and the number of
+/// data bytes announced by the header (None when the header leaves it open,
+/// as streamed WAV files do).
+///
+/// # Errors
+/// Returns `AudioError::UnsupportedFormat` if the data is not a supported WAV
+/// stream, or `AudioError::Io` if reading fails.
+pub fn read_wav_header<R: Read>(reader: &mut R) -> AudioResult<(PcmFormat, Option<u64>)> {
+    let mut riff = [0u8; 12];
+    reader.read_exact(&mut riff)?;
+    if &riff[0..4] != b"RIFF" || &riff[8..12] != b"WAVE" {
+        return Err(AudioError::UnsupportedFormat(
+            "not a RIFF/WAVE file".to_string(),
+        ));
+    }
+
+    let mut format = None;
+    loop {
+        let mut header = [0u8; 8];
+        reader.read_exact(&mut header)?;
+        let id = [header[0], header[1], header[2], header[3]];
+        let size = u32::from_le_bytes([header[4], header[5], header[6], header[7]]);
+
+        match &id {
+            b"fmt " => {
+                if size > MAX_FMT_CHUNK_SIZE {
+                    return Err(AudioError::UnsupportedFormat(format!(
+                        "fmt chunk of {} bytes",
+                        size
+                    )));
+                }
+                let mut body = vec![0u8; size as usize];
+                reader.read_exact(&mut body)?;
+                format = Some(parse_fmt_chunk(&body)?);
+            }
+            b"data" => {
+                let format = format.ok_or_else(|| {
+                    AudioError::UnsupportedFormat("data chunk before fmt chunk".to_string())
+                })?;
+                let length = match size {
+                    0 | u32::MAX => None,
+                    size => Some(size as u64),
+                };
+                return Ok((format, length));
+            }
+            _ => {
+                // Chunks are padded to an even size
+                let skip = size as u64 + (size as u64 & 1);
+                io::copy(&mut reader.by_ref().take(skip), &mut io::sink())?;
+            }
+        }
+
+        // The fmt chunk may be padded too
+        if &id == b"fmt " && size & 1 == 1 {
+            reader.read_exact(&mut [0u8; 1])?;
+        }
+    }
+}
+
+/// Parse the body of a WAV `fmt ` chunk
+fn parse_fmt_chunk(body: &[u8]) -> AudioResult<PcmFormat> {
+    if body.len() < 16 {
+        return Err(AudioError::UnsupportedFormat(
+            "fmt chunk too short".to_string(),
+        ));
+    }
+
+    let mut tag = u16::from_le_bytes([body[0], body[1]]);
+    let channels = u16::from_le_bytes([body[2], body[3]]);
+    let sample_rate = u32::from_le_bytes([body[4], body[5], body[6], body[7]]);
+    let bits = u16::from_le_bytes([body[14], body[15]]);
+
+    // WAVE_FORMAT_EXTENSIBLE: the real format is the start of the sub-format GUID
+    if tag == 0xFFFE && body.len() >= 26 {
+        tag = u16::from_le_bytes([body[24], body[25]]);
+    }
+
+    let encoding = match (tag, bits) {
+        (1, 8) => SampleEncoding::U8,
+        (1, 16) => SampleEncoding::I16,
+        (1, 24) => SampleEncoding::I24,
+        (1, 32) => SampleEncoding::I32,
+        (3, 32) => SampleEncoding::F32,
+        _ => {
+            return Err(AudioError::UnsupportedFormat(format!(
+                "WAV format tag {} with {} bits per sample",
+                tag, bits
+            )));
+        }
+    };
+
+    if channels == 0 || sample_rate == 0 {
+        return Err(AudioError::UnsupportedFormat(format!(
+            "{} Hz, {} channels",
+            sample_rate, channels
+        )));
+    }
+
+    Ok(PcmFormat {
+        sample_rate,
+        channels,
+        encoding,
+    })
+}
+
+/// Background thread that feeds generated or decoded blocks into a channel
+struct Feeder {
+    /// Set to ask the thread to finish
+    stop: Arc<AtomicBool>,
+    /// Thread handle (None once joined or detached)
+    thread: Option<JoinHandle<()>>,
+    /// Whether `stop` leaves a thread that is still running instead of joining it
+    detach_on_stop: bool,
+}
+
+impl Feeder {
+    /// Spawn a thread that sends blocks from `next_block` until it returns
+    /// None, the receiver is dropped or [`stop`](Self::stop) is called
+    fn spawn<F>(
+        name: &str,
+        sample_rate: u32,
+        pacing: Pacing,
+        sender: mpsc::Sender<Vec<f32>>,
+        mut next_block: F,
+    ) -> AudioResult<Self>
+    where
+        F: FnMut() -> Option<Vec<f32>> + Send + 'static,
+    {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_flag = Arc::clone(&stop);
+
+        let thread = thread::Builder::new()
+            .name(name.to_string())
+            .spawn(move || {
+                let started = Instant::now();
+                let mut sent_samples = 0u64;
+
+                while !stop_flag.load(Ordering::Relaxed) {
+                    let Some(block) = next_block() else {
+                        debug!("Audio source exhausted after {} samples", sent_samples);
+                        break;
+                    };
+                    sent_samples += block.len() as u64;
+
+                    if !send_block(&sender, block, &stop_flag) {
+                        break;
+                    }
+
+                    if pacing == Pacing::Realtime {
+                        let due = started
+                            + Duration::from_secs_f64(sent_samples as f64 / sample_rate as f64);
+                        if let Some(wait) = due.checked_duration_since(Instant::now()) {
+                            thread::sleep(wait);
+                        }
+                    }
+                }
+                // Dropping the sender tells the pipeline the stream has ended
+            })
+            .map_err(|e| AudioError::StreamBuildFailed(e.to_string()))?;
+
+        Ok(Self {
+            stop,
+            thread: Some(thread),
+            detach_on_stop: false,
+        })
+    }
+
+    /// Do not wait for the thread on stop
+    ///
+    /// For threads that can block in a read indefinitely (stdin, pipes): the
+    /// thread notices the stop flag after its current read and ends on its own
+    fn detach_on_stop(mut self) -> Self {
+        self.detach_on_stop = true;
+        self
+    }
+
+    /// Ask the thread to finish and wait for it, unless it may be blocked
+    fn stop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+
+        if self.detach_on_stop && !thread.is_finished() {
+            debug!("Audio source thread still reading, detaching it");
+            return;
+        }
+
+        if thread.join().is_err() {
+            error!("Audio source thread panicked");
+        }
+    }
+}
+
+/// Send a block, waiting while the channel is full
+///
+/// Returns false if the receiver is gone or a stop was requested.
+fn send_block(sender: &mpsc::Sender<Vec<f32>>, mut block: Vec<f32>, stop: &AtomicBool) -> bool {
+    loop {
+        match sender.try_send(block) {
+            Ok(()) => return true,
+            Err(TrySendError::Closed(_)) => return false,
+            Err(TrySendError::Full(returned)) => {
+                if stop.load(Ordering::Relaxed) {
+                    return false;
+                }
+                block = returned;
+                thread::sleep(Duration::from_millis(1));
+            }
+        }
+    }
+}
+
+/// Audio source reading a WAV file or a raw PCM stream
+///
+/// The data is decoded to f32, reduced to mono and delivered in 10ms blocks,
+/// either in real time or as fast as the pipeline accepts it. A source can
+/// only be played once: the underlying reader is consumed by
+/// [`start`](AudioSource::start). [`stop`](AudioSource::stop) does not wait
+/// for a read in progress, so an idle stdin or pipe cannot block it.
+///
+/// # Example
+/// ```no_run
+/// use raflow_lib::audio::source::{FileSource, Pacing, PcmFormat};
+/// use raflow_lib::audio::AudioPipeline;
+///
+/// // A recorded WAV file, as fast as possible
+/// let source = FileSource::open_wav("meeting.wav").unwrap().with_pacing(Pacing::Fast);
+/// let pipeline = AudioPipeline::from_source(Box::new(source));
+///
+/// // 16 kHz mono s16le from another program: `arecord -f S16_LE -r 16000 | raflow`
+/// let source = FileSource::stdin(PcmFormat::new(16000, 1));
+/// ```
+pub struct FileSource {
+    /// Reader positioned at the first sample (None once started)
+    reader: Option<Box<dyn Read + Send>>,
+    /// Layout of the PCM data
+    format: PcmFormat,
+    /// Delivery speed
+    pacing: Pacing,
+    /// How multi-channel data is reduced to mono
+    channel_mode: ChannelMode,
+    /// Feeding thread (None when stopped)
+    feeder: Option<Feeder>,
+}
+
+impl FileSource {
+    /// Open a WAV file
+    ///
+    /// # Errors
+    /// Returns `AudioError::Io` if the file cannot be read, or
+    /// `AudioError::UnsupportedFormat` if it is not a supported WAV file.
+    pub fn open_wav(path: impl AsRef<Path>) -> AudioResult<Self> {
+        let path = path.as_ref();
+        info!("Opening WAV file: {}", path.display());
+        Self::from_wav_reader(BufReader::new(File::open(path)?))
+    }
+
+    /// Open a headerless PCM file with the given layout
+    ///
+    /// # Errors
+    /// Returns `AudioError::Io` if the file cannot be opened.
+    pub fn open_raw(path: impl AsRef<Path>, format: PcmFormat) -> AudioResult<Self> {
+        let path = path.as_ref();
+        info!("Opening raw PCM file: {} ({:?})", path.display(), format);
+        Ok(Self::from_raw_reader(
+            BufReader::new(File::open(path)?),
+            format,
+        ))
+    }
+
+    /// Read headerless PCM from standard input
+    pub fn stdin(format: PcmFormat) -> Self {
+        info!("Reading raw PCM from stdin ({:?})", format);
+        Self::from_raw_reader(io::stdin(), format)
+    }
+
+    /// Read a WAV stream from any reader
+    ///
+    /// # Errors
+    /// Returns `AudioError::UnsupportedFormat` if the header is not a
+    /// supported WAV header.
+    pub fn from_wav_reader<R: Read + Send + 'static>(mut reader: R) -> AudioResult<Self> {
+        let (format, length) = read_wav_header(&mut reader)?;
+        info!("WAV format: {:?}, {:?} data bytes", format, length);
+
+        let reader: Box<dyn Read + Send> = match length {
+            Some(length) => Box::new(reader.take(length)),
+            None => Box::new(reader),
+        };
+
+        Ok(Self::with_reader(reader, format))
+    }
+
+    /// Read headerless PCM with the given layout from any reader
+    pub fn from_raw_reader<R: Read + Send + 'static>(reader: R, format: PcmFormat) -> Self {
+        Self::with_reader(Box::new(reader), format)
+    }
+
+    fn with_reader(reader: Box<dyn Read + Send>, format: PcmFormat) -> Self {
+        Self {
+            reader: Some(reader),
+            format,
+            pacing: Pacing::default(),
+            channel_mode: ChannelMode::Downmix,
+            feeder: None,
+        }
+    }
+
+    /// Set the delivery speed (real time by default)
+    pub fn with_pacing(mut self, pacing: Pacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// Set how multi-channel data is reduced to mono
+    pub fn with_channel_mode(mut self, mode: ChannelMode) -> Self {
+        AudioSource::set_channel_mode(&mut self, mode);
+        self
+    }
+
+    /// Get the layout of the PCM data
+    pub fn format(&self) -> PcmFormat {
+        self.format
+    }
+}
+
+impl AudioSource for FileSource {
+    fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+        if self.feeder.is_some() {
+            warn!("File source already started");
+            return Ok(());
+        }
+
+        let mut reader = self.reader.take().ok_or_else(|| {
+            AudioError::StreamBuildFailed("File source has already been played".to_string())
+        })?;
+
+        let format = self.format;
+        let channel_mode = self.channel_mode;
+        let frames_per_block = (format.sample_rate * BLOCK_MS / 1000).max(1) as usize;
+        let mut bytes = vec![0u8; frames_per_block * format.frame_bytes()];
+
+        info!(
+            "Starting file source ({} Hz, {} channels, {:?})",
+            format.sample_rate, format.channels, self.pacing
+        );
+
+        let feeder = Feeder::spawn(
+            "raflow-file-source",
+            format.sample_rate,
+            self.pacing,
+            sender,
+            move || {
+                let filled = match read_full(&mut reader, &mut bytes) {
+                    Ok(filled) => filled,
+                    Err(e) => {
+                        error!("Failed to read audio data: {}", e);
+                        return None;
+                    }
+                };
+                let whole_frames = filled - filled % format.frame_bytes();
+                if whole_frames == 0 {
+                    return None;
+                }
+
+                let samples = format.decode(&bytes[..whole_frames]);
+                Some(channel_mode.to_mono(&samples, format.channels))
+            },
+        )?
+        .detach_on_stop();
+
+        self.feeder = Some(feeder);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut feeder) = self.feeder.take() {
+            info!("Stopping file source");
+            feeder.stop();
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.format.sample_rate
+    }
+
+    fn is_active(&self) -> bool {
+        self.feeder.is_some()
+    }
+
+    fn set_channel_mode(&mut self, mode: ChannelMode) {
+        self.channel_mode = mode.resolve(self.format.channels);
+    }
+}
+
+impl Drop for FileSource {
+    fn drop(&mut self) {
+        AudioSource::stop(self);
+    }
+}
+
+/// Fill `buffer` as far as possible, stopping early only at end of stream
+fn read_full<R: Read + ?Sized>(reader: &mut R, buffer: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+/// A segment of generated audio
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Signal {
+    /// Digital silence
+    Silence,
+
+    /// Sine wave
+    Tone {
+        /// Frequency in Hz
+        frequency: f32,
+        /// Peak amplitude (0.0 - 1.0)
+        amplitude: f32,
+    },
+
+    /// Deterministic white noise
+    Noise {
+        /// Peak amplitude (0.0 - 1.0)
+        amplitude: f32,
+    },
+}
+
+/// Audio source producing generated signals
+///
+/// Plays a sequence of [`Signal`] segments once, then ends the stream. The
+/// output is deterministic, which makes it suitable for headless tests of
+/// the whole pipeline.
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::source::{Pacing, Signal, SyntheticSource};
+/// use std::time::Duration;
+///
+/// // Half a second of "speech" followed by a second of silence
+/// let source = SyntheticSource::new(48000)
+///     .with_segment(
+///         Signal::Tone { frequency: 440.0, amplitude: 0.5 },
+///         Duration::from_millis(500),
+///     )
+///     .with_segment(Signal::Silence, Duration::from_secs(1))
+///     .with_pacing(Pacing::Fast);
+///
+/// assert_eq!(source.render().len(), 72000);
+/// ```
+pub struct SyntheticSource {
+    /// Sample rate of the generated audio
+    sample_rate: u32,
+    /// Segments played in order
+    segments: Vec<(Signal, Duration)>,
+    /// Delivery speed
+    pacing: Pacing,
+    /// Feeding thread (None when stopped)
+    feeder: Option<Feeder>,
+}
+
+impl SyntheticSource {
+    /// Create a source without any segments
+    pub fn new(sample_rate: u32) -> Self {
+        Self {
+            sample_rate: sample_rate.max(1),
+            segments: Vec::new(),
+            pacing: Pacing::default(),
+            feeder: None,
+        }
+    }
+
+    /// Append a segment
+    pub fn with_segment(mut self, signal: Signal, duration: Duration) -> Self {
+        self.segments.push((signal, duration));
+        self
+    }
+
+    /// Set the delivery speed (real time by default)
+    pub fn with_pacing(mut self, pacing: Pacing) -> Self {
+        self.pacing = pacing;
+        self
+    }
+
+    /// Total duration of all segments
+    pub fn duration(&self) -> Duration {
+        self.segments.iter().map(|(_, duration)| *duration).sum()
+    }
+
+    /// Generate all samples at once
+    pub fn render(&self) -> Vec<f32> {
+        let mut generator = SignalGenerator::new(self.sample_rate, self.segments.clone());
+        let mut samples = Vec::new();
+        while let Some(block) = generator.next_block(self.block_len()) {
+            samples.extend(block);
+        }
+        samples
+    }
+
+    fn block_len(&self) -> usize {
+        (self.sample_rate * BLOCK_MS / 1000).max(1) as usize
+    }
+}
+
+impl AudioSource for SyntheticSource {
+    fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+        if self.feeder.is_some() {
+            warn!("Synthetic source already started");
+            return Ok(());
+        }
+
+        info!(
+            "Starting synthetic source ({} Hz, {:?}, {:?})",
+            self.sample_rate,
+            self.duration(),
+            self.pacing
+        );
+
+        let mut generator = SignalGenerator::new(self.sample_rate, self.segments.clone());
+        let block_len = self.block_len();
+
+        self.feeder = Some(Feeder::spawn(
+            "raflow-synthetic-source",
+            self.sample_rate,
+            self.pacing,
+            sender,
+            move || generator.next_block(block_len),
+        )?);
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if let Some(mut feeder) = self.feeder.take() {
+            info!("Stopping synthetic source");
+            feeder.stop();
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn is_active(&self) -> bool {
+        self.feeder.is_some()
+    }
+}
+
+impl Drop for SyntheticSource {
+    fn drop(&mut self) {
+        AudioSource::stop(self);
+    }
+}
+
+/// Sample-by-sample generator for a list of segments
+struct SignalGenerator {
+    sample_rate: u32,
+    /// Remaining segments as (signal, samples left)
+    segments: Vec<(Signal, u64)>,
+    /// Index of the current segment
+    current: usize,
+    /// Sine phase in radians, continuous across blocks
+    phase: f32,
+    /// Noise generator state
+    seed: u32,
+}
+
+impl SignalGenerator {
+    fn new(sample_rate: u32, segments: Vec<(Signal, Duration)>) -> Self {
+        let segments = segments
+            .into_iter()
+            .map(|(signal, duration)| {
+                let samples = (duration.as_secs_f64() * sample_rate as f64).round() as u64;
+                (signal, samples)
+            })
+            .collect();
+
+        Self {
+            sample_rate,
+            segments,
+            current: 0,
+            phase: 0.0,
+            seed: 0x1234_5678,
+        }
+    }
+
+    /// Generate up to `max_len` samples; None once every segment is done
+    fn next_block(&mut self, max_len: usize) -> Option<Vec<f32>> {
+        let mut block = Vec::with_capacity(max_len);
+
+        while block.len() < max_len {
+            let Some((signal, remaining)) = self.segments.get_mut(self.current) else {
+                break;
+            };
+            if *remaining == 0 {
+                self.current += 1;
+                self.phase = 0.0;
+                continue;
+            }
+
+            let count = (*remaining).min((max_len - block.len()) as u64);
+            *remaining -= count;
+            let signal = *signal;
+
+            for _ in 0..count {
+                let sample = self.sample(signal);
+                block.push(sample);
+            }
+        }
+
+        if block.is_empty() { None } else { Some(block) }
+    }
+
+    fn sample(&mut self, signal: Signal) -> f32 {
+        match signal {
+            Signal::Silence => 0.0,
+            Signal::Tone {
+                frequency,
+                amplitude,
+            } => {
+                let value = self.phase.sin() * amplitude;
+                let step = 2.0 * std::f32::consts::PI * frequency / self.sample_rate as f32;
+                self.phase = (self.phase + step) % (2.0 * std::f32::consts::PI);
+                value
+            }
+            Signal::Noise { amplitude } => {
+                // Linear congruential generator, uniform in [-1.0, 1.0)
+                self.seed = self
+                    .seed
+                    .wrapping_mul(1_664_525)
+                    .wrapping_add(1_013_904_223);
+                ((self.seed >> 8) as f32 / (1u32 << 23) as f32 - 1.0) * amplitude
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    /// Build a WAV file in memory
+    fn wav_bytes(
+        format_tag: u16,
+        channels: u16,
+        sample_rate: u32,
+        bits: u16,
+        data: &[u8],
+    ) -> Vec<u8> {
+        let block_align = channels * bits / 8;
+        let mut bytes = Vec::new();
+        bytes.extend_from_slice(b"RIFF");
+        bytes.extend_from_slice(&(36 + data.len() as u32 + 12).to_le_bytes());
+        bytes.extend_from_slice(b"WAVE");
+        bytes.extend_from_slice(b"fmt ");
+        bytes.extend_from_slice(&16u32.to_le_bytes());
+        bytes.extend_from_slice(&format_tag.to_le_bytes());
+        bytes.extend_from_slice(&channels.to_le_bytes());
+        bytes.extend_from_slice(&sample_rate.to_le_bytes());
+        bytes.extend_from_slice(&(sample_rate * block_align as u32).to_le_bytes());
+        bytes.extend_from_slice(&block_align.to_le_bytes());
+        bytes.extend_from_slice(&bits.to_le_bytes());
+        // An unrelated chunk before the data must be skipped
+        bytes.extend_from_slice(b"LIST");
+        bytes.extend_from_slice(&3u32.to_le_bytes());
+        bytes.extend_from_slice(&[1, 2, 3, 0]);
+        bytes.extend_from_slice(b"data");
+        bytes.extend_from_slice(&(data.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(data);
+        bytes
+    }
+
+    fn i16_bytes(samples: &[i16]) -> Vec<u8> {
+        samples.iter().flat_map(|s| s.to_le_bytes()).collect()
+    }
+
+    /// Collect everything a source sends until it ends the stream
+    async fn drain(mut source: impl AudioSource) -> Vec<f32> {
+        let (tx, mut rx) = mpsc::channel(4);
+        source.start(tx).unwrap();
+
+        let mut samples = Vec::new();
+        while let Some(block) = rx.recv().await {
+            samples.extend(block);
+        }
+        source.stop();
+        samples
+    }
+
+    #[test]
+    fn test_sample_encoding_decode() {
+        assert_eq!(SampleEncoding::U8.decode(&[0]), -1.0);
+        assert_eq!(SampleEncoding::U8.decode(&[128]), 0.0);
+        assert_eq!(SampleEncoding::I16.decode(&i16::MIN.to_le_bytes()), -1.0);
+        assert_eq!(SampleEncoding::I16.decode(&16384i16.to_le_bytes()), 0.5);
+        assert_eq!(SampleEncoding::I24.decode(&[0x00, 0x00, 0x80]), -1.0);
+        assert_eq!(SampleEncoding::I24.decode(&[0x00, 0x00, 0x40]), 0.5);
+        assert_eq!(SampleEncoding::I32.decode(&i32::MIN.to_le_bytes()), -1.0);
+        assert_eq!(SampleEncoding::F32.decode(&0.25f32.to_le_bytes()), 0.25);
+    }
+
+    #[test]
+    fn test_read_wav_header() {
+        let data = i16_bytes(&[0, 100, -100, 0]);
+        let bytes = wav_bytes(1, 2, 44100, 16, &data);
+
+        let mut reader = Cursor::new(bytes);
+        let (format, length) = read_wav_header(&mut reader).unwrap();
+
+        assert_eq!(format, PcmFormat::new(44100, 2));
+        assert_eq!(length, Some(8));
+        assert_eq!(reader.position() as usize, reader.get_ref().len() - 8);
+    }
+
+    #[test]
+    fn test_read_wav_header_float_and_unsupported() {
+        let bytes = wav_bytes(3, 1, 16000, 32, &[]);
+        let (format, _) = read_wav_header(&mut Cursor::new(bytes)).unwrap();
+        assert_eq!(format.encoding, SampleEncoding::F32);
+
+        // A-law is not supported
+        let bytes = wav_bytes(6, 1, 8000, 8, &[]);
+        assert!(matches!(
+            read_wav_header(&mut Cursor::new(bytes)),
+            Err(AudioError::UnsupportedFormat(_))
+        ));
+
+        assert!(matches!(
+            read_wav_header(&mut Cursor::new(b"not a wav file at all".to_vec())),
+            Err(AudioError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[test]
+    fn test_read_wav_header_rejects_oversized_fmt() {
+        let mut bytes = wav_bytes(1, 1, 16000, 16, &[]);
+        // Announce a 4 GiB fmt chunk
+        bytes[16..20].copy_from_slice(&u32::MAX.to_le_bytes());
+
+        assert!(matches!(
+            read_wav_header(&mut Cursor::new(bytes)),
+            Err(AudioError::UnsupportedFormat(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_wav_source_decodes_and_downmixes() {
+        // Stereo frames: (L, R)
+        let data = i16_bytes(&[16384, 0, -16384, -16384, 0, 16384]);
+        let bytes = wav_bytes(1, 2, 16000, 16, &data);
+
+        let source = FileSource::from_wav_reader(Cursor::new(bytes))
+            .unwrap()
+            .with_pacing(Pacing::Fast);
+        assert_eq!(source.sample_rate(), 16000);
+
+        assert_eq!(drain(source).await, vec![0.25, -0.5, 0.25]);
+    }
+
+    #[tokio::test]
+    async fn test_raw_source_select_channel() {
+        let data = i16_bytes(&[16384, 0, -16384, 8192]);
+        let source = FileSource::from_raw_reader(Cursor::new(data), PcmFormat::new(8000, 2))
+            .with_channel_mode(ChannelMode::Select(1))
+            .with_pacing(Pacing::Fast);
+
+        assert_eq!(drain(source).await, vec![0.0, 0.25]);
+    }
+
+    #[tokio::test]
+    async fn test_file_source_plays_once() {
+        let data = i16_bytes(&[1000; 320]);
+        let mut source = FileSource::from_raw_reader(Cursor::new(data), PcmFormat::new(16000, 1))
+            .with_pacing(Pacing::Fast);
+
+        let (tx, _rx) = mpsc::channel(100);
+        assert!(source.start(tx.clone()).is_ok());
+        assert!(source.is_active());
+        source.stop();
+        assert!(!source.is_active());
+
+        assert!(source.start(tx).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stop_does_not_wait_for_blocked_read() {
+        /// Reader that blocks like an idle stdin until the test ends
+        struct IdleReader(std::sync::mpsc::Receiver<()>);
+
+        impl Read for IdleReader {
+            fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+                let _ = self.0.recv();
+                Ok(0)
+            }
+        }
+
+        let (unblock, blocked) = std::sync::mpsc::channel();
+        let mut source = FileSource::from_raw_reader(IdleReader(blocked), PcmFormat::new(16000, 1));
+
+        let (tx, _rx) = mpsc::channel(100);
+        source.start(tx).unwrap();
+
+        let started = Instant::now();
+        source.stop();
+        assert!(started.elapsed() < Duration::from_millis(500));
+        assert!(!source.is_active());
+
+        drop(unblock);
+    }
+
+    #[test]
+    fn test_synthetic_render_is_deterministic() {
+        let source = SyntheticSource::new(16000)
+            .with_segment(
+                Signal::Tone {
+                    frequency: 1000.0,
+                    amplitude: 0.5,
+                },
+                Duration::from_millis(100),
+            )
+            .with_segment(Signal::Silence, Duration::from_millis(50))
+            .with_segment(Signal::Noise { amplitude: 0.1 }, Duration::from_millis(25));
+
+        let samples = source.render();
+        assert_eq!(samples.len(), 1600 + 800 + 400);
+        assert_eq!(samples, source.render());
+        assert_eq!(source.duration(), Duration::from_millis(175));
+
+        let tone = &samples[..1600];
+        let peak = tone.iter().fold(0.0f32, |m, s| m.max(s.abs()));
+        assert!((peak - 0.5).abs() < 0.01);
+        assert!(samples[1600..2400].iter().all(|&s| s == 0.0));
+        assert!(samples[2400..].iter().all(|s| s.abs() <= 0.1));
+        assert!(samples[2400..].iter().any(|&s| s != 0.0));
+    }
+
+    #[tokio::test]
+    async fn test_synthetic_source_ends_stream() {
+        let source = SyntheticSource::new(48000)
+            .with_segment(Signal::Silence, Duration::from_millis(105))
+            .with_pacing(Pacing::Fast);
+
+        assert_eq!(drain(source).await.len(), 5040);
+    }
+
+    #[tokio::test]
+    async fn test_realtime_pacing() {
+        let source =
+            SyntheticSource::new(16000).with_segment(Signal::Silence, Duration::from_millis(200));
+
+        let started = Instant::now();
+        drain(source).await;
+
+        assert!(started.elapsed() >= Duration::from_millis(180));
+    }
+}
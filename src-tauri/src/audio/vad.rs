@@ -0,0 +1,507 @@
+use std::collections::VecDeque;
+use tracing::debug;
+
+/// Default RMS level (0.0 - 1.0) below which a chunk counts as silence
+pub const DEFAULT_VAD_THRESHOLD: f32 = 0.01;
+
+/// Default time to keep streaming after speech drops below the threshold
+pub const DEFAULT_VAD_HANGOVER_MS: u64 = 800;
+
+/// Default amount of silence kept and flushed when speech starts
+pub const DEFAULT_VAD_PRE_ROLL_MS: u64 = 300;
+
+/// Default interval at which a silent chunk is still streamed
+pub const DEFAULT_VAD_KEEPALIVE_MS: u64 = 1000;
+
+/// Voice activity detection settings
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::VadConfig;
+///
+/// let config = VadConfig::new(0.02)
+///     .with_hangover_ms(500)
+///     .with_auto_stop_ms(10_000);
+///
+/// assert!(config.enabled);
+/// assert_eq!(config.auto_stop_silence_ms, Some(10_000));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct VadConfig {
+    /// Whether silence is filtered (disabled streams every chunk, but still
+    /// reports the silence timeout)
+    pub enabled: bool,
+
+    /// RMS level (0.0 - 1.0) at or above which a chunk counts as speech
+    pub threshold: f32,
+
+    /// How long to keep streaming after the level drops below the threshold
+    pub hangover_ms: u64,
+
+    /// How much silence before speech onset is buffered and sent with it
+    pub pre_roll_ms: u64,
+
+    /// Stream one silent chunk at this interval during long silences (0 = never)
+    pub keepalive_interval_ms: u64,
+
+    /// Report a silence timeout after this much continuous silence
+    pub auto_stop_silence_ms: Option<u64>,
+}
+
+impl Default for VadConfig {
+    fn default() -> Self {
+        Self::new(DEFAULT_VAD_THRESHOLD)
+    }
+}
+
+impl VadConfig {
+    /// Create an enabled configuration with the given threshold
+    pub fn new(threshold: f32) -> Self {
+        Self {
+            enabled: true,
+            threshold,
+            hangover_ms: DEFAULT_VAD_HANGOVER_MS,
+            pre_roll_ms: DEFAULT_VAD_PRE_ROLL_MS,
+            keepalive_interval_ms: DEFAULT_VAD_KEEPALIVE_MS,
+            auto_stop_silence_ms: None,
+        }
+    }
+
+    /// Create a configuration that streams every chunk unchanged
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    /// Set the hangover time
+    pub fn with_hangover_ms(mut self, hangover_ms: u64) -> Self {
+        self.hangover_ms = hangover_ms;
+        self
+    }
+
+    /// Set the pre-roll length
+    pub fn with_pre_roll_ms(mut self, pre_roll_ms: u64) -> Self {
+        self.pre_roll_ms = pre_roll_ms;
+        self
+    }
+
+    /// Set the keep-alive interval for silent chunks (0 = never)
+    pub fn with_keepalive_interval_ms(mut self, interval_ms: u64) -> Self {
+        self.keepalive_interval_ms = interval_ms;
+        self
+    }
+
+    /// Report a silence timeout after `silence_ms` of continuous silence
+    pub fn with_auto_stop_ms(mut self, silence_ms: u64) -> Self {
+        self.auto_stop_silence_ms = Some(silence_ms);
+        self
+    }
+}
+
+/// Events reported by the voice activity detector
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VadEvent {
+    /// Level rose above the threshold
+    SpeechStarted,
+
+    /// Level stayed below the threshold for the whole hangover time
+    SpeechEnded,
+
+    /// Continuous silence reached the configured auto-stop limit
+    SilenceTimeout { silence_ms: u64 },
+}
+
+/// Result of feeding one chunk to the detector
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct VadOutput {
+    /// Chunks to stream, in order (pre-roll first)
+    pub chunks: Vec<Vec<i16>>,
+
+    /// Events raised by this chunk
+    pub events: Vec<VadEvent>,
+}
+
+/// Energy-based voice activity detector for 16-bit PCM chunks
+///
+/// Speech is streamed unchanged. After the level drops, chunks keep flowing
+/// for the hangover time so trailing words are not clipped. During silence
+/// chunks are held in a short pre-roll buffer that is flushed when speech
+/// starts again, and only one chunk per keep-alive interval is streamed.
+///
+/// # Example
+/// ```
+/// use raflow_lib::audio::{VadConfig, VadEvent, VoiceActivityDetector};
+///
+/// let mut vad = VoiceActivityDetector::new(VadConfig::default(), 16000);
+///
+/// let output = vad.process(vec![0; 1600]);
+/// assert!(output.chunks.is_empty());
+///
+/// let output = vad.process(vec![8000; 1600]);
+/// assert_eq!(output.events, vec![VadEvent::SpeechStarted]);
+/// ```
+pub struct VoiceActivityDetector {
+    /// Detection settings
+    config: VadConfig,
+    /// Sample rate of the processed chunks
+    sample_rate: u32,
+    /// Whether speech (or its hangover) is in progress
+    is_speaking: bool,
+    /// Continuous silence so far, in milliseconds
+    silence_ms: u64,
+    /// Silence since the last streamed chunk, in milliseconds
+    unsent_ms: u64,
+    /// Silent chunks held back for the next speech onset
+    pre_roll: VecDeque<Vec<i16>>,
+    /// Whether the silence timeout was already reported
+    timed_out: bool,
+}
+
+impl VoiceActivityDetector {
+    /// Create a new detector
+    ///
+    /// # Arguments
+    /// * `config` - Detection settings
+    /// * `sample_rate` - Sample rate of the chunks passed to [`process`](Self::process)
+    pub fn new(config: VadConfig, sample_rate: u32) -> Self {
+        Self {
+            config,
+            sample_rate,
+            is_speaking: false,
+            silence_ms: 0,
+            unsent_ms: 0,
+            pre_roll: VecDeque::new(),
+            timed_out: false,
+        }
+    }
+
+    /// Process one chunk and decide what to stream
+    pub fn process(&mut self, chunk: Vec<i16>) -> VadOutput {
+        let mut output = VadOutput::default();
+
+        let duration_ms = self.duration_ms(chunk.len());
+
+        if !self.config.enabled {
+            if rms_level(&chunk) >= self.config.threshold {
+                self.silence_ms = 0;
+                self.timed_out = false;
+            } else {
+                self.silence_ms += duration_ms;
+            }
+            output.chunks.push(chunk);
+            self.check_timeout(&mut output);
+            return output;
+        }
+
+        if rms_level(&chunk) >= self.config.threshold {
+            self.silence_ms = 0;
+            self.unsent_ms = 0;
+            self.timed_out = false;
+
+            if !self.is_speaking {
+                debug!(
+                    "Speech started ({} ms pre-roll)",
+                    self.pre_roll_duration_ms()
+                );
+                self.is_speaking = true;
+                output.events.push(VadEvent::SpeechStarted);
+                output.chunks.extend(self.pre_roll.drain(..));
+            }
+
+            output.chunks.push(chunk);
+            return output;
+        }
+
+        self.silence_ms += duration_ms;
+
+        if self.is_speaking {
+            // Hangover: keep streaming so trailing words are not clipped
+            output.chunks.push(chunk);
+
+            if self.silence_ms >= self.config.hangover_ms {
+                debug!("Speech ended after {} ms of silence", self.silence_ms);
+                self.is_speaking = false;
+                self.unsent_ms = 0;
+                output.events.push(VadEvent::SpeechEnded);
+            }
+        } else {
+            self.unsent_ms += duration_ms;
+
+            let interval = self.config.keepalive_interval_ms;
+            if interval > 0 && self.unsent_ms >= interval {
+                // The pre-roll now starts after this chunk, so the stream stays contiguous
+                self.pre_roll.clear();
+                self.unsent_ms = 0;
+                output.chunks.push(chunk);
+            } else {
+                self.push_pre_roll(chunk);
+            }
+        }
+
+        self.check_timeout(&mut output);
+        output
+    }
+
+    /// Report the silence timeout once the limit is reached
+    fn check_timeout(&mut self, output: &mut VadOutput) {
+        if let Some(limit) = self.config.auto_stop_silence_ms
+            && !self.timed_out
+            && self.silence_ms >= limit
+        {
+            debug!("Silence timeout after {} ms", self.silence_ms);
+            self.timed_out = true;
+            output.events.push(VadEvent::SilenceTimeout {
+                silence_ms: self.silence_ms,
+            });
+        }
+    }
+
+    /// Whether speech (or its hangover) is in progress
+    pub fn is_speaking(&self) -> bool {
+        self.is_speaking
+    }
+
+    /// Continuous silence so far, in milliseconds
+    pub fn silence_ms(&self) -> u64 {
+        self.silence_ms
+    }
+
+    /// Get the detection settings
+    pub fn config(&self) -> &VadConfig {
+        &self.config
+    }
+
+    /// Reset the detector to its initial (silent) state
+    pub fn reset(&mut self) {
+        self.is_speaking = false;
+        self.silence_ms = 0;
+        self.unsent_ms = 0;
+        self.pre_roll.clear();
+        self.timed_out = false;
+    }
+
+    /// Add a silent chunk to the pre-roll, dropping the oldest beyond the limit
+    fn push_pre_roll(&mut self, chunk: Vec<i16>) {
+        self.pre_roll.push_back(chunk);
+
+        while self.pre_roll_duration_ms() > self.config.pre_roll_ms {
+            self.pre_roll.pop_front();
+        }
+    }
+
+    /// Duration of the buffered pre-roll, in milliseconds
+    fn pre_roll_duration_ms(&self) -> u64 {
+        self.pre_roll
+            .iter()
+            .map(|chunk| self.duration_ms(chunk.len()))
+            .sum()
+    }
+
+    /// Duration of `samples` mono samples, in milliseconds
+    fn duration_ms(&self, samples: usize) -> u64 {
+        if self.sample_rate == 0 {
+            return 0;
+        }
+        samples as u64 * 1000 / self.sample_rate as u64
+    }
+}
+
+/// Compute the RMS level of 16-bit samples, normalized to 0.0 - 1.0
+pub fn rms_level(samples: &[i16]) -> f32 {
+    if samples.is_empty() {
+        return 0.0;
+    }
+
+    let sum: f64 = samples
+        .iter()
+        .map(|&s| {
+            let v = s as f64 / 32768.0;
+            v * v
+        })
+        .sum();
+
+    (sum / samples.len() as f64).sqrt() as f32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CHUNK: usize = 1600; // 100ms @ 16kHz
+
+    fn silence() -> Vec<i16> {
+        vec![0; CHUNK]
+    }
+
+    fn speech() -> Vec<i16> {
+        (0..CHUNK)
+            .map(|i| if i % 2 == 0 { 8000 } else { -8000 })
+            .collect()
+    }
+
+    fn detector(config: VadConfig) -> VoiceActivityDetector {
+        VoiceActivityDetector::new(config, 16000)
+    }
+
+    #[test]
+    fn test_rms_level() {
+        assert_eq!(rms_level(&[]), 0.0);
+        assert_eq!(rms_level(&silence()), 0.0);
+
+        let level = rms_level(&speech());
+        assert!((level - 8000.0 / 32768.0).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_disabled_streams_everything() {
+        let mut vad = detector(VadConfig::disabled());
+
+        for _ in 0..20 {
+            let output = vad.process(silence());
+            assert_eq!(output.chunks.len(), 1);
+            assert!(output.events.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_disabled_still_reports_silence_timeout() {
+        let mut vad = detector(VadConfig::disabled().with_auto_stop_ms(500));
+
+        vad.process(speech());
+        let outputs: Vec<VadOutput> = (0..10).map(|_| vad.process(silence())).collect();
+
+        assert!(outputs.iter().all(|output| output.chunks.len() == 1));
+        let events: Vec<VadEvent> = outputs.into_iter().flat_map(|o| o.events).collect();
+        assert_eq!(events, vec![VadEvent::SilenceTimeout { silence_ms: 500 }]);
+    }
+
+    #[test]
+    fn test_silence_is_skipped() {
+        let mut vad = detector(VadConfig::default().with_keepalive_interval_ms(0));
+
+        let sent: usize = (0..50).map(|_| vad.process(silence()).chunks.len()).sum();
+
+        assert_eq!(sent, 0);
+        assert!(!vad.is_speaking());
+        assert_eq!(vad.silence_ms(), 5000);
+    }
+
+    #[test]
+    fn test_silence_is_thinned_by_keepalive() {
+        let mut vad = detector(VadConfig::default().with_keepalive_interval_ms(1000));
+
+        let sent: usize = (0..50).map(|_| vad.process(silence()).chunks.len()).sum();
+
+        // One chunk per second of silence
+        assert_eq!(sent, 5);
+    }
+
+    #[test]
+    fn test_speech_start_flushes_pre_roll() {
+        let mut vad = detector(
+            VadConfig::default()
+                .with_pre_roll_ms(300)
+                .with_keepalive_interval_ms(0),
+        );
+
+        for _ in 0..10 {
+            vad.process(silence());
+        }
+
+        let output = vad.process(speech());
+
+        assert_eq!(output.events, vec![VadEvent::SpeechStarted]);
+        // 3 pre-roll chunks followed by the speech chunk
+        assert_eq!(output.chunks.len(), 4);
+        assert_eq!(output.chunks[3], speech());
+        assert!(vad.is_speaking());
+    }
+
+    #[test]
+    fn test_hangover_then_speech_end() {
+        let mut vad = detector(
+            VadConfig::default()
+                .with_hangover_ms(300)
+                .with_keepalive_interval_ms(0),
+        );
+
+        vad.process(speech());
+
+        let first = vad.process(silence());
+        let second = vad.process(silence());
+        assert_eq!(first.chunks.len(), 1);
+        assert_eq!(second.chunks.len(), 1);
+        assert!(first.events.is_empty() && second.events.is_empty());
+
+        let third = vad.process(silence());
+        assert_eq!(third.chunks.len(), 1);
+        assert_eq!(third.events, vec![VadEvent::SpeechEnded]);
+        assert!(!vad.is_speaking());
+
+        // Past the hangover, silence is held back again
+        assert!(vad.process(silence()).chunks.is_empty());
+    }
+
+    #[test]
+    fn test_short_pause_does_not_end_speech() {
+        let mut vad = detector(VadConfig::default().with_hangover_ms(500));
+
+        vad.process(speech());
+        vad.process(silence());
+        vad.process(silence());
+        let output = vad.process(speech());
+
+        assert!(output.events.is_empty());
+        assert_eq!(output.chunks.len(), 1);
+        assert!(vad.is_speaking());
+    }
+
+    #[test]
+    fn test_silence_timeout_reported_once() {
+        let mut vad = detector(VadConfig::default().with_auto_stop_ms(1000));
+
+        let events: Vec<VadEvent> = (0..30)
+            .flat_map(|_| vad.process(silence()).events)
+            .collect();
+
+        assert_eq!(events, vec![VadEvent::SilenceTimeout { silence_ms: 1000 }]);
+    }
+
+    #[test]
+    fn test_silence_timeout_restarts_after_speech() {
+        let mut vad = detector(
+            VadConfig::default()
+                .with_hangover_ms(200)
+                .with_auto_stop_ms(500),
+        );
+
+        vad.process(speech());
+        let events: Vec<VadEvent> = (0..5).flat_map(|_| vad.process(silence()).events).collect();
+
+        assert_eq!(
+            events,
+            vec![
+                VadEvent::SpeechEnded,
+                VadEvent::SilenceTimeout { silence_ms: 500 }
+            ]
+        );
+
+        vad.process(speech());
+        let events: Vec<VadEvent> = (0..5).flat_map(|_| vad.process(silence()).events).collect();
+        assert!(events.contains(&VadEvent::SilenceTimeout { silence_ms: 500 }));
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut vad = detector(VadConfig::default());
+
+        vad.process(speech());
+        assert!(vad.is_speaking());
+
+        vad.reset();
+        assert!(!vad.is_speaking());
+        assert_eq!(vad.silence_ms(), 0);
+    }
+}
@@ -0,0 +1,443 @@
+use crate::audio::buffer::{AudioBufferConsumer, AudioBufferProducer, AudioRingBuffer};
+use crate::audio::error::{AudioError, AudioResult};
+use crate::audio::{AudioSource, ChannelMode, DeviceEvent, FailoverPolicy, SupervisedCapture};
+use std::sync::{Arc, Mutex};
+use std::thread::{self, JoinHandle};
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tracing::{debug, info, warn};
+
+/// Default amount of audio kept from before a session starts, in milliseconds
+pub const DEFAULT_WARM_PRE_ROLL_MS: u64 = 500;
+
+/// Microphone that stays open between sessions
+///
+/// While idle, captured audio goes into a rolling [`AudioRingBuffer`] holding
+/// the last `pre_roll_ms` of audio. When a session starts its
+/// [`PreRollSource`], the buffered audio is sent first and live audio follows
+/// without a gap, so a word spoken right on key-down is not clipped. When the
+/// session stops, audio goes back into the ring buffer.
+///
+/// Device errors and changes of the microphone go to the active session
+/// (see [`AudioSource::set_device_events`]); while idle they are only logged.
+/// If the device is lost for good, the active session's stream ends and
+/// [`is_running`](Self::is_running) turns false.
+///
+/// # Example
+/// ```no_run
+/// use raflow_lib::audio::{AudioPipeline, ChannelMode, FailoverPolicy, WarmMicrophone};
+///
+/// // Keep the default microphone open with half a second of pre-roll
+/// let warm =
+///     WarmMicrophone::open(None, ChannelMode::Downmix, 500, FailoverPolicy::default()).unwrap();
+///
+/// // Each session gets a new handle; the microphone stays open
+/// let pipeline = AudioPipeline::from_source(Box::new(warm.source()));
+/// ```
+pub struct WarmMicrophone {
+    /// The always-running source
+    source: Box<dyn AudioSource>,
+    /// State shared with the routing thread and session handles
+    router: Arc<Mutex<Router>>,
+    /// Routing thread (ends when the source stops)
+    thread: Option<JoinHandle<()>>,
+    /// Device the microphone was opened with (None for the default device)
+    device_id: Option<String>,
+    /// Channel mode the microphone was opened with
+    channel_mode: ChannelMode,
+    /// Pre-roll length in milliseconds
+    pre_roll_ms: u64,
+    /// Failover policy the microphone was opened with
+    failover: FailoverPolicy,
+}
+
+impl WarmMicrophone {
+    /// Open a capture device and keep it running
+    ///
+    /// # Errors
+    /// Returns error if the device cannot be opened or started.
+    pub fn open(
+        device_id: Option<&str>,
+        channel_mode: ChannelMode,
+        pre_roll_ms: u64,
+        failover: FailoverPolicy,
+    ) -> AudioResult<Self> {
+        let capture = SupervisedCapture::new(device_id)?
+            .with_channel_mode(channel_mode)
+            .with_failover(failover.clone());
+
+        let mut warm = Self::from_source(Box::new(capture), pre_roll_ms)?;
+        warm.device_id = device_id.map(str::to_string);
+        warm.channel_mode = channel_mode;
+        warm.failover = failover;
+        Ok(warm)
+    }
+
+    /// Keep `source` running and buffer its last `pre_roll_ms` of audio
+    ///
+    /// # Errors
+    /// Returns error if the source cannot be started.
+    pub fn from_source(mut source: Box<dyn AudioSource>, pre_roll_ms: u64) -> AudioResult<Self> {
+        let sample_rate = source.sample_rate();
+        let capacity = (sample_rate as u64 * pre_roll_ms / 1000).max(1) as usize;
+        let (producer, consumer) = AudioRingBuffer::new(capacity);
+
+        let router = Arc::new(Mutex::new(Router {
+            producer,
+            consumer,
+            target: None,
+            device_events: None,
+        }));
+
+        let (events_tx, mut events) = mpsc::channel::<DeviceEvent>(8);
+        source.set_device_events(events_tx);
+        let (tx, mut rx) = mpsc::channel::<Vec<f32>>(100);
+        source.start(tx)?;
+
+        let thread_router = Arc::clone(&router);
+        let thread = thread::Builder::new()
+            .name("raflow-warm-microphone".to_string())
+            .spawn(move || {
+                while let Some(block) = rx.blocking_recv() {
+                    if let Ok(mut router) = thread_router.lock() {
+                        router.route(block);
+                        while let Ok(event) = events.try_recv() {
+                            router.report(event);
+                        }
+                    }
+                }
+                debug!("Warm microphone input ended");
+
+                // Let the active session see the end of its stream
+                if let Ok(mut router) = thread_router.lock() {
+                    while let Ok(event) = events.try_recv() {
+                        router.report(event);
+                    }
+                    router.target = None;
+                }
+            })
+            .map_err(|e| AudioError::StreamBuildFailed(e.to_string()))?;
+
+        info!(
+            "Warm microphone started ({} Hz, {} ms pre-roll)",
+            sample_rate, pre_roll_ms
+        );
+
+        Ok(Self {
+            source,
+            router,
+            thread: Some(thread),
+            device_id: None,
+            channel_mode: ChannelMode::Downmix,
+            pre_roll_ms,
+            failover: FailoverPolicy::default(),
+        })
+    }
+
+    /// Create a source for one session
+    ///
+    /// Only one handle receives audio at a time: starting a handle detaches
+    /// the previous one.
+    pub fn source(&self) -> PreRollSource {
+        PreRollSource {
+            router: Arc::clone(&self.router),
+            sample_rate: self.source.sample_rate(),
+            device_events: None,
+            active: false,
+        }
+    }
+
+    /// Whether the microphone was opened with these settings
+    pub fn matches(
+        &self,
+        device_id: Option<&str>,
+        channel_mode: ChannelMode,
+        pre_roll_ms: u64,
+        failover: &FailoverPolicy,
+    ) -> bool {
+        self.device_id.as_deref() == device_id
+            && self.channel_mode == channel_mode
+            && self.pre_roll_ms == pre_roll_ms
+            && &self.failover == failover
+    }
+
+    /// Whether the microphone still delivers audio
+    ///
+    /// False once the source has ended, for example because its device was
+    /// unplugged and no replacement could be opened.
+    pub fn is_running(&self) -> bool {
+        self.thread
+            .as_ref()
+            .is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Number of samples currently buffered as pre-roll
+    pub fn buffered_samples(&self) -> usize {
+        self.router
+            .lock()
+            .map(|router| router.consumer.available_samples())
+            .unwrap_or(0)
+    }
+
+    /// Sample rate of the captured audio
+    pub fn sample_rate(&self) -> u32 {
+        self.source.sample_rate()
+    }
+}
+
+impl Drop for WarmMicrophone {
+    fn drop(&mut self) {
+        info!("Closing warm microphone");
+        self.source.stop();
+
+        // Dropping the stream drops its sender, which ends the routing thread
+        if let Some(thread) = self.thread.take()
+            && thread.join().is_err()
+        {
+            warn!("Warm microphone thread panicked");
+        }
+    }
+}
+
+/// Where captured audio currently goes
+struct Router {
+    /// Pre-roll writer
+    producer: AudioBufferProducer,
+    /// Pre-roll reader, used to drop the oldest samples and to flush
+    consumer: AudioBufferConsumer,
+    /// Active session (None while idle)
+    target: Option<mpsc::Sender<Vec<f32>>>,
+    /// Device event channel of the active session
+    device_events: Option<mpsc::Sender<DeviceEvent>>,
+}
+
+impl Router {
+    /// Forward a block to the active session or keep it as pre-roll
+    fn route(&mut self, block: Vec<f32>) {
+        if let Some(target) = &self.target {
+            match target.try_send(block) {
+                Ok(()) => {}
+                // Same policy as the capture callback: drop rather than block
+                Err(TrySendError::Full(_)) => {}
+                Err(TrySendError::Closed(block)) => {
+                    debug!("Session closed, buffering pre-roll again");
+                    self.target = None;
+                    self.buffer(&block);
+                }
+            }
+        } else {
+            self.buffer(&block);
+        }
+    }
+
+    /// Pass a device event to the active session, or log it while idle
+    fn report(&mut self, event: DeviceEvent) {
+        if let Some(events) = &self.device_events
+            && !events.is_closed()
+        {
+            if events.try_send(event).is_err() {
+                warn!("Dropping device event: session channel full");
+            }
+            return;
+        }
+
+        match event {
+            DeviceEvent::Error(error) => warn!("Warm microphone error: {}", error),
+            DeviceEvent::Lost(error) => warn!("Warm microphone lost: {}", error),
+            DeviceEvent::Switched { device, .. } => {
+                info!("Warm microphone switched to {}", device)
+            }
+        }
+    }
+
+    /// Append to the ring buffer, dropping the oldest samples when full
+    fn buffer(&mut self, block: &[f32]) {
+        let capacity = self.producer.capacity();
+        let block = &block[block.len().saturating_sub(capacity)..];
+
+        let missing = block.len().saturating_sub(self.producer.available_space());
+        if missing > 0 {
+            self.consumer.skip(missing);
+        }
+        self.producer.push_slice(block);
+    }
+}
+
+/// Session handle for a [`WarmMicrophone`]
+///
+/// Starting it sends the buffered pre-roll, then live audio. Stopping it
+/// returns the microphone to buffering instead of closing it.
+pub struct PreRollSource {
+    router: Arc<Mutex<Router>>,
+    sample_rate: u32,
+    device_events: Option<mpsc::Sender<DeviceEvent>>,
+    active: bool,
+}
+
+impl AudioSource for PreRollSource {
+    fn start(&mut self, sender: mpsc::Sender<Vec<f32>>) -> AudioResult<()> {
+        let mut router = self
+            .router
+            .lock()
+            .map_err(|_| AudioError::StreamError("Warm microphone lock poisoned".to_string()))?;
+
+        let pre_roll = router.consumer.pop_all();
+        debug!(
+            "Flushing {} ms of pre-roll",
+            pre_roll.len() as u64 * 1000 / self.sample_rate.max(1) as u64
+        );
+        if !pre_roll.is_empty() && sender.try_send(pre_roll).is_err() {
+            warn!("Dropping pre-roll: session channel full or closed");
+        }
+
+        // Later blocks follow the pre-roll in order: routing holds the same lock
+        router.target = Some(sender);
+        router.device_events = self.device_events.clone();
+        self.active = true;
+        Ok(())
+    }
+
+    fn stop(&mut self) {
+        if !self.active {
+            return;
+        }
+        self.active = false;
+
+        if let Ok(mut router) = self.router.lock() {
+            router.target = None;
+            router.device_events = None;
+        }
+    }
+
+    fn sample_rate(&self) -> u32 {
+        self.sample_rate
+    }
+
+    fn is_active(&self) -> bool {
+        self.active
+    }
+
+    fn set_device_events(&mut self, events: mpsc::Sender<DeviceEvent>) {
+        self.device_events = Some(events);
+    }
+}
+
+impl Drop for PreRollSource {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::audio::{Pacing, Signal, SyntheticSource};
+    use std::time::Duration;
+
+    fn router(capacity: usize) -> Router {
+        let (producer, consumer) = AudioRingBuffer::new(capacity);
+        Router {
+            producer,
+            consumer,
+            target: None,
+            device_events: None,
+        }
+    }
+
+    #[test]
+    fn test_router_keeps_latest_samples() {
+        let mut router = router(4);
+
+        router.route(vec![1.0, 2.0, 3.0]);
+        router.route(vec![4.0, 5.0]);
+
+        assert_eq!(router.consumer.pop_all(), vec![2.0, 3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_router_block_longer_than_capacity() {
+        let mut router = router(3);
+
+        router.route(vec![1.0, 2.0, 3.0, 4.0, 5.0]);
+
+        assert_eq!(router.consumer.pop_all(), vec![3.0, 4.0, 5.0]);
+    }
+
+    #[test]
+    fn test_router_forwards_to_target() {
+        let mut router = router(4);
+        let (tx, mut rx) = mpsc::channel(4);
+        router.target = Some(tx);
+
+        router.route(vec![1.0]);
+
+        assert_eq!(rx.try_recv().unwrap(), vec![1.0]);
+        assert!(router.consumer.is_empty());
+
+        // A closed session falls back to buffering
+        drop(rx);
+        router.route(vec![2.0]);
+        assert!(router.target.is_none());
+        assert_eq!(router.consumer.pop_all(), vec![2.0]);
+    }
+
+    #[tokio::test]
+    async fn test_pre_roll_is_flushed_before_live_audio() {
+        // 16kHz silence in real time, so audio keeps arriving
+        let source = SyntheticSource::new(16000)
+            .with_segment(Signal::Silence, Duration::from_secs(5))
+            .with_pacing(Pacing::Realtime);
+        let warm = WarmMicrophone::from_source(Box::new(source), 100).unwrap();
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(warm.buffered_samples(), 1600);
+
+        let (tx, mut rx) = mpsc::channel(100);
+        let mut session = warm.source();
+        session.start(tx).unwrap();
+        assert!(session.is_active());
+
+        // The whole pre-roll arrives first, then 10ms live blocks
+        assert_eq!(rx.recv().await.unwrap().len(), 1600);
+        assert_eq!(rx.recv().await.unwrap().len(), 160);
+
+        session.stop();
+        assert!(!session.is_active());
+
+        // Back to buffering
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert!(warm.buffered_samples() > 0);
+    }
+
+    #[test]
+    fn test_matches_settings() {
+        let source = SyntheticSource::new(16000).with_pacing(Pacing::Fast);
+        let warm = WarmMicrophone::from_source(Box::new(source), 300).unwrap();
+
+        let no_failover = FailoverPolicy::default();
+        let to_default = FailoverPolicy::to_default();
+
+        assert!(warm.matches(None, ChannelMode::Downmix, 300, &no_failover));
+        assert!(!warm.matches(None, ChannelMode::Downmix, 500, &no_failover));
+        assert!(!warm.matches(Some("USB Mic"), ChannelMode::Downmix, 300, &no_failover));
+        assert!(!warm.matches(None, ChannelMode::Select(1), 300, &no_failover));
+        assert!(!warm.matches(None, ChannelMode::Downmix, 300, &to_default));
+    }
+
+    #[tokio::test]
+    async fn test_source_end_closes_session_stream() {
+        let source = SyntheticSource::new(16000)
+            .with_segment(Signal::Silence, Duration::from_millis(200))
+            .with_pacing(Pacing::Realtime);
+        let warm = WarmMicrophone::from_source(Box::new(source), 100).unwrap();
+        assert!(warm.is_running());
+
+        let (tx, mut rx) = mpsc::channel(100);
+        let mut session = warm.source();
+        session.start(tx).unwrap();
+
+        // The session's stream ends with the microphone's
+        while rx.recv().await.is_some() {}
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!warm.is_running());
+    }
+}
@@ -0,0 +1,32 @@
+//! 音频设备相关的 Tauri 命令
+//!
+//! 提供前端列出输入设备、查询实际选中设备的命令；设备插拔时后端会发出
+//! `audio:devices_changed` 事件（载荷为新的设备列表），设置页据此刷新
+
+use tauri::{command, AppHandle};
+
+use super::session::session_controller;
+use crate::audio::{list_input_devices, AudioDevice, AudioError, DeviceMatch};
+
+/// 列出所有输入设备
+///
+/// 没有任何输入设备时返回空列表
+#[command]
+pub fn list_audio_devices() -> Result<Vec<AudioDevice>, String> {
+    match list_input_devices() {
+        Ok(devices) => Ok(devices),
+        Err(AudioError::DeviceNotFound) => Ok(Vec::new()),
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// 获取最近一次打开麦克风时实际选中的输入设备
+///
+/// 结果包含设备信息和匹配方式（精确 ID、名称、模糊匹配、首选设备或系统默认），
+/// 尚未打开过麦克风时返回 None
+#[command]
+pub fn get_selected_input_device(app: AppHandle) -> Result<Option<DeviceMatch>, String> {
+    let controller = session_controller(&app)?;
+
+    Ok(controller.selected_device())
+}
@@ -6,6 +6,8 @@ use std::sync::Arc;
 
 use tauri::{command, AppHandle, Manager};
 
+use crate::hotkey::{self, HotkeyConfig};
+use crate::session::SessionController;
 use crate::state::{AppConfig, ConfigManager, GlobalConfig};
 
 /// 获取当前配置
@@ -19,16 +21,35 @@ pub fn get_config(app: AppHandle) -> Result<AppConfig, String> {
 }
 
 /// 保存配置
+///
+/// 热键发生变化时会先重新绑定，绑定失败则恢复旧热键且不保存配置
 #[command]
 pub fn save_config(app: AppHandle, config: AppConfig) -> Result<(), String> {
+    let global = app.try_state::<Arc<GlobalConfig>>();
+
+    // 重新绑定热键
+    if let Some(ref global) = global {
+        rebind_hotkeys(&app, &global.get().hotkeys, &config.hotkeys)?;
+    }
+
     // 保存到文件
-    ConfigManager::save(&app, &config).map_err(|e| e.to_string())?;
+    if let Err(e) = ConfigManager::save(&app, &config) {
+        if let Some(ref global) = global {
+            let _ = rebind_hotkeys(&app, &config.hotkeys, &global.get().hotkeys);
+        }
+        return Err(e.to_string());
+    }
 
     // 更新全局配置
-    if let Some(global) = app.try_state::<Arc<GlobalConfig>>() {
+    if let Some(global) = global {
         global.update(config);
     }
 
+    // 预热设置或输入设备可能已改变
+    if let Some(controller) = app.try_state::<Arc<SessionController>>() {
+        controller.refresh_warm_microphone();
+    }
+
     tracing::info!("Config saved via command");
     Ok(())
 }
@@ -74,17 +95,47 @@ pub fn has_api_key(app: AppHandle) -> Result<bool, String> {
 /// 重置配置为默认值
 #[command]
 pub fn reset_config(app: AppHandle) -> Result<AppConfig, String> {
-    let config = ConfigManager::reset(&app).map_err(|e| e.to_string())?;
+    let global = app.try_state::<Arc<GlobalConfig>>();
+    let default_hotkeys = HotkeyConfig::default();
+
+    // 恢复默认热键
+    if let Some(ref global) = global {
+        rebind_hotkeys(&app, &global.get().hotkeys, &default_hotkeys)?;
+    }
+
+    let config = match ConfigManager::reset(&app) {
+        Ok(config) => config,
+        Err(e) => {
+            if let Some(ref global) = global {
+                let _ = rebind_hotkeys(&app, &default_hotkeys, &global.get().hotkeys);
+            }
+            return Err(e.to_string());
+        }
+    };
 
     // 更新全局配置
-    if let Some(global) = app.try_state::<Arc<GlobalConfig>>() {
+    if let Some(global) = global {
         global.update(config.clone());
     }
 
+    if let Some(controller) = app.try_state::<Arc<SessionController>>() {
+        controller.refresh_warm_microphone();
+    }
+
     tracing::info!("Config reset via command");
     Ok(config)
 }
 
+/// 重新绑定全局热键
+///
+/// 失败时 `hotkey::rebind_hotkeys` 已恢复旧热键，这里只负责转换错误
+fn rebind_hotkeys(app: &AppHandle, old: &HotkeyConfig, new: &HotkeyConfig) -> Result<(), String> {
+    hotkey::rebind_hotkeys(app, old, new).map_err(|e| {
+        tracing::error!(error = %e, "Failed to rebind hotkeys");
+        e.to_string()
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
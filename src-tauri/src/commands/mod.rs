@@ -4,14 +4,23 @@
 //!
 //! # 模块结构
 //!
+//! - `audio` - 音频设备命令
 //! - `config` - 配置管理命令
+//! - `session` - 会话控制命令
 //! - `state` - 状态管理命令
+//! - `text` - 文本处理命令（替换词典）
 //! - `window` - 窗口管理命令
 
+pub mod audio;
 pub mod config;
+pub mod session;
 pub mod state;
+pub mod text;
 pub mod window;
 
+pub use audio::*;
 pub use config::*;
+pub use session::*;
 pub use state::*;
+pub use text::*;
 pub use window::*;
@@ -0,0 +1,53 @@
+//! 会话相关的 Tauri 命令
+//!
+//! 提供前端控制转写会话的命令，与全局热键共用同一个 `SessionController`
+
+use std::sync::Arc;
+
+use tauri::{command, AppHandle, Manager};
+
+use crate::session::SessionController;
+
+/// 获取会话控制器
+pub(crate) fn session_controller(app: &AppHandle) -> Result<Arc<SessionController>, String> {
+    app.try_state::<Arc<SessionController>>()
+        .map(|controller| Arc::clone(&controller))
+        .ok_or_else(|| "SessionController not available".to_string())
+}
+
+/// 使用当前配置启动会话
+#[command]
+pub async fn start_session(app: AppHandle) -> Result<(), String> {
+    let controller = session_controller(&app)?;
+
+    controller
+        .start_from_config()
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// 停止会话
+///
+/// 返回本次会话的最终文本（已按配置注入或复制）
+#[command]
+pub async fn stop_session(app: AppHandle) -> Result<Option<String>, String> {
+    let controller = session_controller(&app)?;
+
+    controller.stop_session().await.map_err(|e| e.to_string())
+}
+
+/// 取消会话并丢弃转写结果
+#[command]
+pub async fn cancel_session(app: AppHandle) -> Result<(), String> {
+    let controller = session_controller(&app)?;
+
+    controller.cancel_session().await.map_err(|e| e.to_string())
+}
+
+/// 检查是否有活跃会话
+#[command]
+pub fn is_session_active(app: AppHandle) -> Result<bool, String> {
+    let controller = session_controller(&app)?;
+
+    Ok(controller.is_active())
+}
@@ -0,0 +1,132 @@
+//! 文本处理相关的 Tauri 命令
+//!
+//! 管理替换词典规则（增删、导入导出）和试运行
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use tauri::{command, AppHandle, Manager};
+
+use crate::state::{ConfigManager, GlobalConfig};
+use crate::text::{self, ReplacementDictionary, ReplacementRule, TextProcessor};
+
+/// 获取替换词典规则
+#[command]
+pub fn list_replacements(app: AppHandle) -> Result<Vec<ReplacementRule>, String> {
+    let config = app
+        .try_state::<Arc<GlobalConfig>>()
+        .ok_or("Config not initialized")?;
+
+    Ok(config.get().processing.replacements.clone())
+}
+
+/// 添加替换规则
+///
+/// 规则无效（如正则无法编译）时返回错误且不保存
+#[command]
+pub fn add_replacement(
+    app: AppHandle,
+    rule: ReplacementRule,
+) -> Result<Vec<ReplacementRule>, String> {
+    rule.validate().map_err(|e| e.to_string())?;
+
+    update_replacements(&app, |rules| {
+        rules.push(rule);
+        Ok(())
+    })
+}
+
+/// 按索引删除替换规则
+#[command]
+pub fn remove_replacement(app: AppHandle, index: usize) -> Result<Vec<ReplacementRule>, String> {
+    update_replacements(&app, |rules| {
+        if index >= rules.len() {
+            return Err(format!("Replacement rule {} does not exist", index));
+        }
+        rules.remove(index);
+        Ok(())
+    })
+}
+
+/// 从 JSON 文件导入替换规则
+///
+/// `replace` 为 true 时覆盖现有规则，否则追加到末尾
+#[command]
+pub fn import_replacements(
+    app: AppHandle,
+    path: PathBuf,
+    replace: bool,
+) -> Result<Vec<ReplacementRule>, String> {
+    let imported = text::import_rules(&path).map_err(|e| e.to_string())?;
+    let count = imported.len();
+
+    let rules = update_replacements(&app, |rules| {
+        if replace {
+            rules.clear();
+        }
+        rules.extend(imported);
+        Ok(())
+    })?;
+
+    tracing::info!(path = %path.display(), count, "Replacement rules imported");
+    Ok(rules)
+}
+
+/// 将替换规则导出为 JSON 文件，返回导出的规则数量
+#[command]
+pub fn export_replacements(app: AppHandle, path: PathBuf) -> Result<usize, String> {
+    let config = app
+        .try_state::<Arc<GlobalConfig>>()
+        .ok_or("Config not initialized")?;
+    let rules = config.get().processing.replacements.clone();
+
+    text::export_rules(&path, &rules).map_err(|e| e.to_string())?;
+
+    tracing::info!(path = %path.display(), count = rules.len(), "Replacement rules exported");
+    Ok(rules.len())
+}
+
+/// 试运行替换词典
+///
+/// 返回 `text` 经过替换后的结果，不修改配置。
+/// 传入 `rules` 时使用这些规则（用于预览未保存的编辑），否则使用已保存的规则
+#[command]
+pub fn preview_replacements(
+    app: AppHandle,
+    text: String,
+    rules: Option<Vec<ReplacementRule>>,
+) -> Result<String, String> {
+    let rules = match rules {
+        Some(rules) => rules,
+        None => {
+            let config = app
+                .try_state::<Arc<GlobalConfig>>()
+                .ok_or("Config not initialized")?;
+            config.get().processing.replacements.clone()
+        }
+    };
+
+    let dictionary = ReplacementDictionary::new(&rules).map_err(|e| e.to_string())?;
+    Ok(dictionary.process(&text))
+}
+
+/// 修改替换规则并保存配置
+///
+/// 下一次会话开始时按新配置构建处理链
+fn update_replacements<F>(app: &AppHandle, modify: F) -> Result<Vec<ReplacementRule>, String>
+where
+    F: FnOnce(&mut Vec<ReplacementRule>) -> Result<(), String>,
+{
+    let global = app
+        .try_state::<Arc<GlobalConfig>>()
+        .ok_or("Config not initialized")?;
+
+    let mut config = (*global.get()).clone();
+    modify(&mut config.processing.replacements)?;
+
+    ConfigManager::save(app, &config).map_err(|e| e.to_string())?;
+    let rules = config.processing.replacements.clone();
+    global.update(config);
+
+    Ok(rules)
+}
@@ -6,142 +6,49 @@ use std::sync::Arc;
 
 use tauri::{AppHandle, Emitter, Manager};
 
-use super::session::{SessionController, SessionControllerError};
+use crate::session::{SessionController, SessionControllerError};
 use crate::state::{
-    setup_state_transitions, AppState, ProcessingTimeoutHandler, StateEventEmitter, StateManager,
+    setup_state_transitions, ProcessingTimeoutHandler, StateEventEmitter, StateManager,
+    StateTransitionContext,
 };
 
-/// API Key 持有者
-///
-/// 存储 API Key 供会话使用
-pub struct ApiKeyHolder {
-    api_key: tokio::sync::RwLock<Option<String>>,
-}
-
-impl ApiKeyHolder {
-    /// 创建新的 API Key 持有者
-    pub fn new() -> Self {
-        Self {
-            api_key: tokio::sync::RwLock::new(None),
-        }
-    }
-
-    /// 设置 API Key
-    pub async fn set(&self, api_key: impl Into<String>) {
-        let mut key = self.api_key.write().await;
-        *key = Some(api_key.into());
-    }
-
-    /// 获取 API Key
-    pub async fn get(&self) -> Option<String> {
-        self.api_key.read().await.clone()
-    }
-}
-
-impl Default for ApiKeyHolder {
-    fn default() -> Self {
-        Self::new()
+/// 获取会话控制器
+fn session_controller(app: &AppHandle) -> Option<Arc<SessionController>> {
+    let controller = app.try_state::<Arc<SessionController>>();
+    if controller.is_none() {
+        tracing::error!("SessionController not available");
     }
+    controller.map(|c| Arc::clone(&c))
 }
 
 /// 处理 Push-to-Talk 按下事件
 ///
-/// 当用户按下 PTT 热键时：
-/// 1. 检查当前状态是否为 Idle
-/// 2. 转换状态为 Connecting
-/// 3. 启动转写会话
+/// 使用当前配置通过 `SessionController` 启动会话，
+/// 状态转换（Idle -> Connecting -> Recording）由会话负责
 pub fn handle_ptt_pressed(app: &AppHandle) {
-    // 获取状态管理器
-    let state_manager = match app.try_state::<Arc<StateManager>>() {
-        Some(manager) => manager,
-        None => {
-            tracing::warn!("StateManager not available, ignoring PTT pressed event");
-            return;
-        }
-    };
-
-    let current = state_manager.current();
-
-    // 只在 Idle 状态时响应
-    if !current.is_idle() {
-        tracing::warn!(
-            current_state = %current.name(),
-            "PTT pressed but not in Idle state, ignoring"
-        );
+    let Some(controller) = session_controller(app) else {
         return;
-    }
-
-    // 转换到 Connecting 状态
-    if let Err(e) = state_manager.transition(AppState::connecting()) {
-        tracing::error!(error = %e, "Failed to transition to Connecting state");
-        return;
-    }
-
-    tracing::info!("PTT pressed: transitioning to Connecting state");
-
-    // 获取会话控制器
-    let session_controller = match app.try_state::<Arc<SessionController>>() {
-        Some(controller) => controller,
-        None => {
-            tracing::error!("SessionController not available");
-            state_manager.reset();
-            return;
-        }
     };
 
-    // 获取 API Key
-    let api_key_holder = match app.try_state::<Arc<ApiKeyHolder>>() {
-        Some(holder) => holder,
-        None => {
-            tracing::error!("ApiKeyHolder not available");
-            let _ = state_manager.transition(AppState::error("API Key 未配置".to_string()));
-            return;
-        }
-    };
-
-    // 在后台启动会话
     let app_handle = app.clone();
-    let controller = Arc::clone(&session_controller);
-    let state_mgr = Arc::clone(&state_manager);
-    let api_holder = Arc::clone(&api_key_holder);
-
-    tokio::spawn(async move {
-        // 获取 API Key
-        let api_key = match api_holder.get().await {
-            Some(key) => key,
-            None => {
-                tracing::error!("API Key not set");
-                let _ = state_mgr.transition(AppState::error("API Key 未设置，请在设置中配置".to_string()));
-                let _ = app_handle.emit("transcription:error", "API Key 未设置");
-                return;
-            }
-        };
 
-        match controller.start_session(&api_key).await {
+    tauri::async_runtime::spawn(async move {
+        match controller.start_from_config().await {
             Ok(()) => {
-                tracing::info!("Transcription session started successfully");
+                tracing::info!("Session started from hotkey");
+            }
+            Err(SessionControllerError::SessionAlreadyActive) => {
+                tracing::debug!("PTT pressed while a session is active, ignoring");
+            }
+            Err(SessionControllerError::ApiKeyNotSet) => {
+                tracing::error!("API Key not set");
+                if let Some(ctx) = StateTransitionContext::from_app(&app_handle) {
+                    let _ = ctx.report_error("API Key 未设置，请在设置中配置");
+                }
             }
             Err(e) => {
-                tracing::error!(error = %e, "Failed to start transcription session");
-
-                // 转换到错误状态
-                let error_msg = match &e {
-                    SessionControllerError::ApiKeyNotSet => {
-                        "API Key 未设置，请在设置中配置".to_string()
-                    }
-                    SessionControllerError::SessionAlreadyActive => {
-                        "会话已在运行中".to_string()
-                    }
-                    SessionControllerError::StartFailed(msg) => {
-                        format!("启动失败: {}", msg)
-                    }
-                    _ => e.to_string(),
-                };
-
-                let _ = state_mgr.transition(AppState::error(error_msg));
-
-                // 发送错误通知到前端
-                let _ = app_handle.emit("transcription:error", e.to_string());
+                // 启动失败时会话已将状态切换为 Error 并通知前端
+                tracing::error!(error = %e, "Failed to start session from hotkey");
             }
         }
     });
@@ -149,85 +56,25 @@ pub fn handle_ptt_pressed(app: &AppHandle) {
 
 /// 处理 Push-to-Talk 松开事件
 ///
-/// 当用户松开 PTT 热键时：
-/// 1. 检查当前状态是否为 Recording
-/// 2. 转换状态为 Processing
-/// 3. 停止会话并获取最终结果
-/// 4. 转换状态为 Injecting（如果有结果）
+/// 停止当前会话，会话负责 Processing -> Injecting -> Idle 的转换和文本注入
 pub fn handle_ptt_released(app: &AppHandle) {
-    // 获取状态管理器
-    let state_manager = match app.try_state::<Arc<StateManager>>() {
-        Some(manager) => manager,
-        None => {
-            tracing::warn!("StateManager not available, ignoring PTT released event");
-            return;
-        }
-    };
-
-    let current = state_manager.current();
-
-    // 只在 Recording 状态时响应
-    if !current.is_recording() {
-        tracing::debug!(
-            current_state = %current.name(),
-            "PTT released but not in Recording state, ignoring"
-        );
-        return;
-    }
-
-    // 转换到 Processing 状态
-    if let Err(e) = state_manager.transition(AppState::processing()) {
-        tracing::error!(error = %e, "Failed to transition to Processing state");
+    let Some(controller) = session_controller(app) else {
         return;
-    }
-
-    tracing::info!("PTT released: transitioning to Processing state");
-
-    // 获取会话控制器
-    let session_controller = match app.try_state::<Arc<SessionController>>() {
-        Some(controller) => controller,
-        None => {
-            tracing::error!("SessionController not available");
-            state_manager.reset();
-            return;
-        }
     };
 
-    // 在后台停止会话并处理结果
-    let app_handle = app.clone();
-    let controller = Arc::clone(&session_controller);
-    let state_mgr = Arc::clone(&state_manager);
-
-    tokio::spawn(async move {
+    tauri::async_runtime::spawn(async move {
         match controller.stop_session().await {
             Ok(Some(text)) => {
-                tracing::info!(text = %text, "Got committed transcript");
-
-                // 转换到 Injecting 状态
-                if let Err(e) = state_mgr.transition(AppState::injecting()) {
-                    tracing::error!(error = %e, "Failed to transition to Injecting state");
-                    state_mgr.reset();
-                    return;
-                }
-
-                // 发送结果到前端
-                let _ = app_handle.emit("transcription:committed", &text);
-
-                // TODO: P2-T7 中实现文本注入
-                // 这里先只是通知前端，实际注入在 P2-T7 中实现
-
-                // 注入完成后返回 Idle
-                // 暂时直接重置，等 P2-T7 实现后会在注入完成后重置
-                tokio::time::sleep(tokio::time::Duration::from_millis(100)).await;
-                state_mgr.reset();
+                tracing::info!(text_len = text.len(), "Session stopped from hotkey");
             }
             Ok(None) => {
-                tracing::info!("No committed transcript received");
-                state_mgr.reset();
+                tracing::info!("Session stopped without committed transcript");
+            }
+            Err(SessionControllerError::NoActiveSession) => {
+                tracing::debug!("PTT released without an active session, ignoring");
             }
             Err(e) => {
-                tracing::error!(error = %e, "Failed to stop session");
-                state_mgr.reset();
+                tracing::error!(error = %e, "Failed to stop session from hotkey");
             }
         }
     });
@@ -235,54 +82,16 @@ pub fn handle_ptt_released(app: &AppHandle) {
 
 /// 处理取消事件
 ///
-/// 当用户按下取消键时：
-/// 1. 检查是否在可取消状态（Connecting 或 Recording）
-/// 2. 取消当前会话
-/// 3. 重置状态为 Idle
+/// 取消当前会话并丢弃转写结果
 pub fn handle_cancel(app: &AppHandle) {
-    // 获取状态管理器
-    let state_manager = match app.try_state::<Arc<StateManager>>() {
-        Some(manager) => manager,
-        None => {
-            tracing::warn!("StateManager not available, ignoring Cancel event");
-            return;
-        }
-    };
-
-    let current = state_manager.current();
-
-    // 只在 Recording、Connecting 或 Processing 状态时响应
-    if !current.is_recording() && !current.is_connecting() && !current.is_processing() {
-        tracing::debug!(
-            current_state = %current.name(),
-            "Cancel pressed but not in cancellable state, ignoring"
-        );
+    let Some(controller) = session_controller(app) else {
         return;
-    }
-
-    tracing::info!("Cancel pressed: cancelling session");
-
-    // 获取会话控制器
-    let session_controller = match app.try_state::<Arc<SessionController>>() {
-        Some(controller) => controller,
-        None => {
-            // 没有会话控制器，直接重置状态
-            state_manager.reset();
-            return;
-        }
     };
 
-    // 在后台取消会话
-    let controller = Arc::clone(&session_controller);
-    let app_handle = app.clone();
-
-    tokio::spawn(async move {
+    tauri::async_runtime::spawn(async move {
         if let Err(e) = controller.cancel_session().await {
             tracing::error!(error = %e, "Failed to cancel session");
         }
-
-        // 发送取消通知到前端
-        let _ = app_handle.emit("transcription:cancelled", ());
     });
 }
 
@@ -337,19 +146,21 @@ impl StateTransitionSystem {
 
 /// 初始化热键处理所需的状态
 ///
-/// 在应用启动时调用，注册必要的状态管理器
+/// 在应用启动时调用，需在注册 `SessionController` 之后调用
 pub fn setup_hotkey_state(app: &AppHandle) -> Result<(), HotkeyHandlerError> {
-    // 创建状态管理器
-    let state_manager = Arc::new(StateManager::new());
-    app.manage(Arc::clone(&state_manager));
-
-    // 创建会话控制器
-    let session_controller = Arc::new(SessionController::new(Arc::clone(&state_manager)));
-    app.manage(session_controller);
+    if app.try_state::<Arc<SessionController>>().is_none() {
+        return Err(HotkeyHandlerError::ControllerNotAvailable);
+    }
 
-    // 创建 API Key 持有者
-    let api_key_holder = Arc::new(ApiKeyHolder::new());
-    app.manage(api_key_holder);
+    // 复用已注册的状态管理器，确保热键与命令操作同一个状态机
+    let state_manager = match app.try_state::<Arc<StateManager>>() {
+        Some(manager) => Arc::clone(&manager),
+        None => {
+            let manager = Arc::new(StateManager::new());
+            app.manage(Arc::clone(&manager));
+            manager
+        }
+    };
 
     // 创建状态转换系统（稍后异步初始化）
     let transition_system = Arc::new(StateTransitionSystem::new());
@@ -360,7 +171,7 @@ pub fn setup_hotkey_state(app: &AppHandle) -> Result<(), HotkeyHandlerError> {
     let state_mgr = Arc::clone(&state_manager);
     let trans_sys = Arc::clone(&transition_system);
 
-    tokio::spawn(async move {
+    tauri::async_runtime::spawn(async move {
         trans_sys.initialize(&app_handle, state_mgr).await;
         tracing::info!("State transition system initialized");
     });
@@ -369,19 +180,6 @@ pub fn setup_hotkey_state(app: &AppHandle) -> Result<(), HotkeyHandlerError> {
     Ok(())
 }
 
-/// 设置 API Key
-///
-/// 在应用配置加载后调用
-pub async fn set_api_key(app: &AppHandle, api_key: &str) -> Result<(), HotkeyHandlerError> {
-    let api_key_holder = app
-        .try_state::<Arc<ApiKeyHolder>>()
-        .ok_or(HotkeyHandlerError::ControllerNotAvailable)?;
-
-    api_key_holder.set(api_key).await;
-    tracing::info!("API key configured");
-    Ok(())
-}
-
 /// 热键处理器错误
 #[derive(Debug, thiserror::Error, Clone, PartialEq)]
 pub enum HotkeyHandlerError {
@@ -423,20 +221,4 @@ mod tests {
         let error3 = HotkeyHandlerError::ControllerNotAvailable;
         assert_ne!(error1, error3);
     }
-
-    #[tokio::test]
-    async fn test_api_key_holder() {
-        let holder = ApiKeyHolder::new();
-
-        // 初始为空
-        assert!(holder.get().await.is_none());
-
-        // 设置后可获取
-        holder.set("test-api-key").await;
-        assert_eq!(holder.get().await, Some("test-api-key".to_string()));
-
-        // 可以更新
-        holder.set("new-api-key").await;
-        assert_eq!(holder.get().await, Some("new-api-key".to_string()));
-    }
 }
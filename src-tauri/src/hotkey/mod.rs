@@ -4,19 +4,24 @@
 //!
 //! # 功能
 //!
-//! - Push-to-Talk 热键：按住开始录音，松开结束录音
+//! - Push-to-Talk 热键：按住开始录音，松开结束录音并注入文本
 //! - 取消热键：取消当前录音会话
 //! - 切换模式热键：切换应用程序模式（可选）
 //!
+//! 热键只负责把按键事件转发给 `session::SessionController`，
+//! 会话流程和状态转换由会话模块统一处理
+//!
 //! # 使用方法
 //!
 //! ```ignore
 //! use raflow_lib::hotkey::{HotkeyConfig, register_hotkeys, setup_hotkey_state};
+//! use raflow_lib::session::SessionController;
 //!
 //! // 在 Tauri setup 中初始化状态和注册热键
 //! tauri::Builder::default()
 //!     .setup(|app| {
-//!         // 初始化热键所需状态
+//!         // 注册会话控制器并初始化热键所需状态
+//!         app.manage(Arc::new(SessionController::new(app.handle().clone())));
 //!         setup_hotkey_state(app.handle())?;
 //!
 //!         // 创建配置并注册热键
@@ -30,18 +35,14 @@ mod config;
 mod error;
 mod handlers;
 mod register;
-mod session;
 
 pub use config::HotkeyConfig;
 pub use error::{HotkeyError, HotkeyResult};
 pub use handlers::{
-    handle_cancel, handle_ptt_pressed, handle_ptt_released, handle_toggle_mode, set_api_key,
-    setup_hotkey_state, HotkeyHandlerError, StateTransitionSystem,
+    handle_cancel, handle_ptt_pressed, handle_ptt_released, handle_toggle_mode, setup_hotkey_state,
+    HotkeyHandlerError, StateTransitionSystem,
 };
 pub use register::{
-    is_hotkey_registered, register_hotkeys, unregister_hotkeys, HotkeyEvent, HotkeyHandler,
-    HotkeyManager,
-};
-pub use session::{
-    SessionController, SessionControllerError, SessionEvent, SessionEventSender, SessionState,
+    is_hotkey_registered, rebind_hotkeys, register_hotkeys, unregister_hotkeys, HotkeyEvent,
+    HotkeyHandler, HotkeyManager,
 };
@@ -203,6 +203,64 @@ pub fn unregister_hotkeys(app: &AppHandle, config: &HotkeyConfig) -> HotkeyResul
     Ok(())
 }
 
+/// 重新绑定热键
+///
+/// 注销旧配置的热键并注册新配置的热键。
+/// 如果新热键注册失败，会清理已注册的部分并恢复旧热键。
+///
+/// # Arguments
+///
+/// * `app` - Tauri 应用句柄
+/// * `old` - 当前已注册的热键配置
+/// * `new` - 要注册的新热键配置
+///
+/// # Errors
+///
+/// 新热键注册失败时返回对应的 `HotkeyError`（此时旧热键已恢复）
+pub fn rebind_hotkeys(app: &AppHandle, old: &HotkeyConfig, new: &HotkeyConfig) -> HotkeyResult<()> {
+    if old == new {
+        tracing::debug!("Hotkey config unchanged, skipping rebind");
+        return Ok(());
+    }
+
+    unregister_hotkeys_quietly(app, old);
+
+    if let Err(e) = register_hotkeys(app, new) {
+        tracing::error!(error = %e, "Failed to register new hotkeys, rolling back");
+
+        // 清理新配置中已成功注册的部分
+        unregister_hotkeys_quietly(app, new);
+
+        if let Err(rollback_err) = register_hotkeys(app, old) {
+            tracing::error!(error = %rollback_err, "Failed to restore previous hotkeys");
+        }
+
+        return Err(e);
+    }
+
+    tracing::info!("Hotkeys rebound successfully");
+    Ok(())
+}
+
+/// 尽力注销配置中所有已注册的热键
+///
+/// 与 `unregister_hotkeys` 不同，单个热键失败不会中断其余热键的注销
+fn unregister_hotkeys_quietly(app: &AppHandle, config: &HotkeyConfig) {
+    let shortcut_manager = app.global_shortcut();
+
+    for hotkey in config.all_hotkeys() {
+        let Ok(shortcut) = parse_shortcut(hotkey) else {
+            continue;
+        };
+
+        if shortcut_manager.is_registered(shortcut) {
+            if let Err(e) = shortcut_manager.unregister(shortcut) {
+                tracing::warn!(hotkey = %hotkey, error = %e, "Failed to unregister hotkey");
+            }
+        }
+    }
+}
+
 /// 检查热键是否已注册
 pub fn is_hotkey_registered(app: &AppHandle, hotkey: &str) -> HotkeyResult<bool> {
     let shortcut = parse_shortcut(hotkey)?;
@@ -0,0 +1,207 @@
+//! 注入历史
+//!
+//! 记录最近注入的文本段，供语音命令 "delete that" 删除上一段文字。
+//! 删除通过 Backspace 实现，因此只有光标仍停在上一段文字末尾时才能正确删除：
+//! 文字之后的 Enter、Tab、Backspace 计入上一段，焦点窗口变化或全选、撤销后清空历史
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+/// 默认保留的文本段数量
+pub const DEFAULT_HISTORY_CAPACITY: usize = 20;
+
+/// 注入历史
+///
+/// 跨会话共享（注册为 Tauri 状态），超出容量时丢弃最早的文本段
+#[derive(Debug)]
+pub struct InjectionHistory {
+    segments: Mutex<VecDeque<String>>,
+    capacity: usize,
+    /// 历史所属的窗口
+    target: Mutex<Option<String>>,
+}
+
+impl Default for InjectionHistory {
+    fn default() -> Self {
+        Self::new(DEFAULT_HISTORY_CAPACITY)
+    }
+}
+
+impl InjectionHistory {
+    /// 创建指定容量的注入历史
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            segments: Mutex::new(VecDeque::with_capacity(capacity)),
+            capacity,
+            target: Mutex::new(None),
+        }
+    }
+
+    /// 设置接下来注入的目标窗口，与之前的窗口不同时清空历史
+    pub fn set_target(&self, target: &str) {
+        let Ok(mut current) = self.target.lock() else {
+            return;
+        };
+        if current.as_deref() != Some(target) {
+            *current = Some(target.to_string());
+            self.clear();
+        }
+    }
+
+    /// 记录一段已注入的文本
+    pub fn push(&self, text: &str) {
+        if text.is_empty() || self.capacity == 0 {
+            return;
+        }
+
+        if let Ok(mut segments) = self.segments.lock() {
+            if segments.len() == self.capacity {
+                segments.pop_front();
+            }
+            segments.push_back(text.to_string());
+        }
+    }
+
+    /// 把按键产生的字符（换行、制表符）计入最近的一段文本
+    pub fn append(&self, text: &str) {
+        if text.is_empty() || self.capacity == 0 {
+            return;
+        }
+
+        let appended = self.segments.lock().is_ok_and(|mut segments| {
+            segments
+                .back_mut()
+                .map(|last| last.push_str(text))
+                .is_some()
+        });
+        if !appended {
+            self.push(text);
+        }
+    }
+
+    /// 记录一次退格：去掉最近一段文本的最后一个字符
+    pub fn backspace(&self) {
+        if let Ok(mut segments) = self.segments.lock()
+            && let Some(last) = segments.back_mut()
+        {
+            last.pop();
+            if last.is_empty() {
+                segments.pop_back();
+            }
+        }
+    }
+
+    /// 取出最近注入的一段文本
+    pub fn pop(&self) -> Option<String> {
+        self.segments
+            .lock()
+            .ok()
+            .and_then(|mut segments| segments.pop_back())
+    }
+
+    /// 清空历史
+    pub fn clear(&self) {
+        if let Ok(mut segments) = self.segments.lock() {
+            segments.clear();
+        }
+    }
+
+    /// 记录的文本段数量
+    pub fn len(&self) -> usize {
+        self.segments.lock().map_or(0, |segments| segments.len())
+    }
+
+    /// 是否没有记录
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_push_pop_order() {
+        let history = InjectionHistory::default();
+        history.push("first");
+        history.push("second");
+
+        assert_eq!(history.pop().as_deref(), Some("second"));
+        assert_eq!(history.pop().as_deref(), Some("first"));
+        assert_eq!(history.pop(), None);
+    }
+
+    #[test]
+    fn test_capacity_drops_oldest() {
+        let history = InjectionHistory::new(2);
+        history.push("a");
+        history.push("b");
+        history.push("c");
+
+        assert_eq!(history.len(), 2);
+        assert_eq!(history.pop().as_deref(), Some("c"));
+        assert_eq!(history.pop().as_deref(), Some("b"));
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_empty_text_is_ignored() {
+        let history = InjectionHistory::default();
+        history.push("");
+
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_keys_extend_previous_segment() {
+        let history = InjectionHistory::default();
+        history.push("hello");
+        history.append("\n");
+        history.append("\t");
+
+        assert_eq!(history.pop().as_deref(), Some("hello\n\t"));
+
+        // 没有文本段时按键单独成段
+        history.append("\n");
+        assert_eq!(history.pop().as_deref(), Some("\n"));
+    }
+
+    #[test]
+    fn test_backspace_shortens_previous_segment() {
+        let history = InjectionHistory::default();
+        history.push("a");
+        history.push("bc");
+        history.backspace();
+        assert_eq!(history.pop().as_deref(), Some("b"));
+
+        history.push("x");
+        history.backspace();
+        assert_eq!(history.pop().as_deref(), Some("a"));
+
+        history.backspace();
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_target_change_clears_history() {
+        let history = InjectionHistory::default();
+        history.set_target("editor");
+        history.push("a");
+
+        history.set_target("editor");
+        assert_eq!(history.len(), 1);
+
+        history.set_target("browser");
+        assert!(history.is_empty());
+    }
+
+    #[test]
+    fn test_clear() {
+        let history = InjectionHistory::default();
+        history.push("a");
+        history.clear();
+
+        assert!(history.is_empty());
+    }
+}
@@ -307,6 +307,13 @@ impl<'a> TextInjector<'a> {
         clipboard.write(text)
     }
 
+    /// 获取键盘模拟器
+    ///
+    /// 用于在注入文本之间执行语音编辑命令对应的按键
+    pub fn keyboard(&mut self) -> &mut KeyboardSimulator {
+        &mut self.keyboard
+    }
+
     /// 获取当前策略
     pub fn strategy(&self) -> InjectionStrategy {
         self.strategy
@@ -7,6 +7,7 @@
 //! - 文本输入：逐字符模拟键盘输入
 //! - 粘贴操作：模拟 Ctrl+V (Windows/Linux) 或 Cmd+V (macOS)
 //! - 按键组合：支持自定义按键组合
+//! - 编辑快捷键：全选、撤销
 //!
 //! # 使用示例
 //!
@@ -390,6 +391,96 @@ impl KeyboardSimulator {
         Ok(())
     }
 
+    /// 模拟撤销操作
+    ///
+    /// 根据平台发送相应的撤销快捷键：
+    /// - Windows/Linux: Ctrl+Z
+    /// - macOS: Cmd+Z
+    ///
+    /// # Returns
+    ///
+    /// 成功返回 `Ok(())`
+    ///
+    /// # Errors
+    ///
+    /// - `InputError::KeyboardSimulationFailed` - 撤销失败
+    pub fn undo(&mut self) -> InputResult<()> {
+        tracing::debug!("Simulating undo operation");
+
+        #[cfg(target_os = "macos")]
+        {
+            self.undo_macos()?;
+        }
+
+        #[cfg(target_os = "windows")]
+        {
+            self.undo_windows()?;
+        }
+
+        #[cfg(target_os = "linux")]
+        {
+            self.undo_linux()?;
+        }
+
+        tracing::debug!("Undo operation completed");
+
+        Ok(())
+    }
+
+    /// macOS 撤销实现 (Cmd+Z)
+    #[cfg(target_os = "macos")]
+    fn undo_macos(&mut self) -> InputResult<()> {
+        self.enigo
+            .key(Key::Meta, Direction::Press)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to press Meta: {}", e)))?;
+
+        self.enigo
+            .key(Key::Unicode('z'), Direction::Click)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to click 'z': {}", e)))?;
+
+        self.enigo
+            .key(Key::Meta, Direction::Release)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to release Meta: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Windows 撤销实现 (Ctrl+Z)
+    #[cfg(target_os = "windows")]
+    fn undo_windows(&mut self) -> InputResult<()> {
+        self.enigo
+            .key(Key::Control, Direction::Press)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to press Control: {}", e)))?;
+
+        self.enigo
+            .key(Key::Unicode('z'), Direction::Click)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to click 'z': {}", e)))?;
+
+        self.enigo
+            .key(Key::Control, Direction::Release)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to release Control: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Linux 撤销实现 (Ctrl+Z)
+    #[cfg(target_os = "linux")]
+    fn undo_linux(&mut self) -> InputResult<()> {
+        self.enigo
+            .key(Key::Control, Direction::Press)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to press Control: {}", e)))?;
+
+        self.enigo
+            .key(Key::Unicode('z'), Direction::Click)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to click 'z': {}", e)))?;
+
+        self.enigo
+            .key(Key::Control, Direction::Release)
+            .map_err(|e| InputError::KeyboardSimulationFailed(format!("Failed to release Control: {}", e)))?;
+
+        Ok(())
+    }
+
     /// 按下单个按键
     ///
     /// # Arguments
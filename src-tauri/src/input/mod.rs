@@ -9,6 +9,7 @@
 //! - [`keyboard`] - 键盘模拟功能
 //! - [`clipboard`] - 剪贴板操作功能
 //! - [`injector`] - 文本注入器（整合键盘和剪贴板）
+//! - [`history`] - 注入历史（用于删除上一段文字）
 //! - [`platform`] - 平台特定实现
 //!
 //! # 功能概述
@@ -80,6 +81,7 @@
 
 pub mod clipboard;
 pub mod error;
+pub mod history;
 pub mod injector;
 pub mod keyboard;
 pub mod platform;
@@ -88,6 +90,7 @@ pub mod window;
 // Re-export commonly used types
 pub use clipboard::{read_from_clipboard, write_to_clipboard, ClipboardManager};
 pub use error::{InputError, InputResult};
+pub use history::{InjectionHistory, DEFAULT_HISTORY_CAPACITY};
 pub use injector::{InjectionResult, InjectionStrategy, TextInjector, AUTO_STRATEGY_THRESHOLD, PASTE_DELAY_MS};
 pub use keyboard::KeyboardSimulator;
 pub use platform::{
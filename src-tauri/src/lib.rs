@@ -25,13 +25,19 @@ pub mod tray;
 /// End-to-end transcription session management
 pub mod transcription;
 
+/// Transcript post-processing before injection
+pub mod text;
+
 /// Utility modules
 pub mod utils;
 
 use std::sync::Arc;
+use std::time::Duration;
 
-use tauri::Manager;
+use tauri::{Emitter, Manager};
 
+use audio::{AudioDevice, DeviceMonitor, DEFAULT_DEVICE_POLL_MS};
+use session::SessionController;
 use state::{init_config, GlobalConfig, StateManager};
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
@@ -52,16 +58,55 @@ pub fn run() {
             app.manage(state_manager);
 
             // Initialize config
-            match init_config(app.handle()) {
+            let global_config = match init_config(app.handle()) {
                 Ok(config) => {
                     tracing::info!(
                         has_api_key = config.has_api_key(),
                         "Config initialized"
                     );
+                    config
                 }
                 Err(e) => {
                     tracing::error!(error = %e, "Failed to initialize config, using defaults");
-                    app.manage(Arc::new(GlobalConfig::default()));
+                    let config = Arc::new(GlobalConfig::default());
+                    app.manage(Arc::clone(&config));
+                    config
+                }
+            };
+
+            // Initialize session controller (shared by hotkeys and commands)
+            let session_controller = Arc::new(SessionController::new(app.handle().clone()));
+            session_controller.refresh_warm_microphone();
+            app.manage(Arc::clone(&session_controller));
+
+            // Watch input devices so settings can refresh and a warm
+            // microphone picks up a re-plugged device
+            let app_handle = app.handle().clone();
+            let on_devices_changed = move |devices: Vec<AudioDevice>| {
+                let _ = app_handle.emit("audio:devices_changed", devices);
+                session_controller.refresh_warm_microphone();
+            };
+            let poll_interval = Duration::from_millis(DEFAULT_DEVICE_POLL_MS);
+            match DeviceMonitor::start(poll_interval, on_devices_changed) {
+                Ok(monitor) => {
+                    app.manage(monitor);
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to start device monitor");
+                }
+            }
+
+            // Setup global hotkeys
+            match hotkey::setup_hotkey_state(app.handle()) {
+                Ok(()) => {
+                    let config = global_config.get();
+
+                    if let Err(e) = hotkey::register_hotkeys(app.handle(), &config.hotkeys) {
+                        tracing::error!(error = %e, "Failed to register global hotkeys");
+                    }
+                }
+                Err(e) => {
+                    tracing::error!(error = %e, "Failed to initialize hotkey state");
                 }
             }
 
@@ -81,12 +126,24 @@ pub fn run() {
             commands::state::is_error,
             commands::state::reset_state,
             commands::state::recover_from_error,
+            commands::audio::list_audio_devices,
+            commands::audio::get_selected_input_device,
             commands::config::get_config,
             commands::config::save_config,
             commands::config::get_api_key,
             commands::config::set_api_key,
             commands::config::has_api_key,
             commands::config::reset_config,
+            commands::session::start_session,
+            commands::session::stop_session,
+            commands::session::cancel_session,
+            commands::session::is_session_active,
+            commands::text::list_replacements,
+            commands::text::add_replacement,
+            commands::text::remove_replacement,
+            commands::text::import_replacements,
+            commands::text::export_replacements,
+            commands::text::preview_replacements,
             commands::window::show_overlay,
             commands::window::hide_overlay,
             commands::window::toggle_overlay,
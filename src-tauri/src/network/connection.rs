@@ -13,12 +13,20 @@ use tokio::net::TcpStream;
 use tokio_tungstenite::{
     connect_async,
     tungstenite::{
+        handshake::client::generate_key,
         http::{Request, Uri},
         Message,
     },
     MaybeTlsStream, WebSocketStream,
 };
 use tracing::{debug, info, warn};
+use url::Url;
+
+/// Default ElevenLabs Scribe v2 Realtime endpoint
+pub const DEFAULT_BASE_URL: &str = "wss://api.elevenlabs.io/v1/speech-to-text/realtime";
+
+/// Default time to wait for the final committed transcript when stopping
+pub const DEFAULT_COMMIT_TIMEOUT_MS: u64 = 2000;
 
 type WsStream = WebSocketStream<MaybeTlsStream<TcpStream>>;
 
@@ -40,9 +48,16 @@ pub type WsReader = SplitStream<WsStream>;
 /// let config = ConnectionConfig::new(16000)
 ///     .with_model("scribe_v2_realtime")
 ///     .with_language("zh");
+///
+/// // Point at a local stand-in server instead of ElevenLabs
+/// let local = ConnectionConfig::new(16000).with_base_url("ws://127.0.0.1:9000/realtime");
 /// ```
 #[derive(Debug, Clone)]
 pub struct ConnectionConfig {
+    /// WebSocket endpoint URL (`wss://` or `ws://`), without query parameters
+    /// added by this config
+    pub base_url: String,
+
     /// Model ID (default: "scribe_v2_realtime")
     pub model_id: String,
 
@@ -60,6 +75,10 @@ pub struct ConnectionConfig {
 
     /// Connection timeout in milliseconds
     pub timeout_ms: u64,
+
+    /// How long to wait for the final committed transcript after the
+    /// closing commit, in milliseconds
+    pub commit_timeout_ms: u64,
 }
 
 impl ConnectionConfig {
@@ -69,15 +88,26 @@ impl ConnectionConfig {
     /// * `sample_rate` - Audio sample rate in Hz (typically 16000)
     pub fn new(sample_rate: u32) -> Self {
         Self {
+            base_url: DEFAULT_BASE_URL.to_string(),
             model_id: "scribe_v2_realtime".to_string(),
             language_code: None,
             sample_rate,
             include_timestamps: false,
             vad_commit_strategy: None,
             timeout_ms: 10000, // 10 seconds default
+            commit_timeout_ms: DEFAULT_COMMIT_TIMEOUT_MS,
         }
     }
 
+    /// Set the WebSocket endpoint URL
+    ///
+    /// Accepts `wss://` and `ws://` URLs, e.g. a corporate relay, a regional
+    /// endpoint or a local mock server.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
     /// Set the model ID
     pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
         self.model_id = model_id.into();
@@ -108,26 +138,78 @@ impl ConnectionConfig {
         self
     }
 
-    /// Build the WebSocket URL with query parameters
-    pub fn build_url(&self) -> NetworkResult<String> {
-        let mut url = format!(
-            "wss://api.elevenlabs.io/v1/speech-to-text/realtime?model_id={}&sample_rate={}",
-            self.model_id, self.sample_rate
-        );
+    /// Set the final commit timeout in milliseconds
+    pub fn with_commit_timeout(mut self, commit_timeout_ms: u64) -> Self {
+        self.commit_timeout_ms = commit_timeout_ms;
+        self
+    }
 
-        if let Some(ref lang) = self.language_code {
-            url.push_str(&format!("&language_code={}", lang));
+    /// Parse and validate the endpoint URL
+    ///
+    /// # Errors
+    /// Returns `NetworkError::InvalidConfig` if the URL cannot be parsed,
+    /// has no host, or does not use the `ws`/`wss` scheme
+    pub fn endpoint(&self) -> NetworkResult<Url> {
+        let url = Url::parse(self.base_url.trim()).map_err(|e| {
+            NetworkError::InvalidConfig(format!("Invalid base URL '{}': {}", self.base_url, e))
+        })?;
+
+        if !matches!(url.scheme(), "ws" | "wss") {
+            return Err(NetworkError::InvalidConfig(format!(
+                "Unsupported URL scheme '{}', expected ws or wss",
+                url.scheme()
+            )));
         }
 
-        if self.include_timestamps {
-            url.push_str("&include_timestamps=true");
+        if url.host_str().is_none() {
+            return Err(NetworkError::InvalidConfig(format!(
+                "Base URL '{}' has no host",
+                self.base_url
+            )));
         }
 
-        if let Some(ref vad) = self.vad_commit_strategy {
-            url.push_str(&format!("&vad_commit_strategy={}", vad));
+        Ok(url)
+    }
+
+    /// Build the WebSocket URL with URL-encoded query parameters
+    ///
+    /// Query parameters already present on the base URL are kept.
+    pub fn build_url(&self) -> NetworkResult<String> {
+        let mut url = self.endpoint()?;
+
+        {
+            let mut query = url.query_pairs_mut();
+            query
+                .append_pair("model_id", &self.model_id)
+                .append_pair("sample_rate", &self.sample_rate.to_string());
+
+            if let Some(ref lang) = self.language_code {
+                query.append_pair("language_code", lang);
+            }
+
+            if self.include_timestamps {
+                query.append_pair("include_timestamps", "true");
+            }
+
+            if let Some(ref vad) = self.vad_commit_strategy {
+                query.append_pair("vad_commit_strategy", vad);
+            }
         }
 
-        Ok(url)
+        Ok(url.into())
+    }
+
+    /// Value for the `Host` header derived from the endpoint URL
+    ///
+    /// Includes the port only when it differs from the scheme's default.
+    pub fn host_header(&self) -> NetworkResult<String> {
+        let url = self.endpoint()?;
+        let host = url.host_str().unwrap_or_default();
+
+        Ok(match url.port() {
+            Some(port) => format!("{}:{}", host, port),
+            None => host.to_string(),
+        })
     }
 }
 
@@ -170,6 +252,7 @@ impl ScribeConnection {
     /// Connect to the ElevenLabs Scribe v2 API
     ///
     /// Establishes a WebSocket connection with the provided API key and configuration.
+    /// The endpoint and `Host` header are taken from `config.base_url`.
     ///
     /// # Arguments
     /// * `api_key` - ElevenLabs API key for authentication
@@ -181,10 +264,10 @@ impl ScribeConnection {
     /// # Errors
     /// Returns `NetworkError` if connection fails
     pub async fn connect(api_key: &str, config: &ConnectionConfig) -> NetworkResult<Self> {
-        info!("Connecting to ElevenLabs Scribe API");
-
         // Build URL
         let url = config.build_url()?;
+        let host = config.host_header()?;
+        info!("Connecting to Scribe API at {}", host);
         debug!("Connection URL: {}", url);
 
         // Parse URI
@@ -196,10 +279,11 @@ impl ScribeConnection {
         let request = Request::builder()
             .uri(uri)
             .header("xi-api-key", api_key)
-            .header("Host", "api.elevenlabs.io")
+            .header("Host", host)
             .header("Connection", "Upgrade")
             .header("Upgrade", "websocket")
             .header("Sec-WebSocket-Version", "13")
+            .header("Sec-WebSocket-Key", generate_key())
             .body(())
             .map_err(|e| NetworkError::HttpError(e.to_string()))?;
 
@@ -219,10 +303,7 @@ impl ScribeConnection {
                 NetworkError::ConnectionFailed(e.to_string())
             })?;
 
-        info!(
-            "Connected to ElevenLabs API (status: {})",
-            response.status()
-        );
+        info!("Connected to Scribe API (status: {})", response.status());
         debug!("Response headers: {:?}", response.headers());
 
         Ok(Self {
@@ -434,5 +515,61 @@ mod tests {
 
         assert_eq!(config.sample_rate, 16000);
         assert_eq!(config.model_id, "scribe_v2_realtime");
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
+    }
+
+    #[test]
+    fn test_connection_config_custom_base_url() {
+        let config = ConnectionConfig::new(16000).with_base_url("ws://127.0.0.1:9000/realtime");
+        let url = config.build_url().unwrap();
+
+        assert!(url.starts_with("ws://127.0.0.1:9000/realtime?"));
+        assert!(url.contains("model_id=scribe_v2_realtime"));
+        assert_eq!(config.host_header().unwrap(), "127.0.0.1:9000");
+    }
+
+    #[test]
+    fn test_connection_config_host_header_default_port() {
+        let config = ConnectionConfig::new(16000);
+        assert_eq!(config.host_header().unwrap(), "api.elevenlabs.io");
+
+        let config = ConnectionConfig::new(16000).with_base_url("wss://relay.example.com:443/stt");
+        assert_eq!(config.host_header().unwrap(), "relay.example.com");
+    }
+
+    #[test]
+    fn test_connection_config_invalid_base_url() {
+        let config = ConnectionConfig::new(16000).with_base_url("https://api.elevenlabs.io");
+        assert!(matches!(
+            config.build_url(),
+            Err(NetworkError::InvalidConfig(_))
+        ));
+
+        let config = ConnectionConfig::new(16000).with_base_url("not a url");
+        assert!(matches!(
+            config.build_url(),
+            Err(NetworkError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_connection_config_query_encoding() {
+        let config = ConnectionConfig::new(16000)
+            .with_model("model&x=1")
+            .with_vad_strategy("silence 500ms");
+        let url = config.build_url().unwrap();
+
+        assert!(url.contains("model_id=model%26x%3D1"));
+        assert!(url.contains("vad_commit_strategy=silence+500ms"));
+        assert!(!url.contains("x=1&"));
+    }
+
+    #[test]
+    fn test_connection_config_keeps_base_query() {
+        let config =
+            ConnectionConfig::new(16000).with_base_url("wss://relay.example.com/stt?region=eu");
+        let url = config.build_url().unwrap();
+
+        assert!(url.starts_with("wss://relay.example.com/stt?region=eu&model_id="));
     }
 }
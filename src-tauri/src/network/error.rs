@@ -46,6 +46,36 @@ pub enum NetworkError {
     /// Server returned an error message
     #[error("Server error: {0}")]
     ServerError(String),
+
+    /// Usage quota exhausted
+    #[error("Quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// Too many requests or concurrent sessions
+    #[error("Rate limited: {0}")]
+    RateLimited(String),
+
+    /// Session reached the server's time limit
+    #[error("Session time limit exceeded: {0}")]
+    SessionTimeout(String),
+}
+
+impl NetworkError {
+    /// Whether retrying the connection may succeed
+    ///
+    /// Authentication, quota and configuration errors are permanent;
+    /// transport failures such as timeouts or dropped sockets, rate limits
+    /// and session time limits are worth retrying.
+    pub fn is_retryable(&self) -> bool {
+        !matches!(
+            self,
+            NetworkError::AuthenticationFailed
+                | NetworkError::QuotaExceeded(_)
+                | NetworkError::InvalidConfig(_)
+                | NetworkError::HttpError(_)
+                | NetworkError::SerializationError(_)
+        )
+    }
 }
 
 /// Result type for network operations
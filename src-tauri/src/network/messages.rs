@@ -5,7 +5,8 @@
 ///
 /// Reference: https://elevenlabs.io/docs/api-reference/speech-to-text/v-1-speech-to-text-realtime
 
-use serde::{Deserialize, Serialize};
+use crate::network::error::NetworkError;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 // ============================================================================
 // Client -> Server Messages
@@ -148,9 +149,11 @@ pub enum ClientMessage {
 /// Messages received from the server
 ///
 /// Uses serde's tagged enum feature to automatically deserialize
-/// based on the `message_type` field.
-#[derive(Deserialize, Debug, Clone, PartialEq)]
-#[serde(tag = "message_type")]
+/// based on the `message_type` field. Messages with a `message_type` this
+/// client does not know are kept as [`ServerMessage::Unknown`] instead of
+/// failing deserialization, so new server messages do not break the stream.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(tag = "message_type", remote = "Self")]
 pub enum ServerMessage {
     /// Session has been started
     #[serde(rename = "session_started")]
@@ -193,6 +196,90 @@ pub enum ServerMessage {
         /// Error message description
         error_message: String,
     },
+
+    /// Invalid or revoked API key
+    #[serde(rename = "auth_error")]
+    AuthError {
+        /// Error message description
+        #[serde(default, alias = "error")]
+        error_message: String,
+    },
+
+    /// Usage quota of the account is exhausted
+    #[serde(rename = "quota_exceeded")]
+    QuotaExceeded {
+        /// Error message description
+        #[serde(default, alias = "error")]
+        error_message: String,
+    },
+
+    /// Too many requests or concurrent sessions
+    #[serde(rename = "rate_limited")]
+    RateLimited {
+        /// Error message description
+        #[serde(default, alias = "error")]
+        error_message: String,
+    },
+
+    /// Session reached its maximum duration
+    #[serde(rename = "session_time_limit_exceeded")]
+    SessionTimeLimitExceeded {
+        /// Error message description
+        #[serde(default, alias = "error")]
+        error_message: String,
+    },
+
+    /// Message with a `message_type` this client does not know
+    #[serde(skip)]
+    Unknown {
+        /// Value of the `message_type` field
+        message_type: String,
+        /// The complete message
+        raw: serde_json::Value,
+    },
+}
+
+/// `message_type` values with a dedicated variant
+const KNOWN_MESSAGE_TYPES: &[&str] = &[
+    "session_started",
+    "partial_transcript",
+    "committed_transcript",
+    "committed_transcript_with_timestamps",
+    "input_error",
+    "auth_error",
+    "quota_exceeded",
+    "rate_limited",
+    "session_time_limit_exceeded",
+];
+
+impl<'de> Deserialize<'de> for ServerMessage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        use serde::de::Error;
+
+        let raw = serde_json::Value::deserialize(deserializer)?;
+        let message_type = raw
+            .get("message_type")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| D::Error::missing_field("message_type"))?;
+
+        if KNOWN_MESSAGE_TYPES.contains(&message_type) {
+            ServerMessage::deserialize(raw).map_err(D::Error::custom)
+        } else {
+            Ok(ServerMessage::Unknown {
+                message_type: message_type.to_string(),
+                raw,
+            })
+        }
+    }
+}
+
+impl Serialize for ServerMessage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ServerMessage::Unknown { raw, .. } => raw.serialize(serializer),
+            _ => ServerMessage::serialize(self, serializer),
+        }
+    }
 }
 
 impl ServerMessage {
@@ -212,7 +299,12 @@ impl ServerMessage {
 
     /// Check if this is an error
     pub fn is_error(&self) -> bool {
-        matches!(self, ServerMessage::InputError { .. })
+        self.error_message().is_some()
+    }
+
+    /// Check if this message type is unknown to this client
+    pub fn is_unknown(&self) -> bool {
+        matches!(self, ServerMessage::Unknown { .. })
     }
 
     /// Get the transcript text if this is a transcript message
@@ -228,11 +320,39 @@ impl ServerMessage {
     /// Get the error message if this is an error
     pub fn error_message(&self) -> Option<&str> {
         match self {
-            ServerMessage::InputError { error_message } => Some(error_message),
+            ServerMessage::InputError { error_message }
+            | ServerMessage::AuthError { error_message }
+            | ServerMessage::QuotaExceeded { error_message }
+            | ServerMessage::RateLimited { error_message }
+            | ServerMessage::SessionTimeLimitExceeded { error_message } => Some(error_message),
             _ => None,
         }
     }
 
+    /// Convert an error message into the matching `NetworkError`
+    ///
+    /// Returns `None` for messages that are not errors.
+    pub fn to_network_error(&self) -> Option<NetworkError> {
+        let error = match self {
+            ServerMessage::InputError { error_message } => {
+                NetworkError::ServerError(error_message.clone())
+            }
+            ServerMessage::AuthError { .. } => NetworkError::AuthenticationFailed,
+            ServerMessage::QuotaExceeded { error_message } => {
+                NetworkError::QuotaExceeded(error_message.clone())
+            }
+            ServerMessage::RateLimited { error_message } => {
+                NetworkError::RateLimited(error_message.clone())
+            }
+            ServerMessage::SessionTimeLimitExceeded { error_message } => {
+                NetworkError::SessionTimeout(error_message.clone())
+            }
+            _ => return None,
+        };
+
+        Some(error)
+    }
+
     /// Get the session ID if this is a session started message
     pub fn session_id(&self) -> Option<&str> {
         match self {
@@ -247,7 +367,7 @@ impl ServerMessage {
 // ============================================================================
 
 /// Session configuration returned by the server
-#[derive(Deserialize, Debug, Clone, PartialEq, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
 pub struct SessionConfig {
     /// Sample rate in Hz
     #[serde(default)]
@@ -271,7 +391,7 @@ pub struct SessionConfig {
 }
 
 /// Voice Activity Detection (VAD) configuration
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct VadConfig {
     /// VAD strategy ("auto", "manual", etc.)
     pub strategy: String,
@@ -286,7 +406,7 @@ pub struct VadConfig {
 }
 
 /// Word-level timestamp information
-#[derive(Deserialize, Debug, Clone, PartialEq)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct WordTimestamp {
     /// The word text
     pub word: String,
@@ -318,6 +438,26 @@ impl WordTimestamp {
     }
 }
 
+/// Append a committed transcript segment to `text`
+///
+/// The segment is trimmed. A space is inserted where two segments meet with
+/// ASCII non-whitespace characters; CJK text is joined directly.
+pub fn append_transcript(text: &mut String, segment: &str) {
+    let segment = segment.trim();
+    if segment.is_empty() {
+        return;
+    }
+
+    let needs_space = match (text.chars().last(), segment.chars().next()) {
+        (Some(prev), Some(next)) => prev.is_ascii() && next.is_ascii() && !prev.is_whitespace(),
+        _ => false,
+    };
+    if needs_space {
+        text.push(' ');
+    }
+    text.push_str(segment);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -486,6 +626,98 @@ mod tests {
         assert_eq!(msg.error_message(), Some("Invalid audio format"));
     }
 
+    #[test]
+    fn test_server_message_unknown_type() {
+        let json = r#"{"message_type": "future_event", "value": 42}"#;
+
+        let msg: ServerMessage = serde_json::from_str(json).unwrap();
+
+        assert!(msg.is_unknown());
+        assert!(!msg.is_error());
+        match &msg {
+            ServerMessage::Unknown { message_type, raw } => {
+                assert_eq!(message_type, "future_event");
+                assert_eq!(raw["value"], 42);
+            }
+            _ => panic!("Expected Unknown"),
+        }
+
+        // Unknown messages serialize back to the original payload
+        let value = serde_json::to_value(&msg).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({"message_type": "future_event", "value": 42})
+        );
+    }
+
+    #[test]
+    fn test_server_message_known_type_still_validated() {
+        // A known message type with a missing field is still an error
+        let result =
+            serde_json::from_str::<ServerMessage>(r#"{"message_type": "partial_transcript"}"#);
+        assert!(result.is_err());
+
+        let result = serde_json::from_str::<ServerMessage>(r#"{"text": "no type"}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_server_message_typed_errors() {
+        let cases = [
+            (
+                r#"{"message_type":"auth_error","error":"bad key"}"#,
+                "bad key",
+            ),
+            (
+                r#"{"message_type":"quota_exceeded","error":"quota"}"#,
+                "quota",
+            ),
+            (
+                r#"{"message_type":"rate_limited","error_message":"slow down"}"#,
+                "slow down",
+            ),
+            (
+                r#"{"message_type":"session_time_limit_exceeded","error":"too long"}"#,
+                "too long",
+            ),
+        ];
+
+        for (json, message) in cases {
+            let msg: ServerMessage = serde_json::from_str(json).unwrap();
+            assert!(msg.is_error(), "{} should be an error", json);
+            assert_eq!(msg.error_message(), Some(message));
+        }
+
+        let msg: ServerMessage = serde_json::from_str(cases[0].0).unwrap();
+        assert!(matches!(
+            msg.to_network_error(),
+            Some(NetworkError::AuthenticationFailed)
+        ));
+
+        let msg: ServerMessage = serde_json::from_str(cases[1].0).unwrap();
+        assert!(matches!(
+            msg.to_network_error(),
+            Some(NetworkError::QuotaExceeded(_))
+        ));
+
+        let msg: ServerMessage = serde_json::from_str(cases[2].0).unwrap();
+        assert!(matches!(
+            msg.to_network_error(),
+            Some(NetworkError::RateLimited(_))
+        ));
+
+        let msg: ServerMessage = serde_json::from_str(cases[3].0).unwrap();
+        assert!(matches!(
+            msg.to_network_error(),
+            Some(NetworkError::SessionTimeout(_))
+        ));
+
+        let partial = ServerMessage::PartialTranscript {
+            text: "hi".to_string(),
+        };
+        assert!(partial.to_network_error().is_none());
+    }
+
     #[test]
     fn test_word_timestamp_duration() {
         let word = WordTimestamp {
@@ -538,4 +770,18 @@ mod tests {
         let json = serde_json::to_string(&msg).unwrap();
         assert!(json.contains("\"message_type\":\"input_audio_chunk\""));
     }
+
+    #[test]
+    fn test_append_transcript() {
+        let mut text = String::new();
+        append_transcript(&mut text, "hello");
+        append_transcript(&mut text, " world ");
+        append_transcript(&mut text, "  ");
+        assert_eq!(text, "hello world");
+
+        let mut text = String::new();
+        append_transcript(&mut text, "你好");
+        append_transcript(&mut text, "世界");
+        assert_eq!(text, "你好世界");
+    }
 }
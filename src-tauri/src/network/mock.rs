@@ -0,0 +1,850 @@
+/// Local mock of the ElevenLabs Scribe v2 Realtime API
+///
+/// This module provides a WebSocket server that speaks the same protocol as
+/// `ClientMessage`/`ServerMessage`, so the network and transcription stack can
+/// be exercised without an API key or network access. It is only compiled with
+/// the `mock-server` cargo feature.
+///
+/// The server replies to every connection with `session_started`, then turns
+/// the received audio chunks into scripted transcripts: each non-silent chunk
+/// reveals part of the current script segment as a `partial_transcript`, and a
+/// segment is sent as `committed_transcript` once it is complete, when the
+/// client commits, or when the client sends `close`. Faults such as input
+/// errors, raw server messages, abrupt disconnects and rejected handshakes can
+/// be injected through `MockFault`.
+///
+/// # Example
+/// ```no_run
+/// use raflow_lib::network::mock::{MockScribeServer, MockServerConfig};
+/// use raflow_lib::network::ScribeConnection;
+///
+/// #[tokio::main]
+/// async fn main() {
+///     let server = MockScribeServer::start(MockServerConfig::new().with_transcript("hello world"))
+///         .await
+///         .unwrap();
+///
+///     let config = server.connection_config(16000);
+///     let conn = ScribeConnection::connect("any-key", &config).await.unwrap();
+///
+///     // Stream audio through `conn`...
+///     # drop(conn);
+///     server.shutdown().await;
+/// }
+/// ```
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures_util::{SinkExt, StreamExt};
+use serde::Deserialize;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use tokio_tungstenite::tungstenite::{
+    handshake::server::{ErrorResponse, Request, Response},
+    http::StatusCode,
+    Message,
+};
+use tokio_tungstenite::{accept_hdr_async, WebSocketStream};
+use tracing::{debug, info, warn};
+
+use crate::network::connection::ConnectionConfig;
+use crate::network::error::{NetworkError, NetworkResult};
+use crate::network::messages::{ServerMessage, SessionConfig, WordTimestamp};
+
+/// Path served by the mock server, mirroring the real endpoint
+pub const MOCK_ENDPOINT_PATH: &str = "/v1/speech-to-text/realtime";
+
+/// Fault injected into a mock session
+///
+/// Each fault fires at most once over the lifetime of the server, so a client
+/// that reconnects after a fault gets a healthy session.
+#[derive(Debug, Clone, PartialEq)]
+pub enum MockFault {
+    /// Send an `input_error` message after the given number of audio chunks
+    InputError {
+        /// Number of audio chunks received before the error is sent
+        after_chunks: usize,
+        /// Error message sent to the client
+        message: String,
+    },
+
+    /// Send an arbitrary text frame after the given number of audio chunks
+    Raw {
+        /// Number of audio chunks received before the payload is sent
+        after_chunks: usize,
+        /// Raw text payload (usually JSON)
+        payload: String,
+    },
+
+    /// Drop the connection without a close frame after the given number of audio chunks
+    Disconnect {
+        /// Number of audio chunks received before the connection is dropped
+        after_chunks: usize,
+    },
+
+    /// Reject the next WebSocket handshake with the given HTTP status
+    RejectHandshake {
+        /// HTTP status code (e.g. 401, 429)
+        status: u16,
+    },
+}
+
+impl MockFault {
+    /// Number of audio chunks after which a session fault fires
+    fn after_chunks(&self) -> Option<usize> {
+        match self {
+            MockFault::InputError { after_chunks, .. }
+            | MockFault::Raw { after_chunks, .. }
+            | MockFault::Disconnect { after_chunks } => Some(*after_chunks),
+            MockFault::RejectHandshake { .. } => None,
+        }
+    }
+}
+
+/// Configuration for the mock server
+#[derive(Debug, Clone)]
+pub struct MockServerConfig {
+    /// Script segments, used in order and repeated when exhausted
+    pub transcripts: Vec<String>,
+
+    /// Number of non-silent audio chunks needed to reveal one token
+    pub chunks_per_token: usize,
+
+    /// Commit a segment automatically once all of its tokens are revealed
+    pub auto_commit: bool,
+
+    /// Minimum peak amplitude for a chunk to count as speech
+    pub min_amplitude: i16,
+
+    /// API key required in the `xi-api-key` header (None accepts any key)
+    pub api_key: Option<String>,
+
+    /// Faults to inject
+    pub faults: Vec<MockFault>,
+
+    /// Delay before answering a commit or close request, in milliseconds
+    pub commit_delay_ms: u64,
+}
+
+impl MockServerConfig {
+    /// Create a configuration with a single "hello world" segment
+    pub fn new() -> Self {
+        Self {
+            transcripts: vec!["hello world".to_string()],
+            chunks_per_token: 2,
+            auto_commit: true,
+            min_amplitude: 0,
+            api_key: None,
+            faults: Vec::new(),
+            commit_delay_ms: 0,
+        }
+    }
+
+    /// Use a single script segment
+    pub fn with_transcript(mut self, text: impl Into<String>) -> Self {
+        self.transcripts = vec![text.into()];
+        self
+    }
+
+    /// Use several script segments
+    pub fn with_transcripts<I, S>(mut self, texts: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.transcripts = texts.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set how many non-silent chunks reveal one token
+    pub fn with_chunks_per_token(mut self, chunks: usize) -> Self {
+        self.chunks_per_token = chunks.max(1);
+        self
+    }
+
+    /// Only commit when the client asks for it (commit message, commit flag or close)
+    pub fn without_auto_commit(mut self) -> Self {
+        self.auto_commit = false;
+        self
+    }
+
+    /// Treat chunks whose peak amplitude is below `amplitude` as silence
+    pub fn with_min_amplitude(mut self, amplitude: i16) -> Self {
+        self.min_amplitude = amplitude;
+        self
+    }
+
+    /// Require the given API key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Inject a fault
+    pub fn with_fault(mut self, fault: MockFault) -> Self {
+        self.faults.push(fault);
+        self
+    }
+
+    /// Delay the reply to commit and close requests (a slow final transcript)
+    pub fn with_commit_delay(mut self, delay_ms: u64) -> Self {
+        self.commit_delay_ms = delay_ms;
+        self
+    }
+}
+
+impl Default for MockServerConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Record of what the mock server saw and sent during one connection
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MockSessionLog {
+    /// Session ID sent in `session_started`
+    pub session_id: String,
+
+    /// Query parameters of the handshake request
+    pub query: HashMap<String, String>,
+
+    /// Value of the `xi-api-key` header
+    pub api_key: Option<String>,
+
+    /// Value of the `Host` header
+    pub host: Option<String>,
+
+    /// Number of `input_audio_chunk` messages received
+    pub audio_chunks: usize,
+
+    /// Total number of PCM samples received
+    pub samples: usize,
+
+    /// `sample_rate` values received on audio chunks, in order
+    pub sample_rates: Vec<u32>,
+
+    /// `previous_text` values received on audio chunks, in order
+    pub previous_texts: Vec<String>,
+
+    /// Number of commit requests (commit messages and chunks with `commit: true`)
+    pub commits: usize,
+
+    /// Whether a `close` message was received
+    pub close_received: bool,
+
+    /// Partial transcripts sent
+    pub partials: Vec<String>,
+
+    /// Committed transcripts sent
+    pub committed: Vec<String>,
+}
+
+/// Shared server state
+struct MockState {
+    config: MockServerConfig,
+    faults: Mutex<Vec<MockFault>>,
+    sessions: Mutex<Vec<MockSessionLog>>,
+    next_segment: Mutex<usize>,
+}
+
+impl MockState {
+    /// Take the next handshake rejection, if any
+    fn take_handshake_fault(&self) -> Option<u16> {
+        let mut faults = self.faults.lock().ok()?;
+        let index = faults
+            .iter()
+            .position(|f| matches!(f, MockFault::RejectHandshake { .. }))?;
+
+        match faults.remove(index) {
+            MockFault::RejectHandshake { status } => Some(status),
+            _ => None,
+        }
+    }
+
+    /// Take the next session fault that is due after `chunks` audio chunks
+    fn take_due_fault(&self, chunks: usize) -> Option<MockFault> {
+        let mut faults = self.faults.lock().ok()?;
+        let index = faults
+            .iter()
+            .position(|f| f.after_chunks().is_some_and(|after| after <= chunks))?;
+
+        Some(faults.remove(index))
+    }
+
+    /// Register a new session and return its index
+    fn register_session(&self, log: MockSessionLog) -> usize {
+        let mut sessions = self.sessions.lock().expect("mock session log poisoned");
+        sessions.push(log);
+        sessions.len() - 1
+    }
+
+    /// Update the log of a session
+    fn update_session(&self, index: usize, update: impl FnOnce(&mut MockSessionLog)) {
+        if let Ok(mut sessions) = self.sessions.lock()
+            && let Some(log) = sessions.get_mut(index)
+        {
+            update(log);
+        }
+    }
+
+    /// Script segment to transcribe next
+    fn current_segment(&self) -> Option<String> {
+        if self.config.transcripts.is_empty() {
+            return None;
+        }
+
+        let index = *self.next_segment.lock().ok()?;
+        Some(self.config.transcripts[index % self.config.transcripts.len()].clone())
+    }
+
+    /// Move on to the next script segment
+    fn advance_segment(&self) {
+        if let Ok(mut index) = self.next_segment.lock() {
+            *index += 1;
+        }
+    }
+}
+
+/// Mock Scribe realtime server
+///
+/// Listens on a random port on `127.0.0.1` until shut down or dropped.
+pub struct MockScribeServer {
+    addr: SocketAddr,
+    state: Arc<MockState>,
+    accept_handle: JoinHandle<()>,
+}
+
+impl MockScribeServer {
+    /// Start the server on a random local port
+    ///
+    /// # Errors
+    /// Returns `NetworkError::ConnectionFailed` if the listener cannot be bound
+    pub async fn start(config: MockServerConfig) -> NetworkResult<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0")
+            .await
+            .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))?;
+        let addr = listener
+            .local_addr()
+            .map_err(|e| NetworkError::ConnectionFailed(e.to_string()))?;
+
+        let state = Arc::new(MockState {
+            faults: Mutex::new(config.faults.clone()),
+            config,
+            sessions: Mutex::new(Vec::new()),
+            next_segment: Mutex::new(0),
+        });
+
+        let accept_state = Arc::clone(&state);
+        let accept_handle = tokio::spawn(async move {
+            loop {
+                match listener.accept().await {
+                    Ok((stream, peer)) => {
+                        debug!("Mock server accepted connection from {}", peer);
+                        tokio::spawn(handle_connection(stream, Arc::clone(&accept_state)));
+                    }
+                    Err(e) => {
+                        warn!("Mock server failed to accept connection: {}", e);
+                        break;
+                    }
+                }
+            }
+        });
+
+        info!("Mock Scribe server listening on {}", addr);
+
+        Ok(Self {
+            addr,
+            state,
+            accept_handle,
+        })
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// WebSocket URL of the mock endpoint (`ws://127.0.0.1:<port>/v1/speech-to-text/realtime`)
+    pub fn url(&self) -> String {
+        format!("ws://{}{}", self.addr, MOCK_ENDPOINT_PATH)
+    }
+
+    /// Connection configuration pointing at this server
+    pub fn connection_config(&self, sample_rate: u32) -> ConnectionConfig {
+        ConnectionConfig::new(sample_rate).with_base_url(self.url())
+    }
+
+    /// Logs of all sessions handled so far, in connection order
+    pub fn sessions(&self) -> Vec<MockSessionLog> {
+        self.state
+            .sessions
+            .lock()
+            .map(|sessions| sessions.clone())
+            .unwrap_or_default()
+    }
+
+    /// Stop accepting connections
+    ///
+    /// Sessions that are already running finish on their own when the client disconnects.
+    pub async fn shutdown(mut self) {
+        self.accept_handle.abort();
+        let _ = (&mut self.accept_handle).await;
+    }
+}
+
+impl Drop for MockScribeServer {
+    fn drop(&mut self) {
+        self.accept_handle.abort();
+    }
+}
+
+/// Messages accepted from the client
+#[derive(Deserialize, Debug)]
+#[serde(tag = "message_type")]
+enum IncomingMessage {
+    #[serde(rename = "input_audio_chunk")]
+    InputAudioChunk {
+        audio_base_64: String,
+        #[serde(default)]
+        commit: Option<bool>,
+        #[serde(default)]
+        sample_rate: Option<u32>,
+        #[serde(default)]
+        previous_text: Option<String>,
+    },
+    #[serde(rename = "commit")]
+    Commit,
+    #[serde(rename = "close")]
+    Close,
+}
+
+/// Progress through the current script segment
+#[derive(Debug, Default)]
+struct SegmentProgress {
+    /// Non-silent chunks received for this segment
+    speech_chunks: usize,
+    /// Number of tokens already sent as partial transcript
+    revealed: usize,
+    /// Audio duration of the segment in seconds
+    duration_secs: f64,
+}
+
+/// What a connection needs to remember between messages
+struct ConnectionContext {
+    state: Arc<MockState>,
+    index: usize,
+    sample_rate: u32,
+    language_code: Option<String>,
+    include_timestamps: bool,
+    audio_chunks: usize,
+    segment: SegmentProgress,
+}
+
+impl ConnectionContext {
+    /// Handle an audio chunk and return the messages to send
+    fn on_audio(&mut self, audio_base_64: &str) -> Vec<ServerMessage> {
+        let samples = match decode_pcm(audio_base_64) {
+            Some(samples) => samples,
+            None => {
+                return vec![ServerMessage::InputError {
+                    error_message: "Invalid audio_base_64 payload".to_string(),
+                }];
+            }
+        };
+
+        self.audio_chunks += 1;
+        let sample_count = samples.len();
+        self.state.update_session(self.index, |log| {
+            log.audio_chunks += 1;
+            log.samples += sample_count;
+        });
+
+        let peak = samples.iter().map(|s| (*s as i32).abs()).max().unwrap_or(0);
+        if peak < self.state.config.min_amplitude as i32 {
+            return Vec::new();
+        }
+
+        let Some(text) = self.state.current_segment() else {
+            return Vec::new();
+        };
+
+        self.segment.speech_chunks += 1;
+        self.segment.duration_secs += sample_count as f64 / self.sample_rate.max(1) as f64;
+
+        let (tokens, separator) = tokenize(&text);
+        let revealed =
+            (self.segment.speech_chunks / self.state.config.chunks_per_token).min(tokens.len());
+
+        let mut messages = Vec::new();
+        if revealed > self.segment.revealed {
+            self.segment.revealed = revealed;
+            let partial = tokens[..revealed].join(separator);
+            self.state
+                .update_session(self.index, |log| log.partials.push(partial.clone()));
+            messages.push(ServerMessage::PartialTranscript { text: partial });
+        }
+
+        if self.state.config.auto_commit && revealed == tokens.len() {
+            messages.extend(self.commit());
+        }
+
+        messages
+    }
+
+    /// Commit the revealed part of the current segment
+    fn commit(&mut self) -> Option<ServerMessage> {
+        let segment = std::mem::take(&mut self.segment);
+        if segment.revealed == 0 {
+            return None;
+        }
+
+        let text = self.state.current_segment()?;
+        self.state.advance_segment();
+
+        let (tokens, separator) = tokenize(&text);
+        let tokens = &tokens[..segment.revealed.min(tokens.len())];
+        let committed = tokens.join(separator);
+
+        self.state
+            .update_session(self.index, |log| log.committed.push(committed.clone()));
+
+        if self.include_timestamps {
+            let step = segment.duration_secs / tokens.len() as f64;
+            let words = tokens
+                .iter()
+                .enumerate()
+                .map(|(i, token)| WordTimestamp {
+                    word: token.clone(),
+                    start: step * i as f64,
+                    end: step * (i + 1) as f64,
+                    word_type: "word".to_string(),
+                    logprob: Some(0.0),
+                })
+                .collect();
+
+            Some(ServerMessage::CommittedTranscriptWithTimestamps {
+                text: committed,
+                language_code: self.language_code.clone().unwrap_or_else(|| "en".to_string()),
+                words,
+            })
+        } else {
+            Some(ServerMessage::CommittedTranscript { text: committed })
+        }
+    }
+}
+
+/// Handle one client connection
+#[allow(clippy::result_large_err)]
+async fn handle_connection(stream: TcpStream, state: Arc<MockState>) {
+    let mut log = MockSessionLog::default();
+    let reject_status = state.take_handshake_fault();
+    let expected_key = state.config.api_key.clone();
+
+    let callback = |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+        log.query = request
+            .uri()
+            .query()
+            .map(|query| url::form_urlencoded::parse(query.as_bytes()).into_owned().collect())
+            .unwrap_or_default();
+        log.api_key = header_value(request, "xi-api-key");
+        log.host = header_value(request, "host");
+
+        if let Some(status) = reject_status {
+            return Err(error_response(status));
+        }
+
+        if let Some(expected) = expected_key
+            && log.api_key.as_deref() != Some(expected.as_str())
+        {
+            return Err(error_response(401));
+        }
+
+        Ok(response)
+    };
+
+    let mut ws = match accept_hdr_async(stream, callback).await {
+        Ok(ws) => ws,
+        Err(e) => {
+            debug!("Mock handshake rejected: {}", e);
+            return;
+        }
+    };
+
+    let sample_rate = log
+        .query
+        .get("sample_rate")
+        .and_then(|rate| rate.parse().ok())
+        .unwrap_or(16000);
+    let language_code = log.query.get("language_code").cloned();
+    let model_id = log
+        .query
+        .get("model_id")
+        .cloned()
+        .unwrap_or_else(|| "scribe_v2_realtime".to_string());
+    let include_timestamps = log.query.get("include_timestamps").map(String::as_str) == Some("true");
+
+    let index = state.register_session(log);
+    let session_id = format!("mock-session-{}", index + 1);
+    state.update_session(index, |log| log.session_id = session_id.clone());
+
+    let started = ServerMessage::SessionStarted {
+        session_id,
+        config: Some(SessionConfig {
+            sample_rate,
+            audio_format: "pcm_s16le".to_string(),
+            language_code: language_code.clone(),
+            model_id,
+            vad_commit_strategy: None,
+        }),
+    };
+    if !send(&mut ws, &started).await {
+        return;
+    }
+
+    let mut ctx = ConnectionContext {
+        state: Arc::clone(&state),
+        index,
+        sample_rate,
+        language_code,
+        include_timestamps,
+        audio_chunks: 0,
+        segment: SegmentProgress::default(),
+    };
+
+    while let Some(message) = ws.next().await {
+        let text = match message {
+            Ok(Message::Text(text)) => text,
+            // Keep polling so tungstenite can complete the closing handshake
+            Ok(_) => continue,
+            Err(e) => {
+                debug!("Mock session read error: {}", e);
+                break;
+            }
+        };
+
+        let incoming = match serde_json::from_str::<IncomingMessage>(&text) {
+            Ok(incoming) => incoming,
+            Err(e) => {
+                let error = ServerMessage::InputError {
+                    error_message: format!("Invalid message: {}", e),
+                };
+                if !send(&mut ws, &error).await {
+                    break;
+                }
+                continue;
+            }
+        };
+
+        let mut replies = Vec::new();
+        let mut close = false;
+
+        match incoming {
+            IncomingMessage::InputAudioChunk {
+                audio_base_64,
+                commit,
+                sample_rate,
+                previous_text,
+            } => {
+                state.update_session(index, |log| {
+                    log.sample_rates.extend(sample_rate);
+                    log.previous_texts.extend(previous_text);
+                });
+
+                replies.extend(ctx.on_audio(&audio_base_64));
+
+                if commit == Some(true) {
+                    state.update_session(index, |log| log.commits += 1);
+                    replies.extend(ctx.commit());
+                }
+            }
+            IncomingMessage::Commit => {
+                state.update_session(index, |log| log.commits += 1);
+                commit_delay(&state).await;
+                replies.extend(ctx.commit());
+            }
+            IncomingMessage::Close => {
+                state.update_session(index, |log| log.close_received = true);
+                commit_delay(&state).await;
+                replies.extend(ctx.commit());
+                close = true;
+            }
+        }
+
+        for reply in &replies {
+            if !send(&mut ws, reply).await {
+                return;
+            }
+        }
+
+        if close {
+            let _ = ws.close(None).await;
+            break;
+        }
+
+        while let Some(fault) = state.take_due_fault(ctx.audio_chunks) {
+            debug!("Injecting mock fault: {:?}", fault);
+            match fault {
+                MockFault::InputError { message, .. } => {
+                    let error = ServerMessage::InputError {
+                        error_message: message,
+                    };
+                    if !send(&mut ws, &error).await {
+                        return;
+                    }
+                }
+                MockFault::Raw { payload, .. } => {
+                    if ws.send(Message::Text(payload.into())).await.is_err() {
+                        return;
+                    }
+                }
+                MockFault::Disconnect { .. } => {
+                    // Dropping the stream closes the socket without a close frame
+                    return;
+                }
+                MockFault::RejectHandshake { .. } => {}
+            }
+        }
+    }
+
+    debug!("Mock session {} finished", index);
+}
+
+/// Wait before answering a commit or close request, if configured
+async fn commit_delay(state: &MockState) {
+    if state.config.commit_delay_ms > 0 {
+        tokio::time::sleep(Duration::from_millis(state.config.commit_delay_ms)).await;
+    }
+}
+
+/// Serialize and send a server message, returning `false` if the client is gone
+async fn send(ws: &mut WebSocketStream<TcpStream>, message: &ServerMessage) -> bool {
+    let json = match serde_json::to_string(message) {
+        Ok(json) => json,
+        Err(e) => {
+            warn!("Failed to serialize mock message: {}", e);
+            return false;
+        }
+    };
+
+    ws.send(Message::Text(json.into())).await.is_ok()
+}
+
+/// Read a request header as a string
+fn header_value(request: &Request, name: &str) -> Option<String> {
+    request
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Build an HTTP error response for a rejected handshake
+fn error_response(status: u16) -> ErrorResponse {
+    let status = StatusCode::from_u16(status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    let mut response = ErrorResponse::new(Some(status.to_string()));
+    *response.status_mut() = status;
+    response
+}
+
+/// Decode Base64 little-endian i16 PCM
+fn decode_pcm(audio_base_64: &str) -> Option<Vec<i16>> {
+    let bytes = STANDARD.decode(audio_base_64).ok()?;
+    Some(
+        bytes
+            .chunks_exact(2)
+            .map(|pair| i16::from_le_bytes([pair[0], pair[1]]))
+            .collect(),
+    )
+}
+
+/// Split a script segment into tokens and the separator used to join them
+///
+/// Whitespace-separated text is revealed word by word; text without spaces
+/// that contains non-ASCII characters (e.g. Chinese) is revealed per character.
+fn tokenize(text: &str) -> (Vec<String>, &'static str) {
+    let text = text.trim();
+
+    if text.contains(char::is_whitespace) || text.is_ascii() {
+        let words = text.split_whitespace().map(str::to_string).collect();
+        (words, " ")
+    } else {
+        let chars = text.chars().map(|c| c.to_string()).collect();
+        (chars, "")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize_words() {
+        let (tokens, separator) = tokenize(" hello  world ");
+        assert_eq!(tokens, vec!["hello", "world"]);
+        assert_eq!(separator, " ");
+    }
+
+    #[test]
+    fn test_tokenize_cjk() {
+        let (tokens, separator) = tokenize("你好世界");
+        assert_eq!(tokens, vec!["你", "好", "世", "界"]);
+        assert_eq!(separator, "");
+    }
+
+    #[test]
+    fn test_decode_pcm() {
+        let bytes: Vec<u8> = [1i16, -2, i16::MAX]
+            .iter()
+            .flat_map(|s| s.to_le_bytes())
+            .collect();
+        let encoded = STANDARD.encode(bytes);
+
+        assert_eq!(decode_pcm(&encoded), Some(vec![1, -2, i16::MAX]));
+        assert_eq!(decode_pcm("not base64!"), None);
+    }
+
+    #[test]
+    fn test_mock_server_config_builder() {
+        let config = MockServerConfig::new()
+            .with_transcripts(["one", "two"])
+            .with_chunks_per_token(0)
+            .without_auto_commit()
+            .with_min_amplitude(100)
+            .with_api_key("key")
+            .with_fault(MockFault::Disconnect { after_chunks: 3 })
+            .with_commit_delay(50);
+
+        assert_eq!(config.transcripts, vec!["one", "two"]);
+        assert_eq!(config.chunks_per_token, 1);
+        assert!(!config.auto_commit);
+        assert_eq!(config.min_amplitude, 100);
+        assert_eq!(config.api_key, Some("key".to_string()));
+        assert_eq!(config.faults.len(), 1);
+        assert_eq!(config.commit_delay_ms, 50);
+    }
+
+    #[test]
+    fn test_incoming_message_parsing() {
+        let chunk: IncomingMessage = serde_json::from_str(
+            r#"{"message_type":"input_audio_chunk","audio_base_64":"AAA=","sample_rate":16000}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            chunk,
+            IncomingMessage::InputAudioChunk {
+                sample_rate: Some(16000),
+                ..
+            }
+        ));
+
+        let commit: IncomingMessage =
+            serde_json::from_str(r#"{"message_type":"commit"}"#).unwrap();
+        assert!(matches!(commit, IncomingMessage::Commit));
+
+        let close: IncomingMessage = serde_json::from_str(r#"{"message_type":"close"}"#).unwrap();
+        assert!(matches!(close, IncomingMessage::Close));
+    }
+}
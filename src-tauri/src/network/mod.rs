@@ -12,13 +12,27 @@ pub mod error;
 /// WebSocket message type definitions
 pub mod messages;
 
+/// Local mock of the Scribe realtime API for offline testing
+#[cfg(feature = "mock-server")]
+pub mod mock;
+
+/// Automatic reconnection with audio replay
+pub mod reconnect;
+
 /// Async tasks for concurrent send/receive operations
 pub mod tasks;
 
 // Re-export commonly used types
-pub use connection::{ConnectionConfig, ScribeConnection, WsReader, WsWriter};
+pub use connection::{
+    ConnectionConfig, ScribeConnection, WsReader, WsWriter, DEFAULT_BASE_URL,
+    DEFAULT_COMMIT_TIMEOUT_MS,
+};
 pub use error::{NetworkError, NetworkResult};
 pub use messages::{
-    ClientMessage, CloseMessage, CommitMessage, InputAudioChunk, ServerMessage, SessionConfig,
-    VadConfig, WordTimestamp,
+    append_transcript, ClientMessage, CloseMessage, CommitMessage, InputAudioChunk, ServerMessage,
+    SessionConfig, VadConfig, WordTimestamp,
+};
+pub use reconnect::{
+    reconnecting_task, ConnectionEvent, ReconnectPolicy, ReplayBuffer, CLOSE_TIMEOUT_MS,
 };
+pub use tasks::OutgoingCommand;
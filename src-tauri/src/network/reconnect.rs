@@ -0,0 +1,712 @@
+/// Automatic reconnection for streaming sessions
+///
+/// This module keeps a transcription stream alive across dropped WebSocket
+/// connections. Audio that the server has not yet acknowledged with a
+/// committed transcript is kept in a bounded replay buffer and re-sent on the
+/// new connection, together with the text committed so far as `previous_text`.
+///
+/// When audio input ends, the stream is finished gracefully: audio sent since
+/// the last commit is committed, the final committed transcript is awaited (up
+/// to `ConnectionConfig::commit_timeout_ms`), and only then is the connection
+/// closed. Messages sent while the server closes are still collected for up to
+/// [`CLOSE_TIMEOUT_MS`].
+
+use crate::network::connection::{ConnectionConfig, ScribeConnection};
+use crate::network::error::{NetworkError, NetworkResult};
+use crate::network::messages::{ServerMessage, append_transcript};
+use crate::network::tasks::{OutgoingCommand, receiver_task, sender_task};
+use std::collections::VecDeque;
+use std::time::Duration;
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+
+/// Maximum number of characters of committed text sent as `previous_text`
+pub const MAX_PREVIOUS_TEXT_CHARS: usize = 500;
+
+/// Time allowed for the server to close the connection after the close message
+///
+/// Independent of the commit timeout, so that a final transcript sent while
+/// closing is not dropped even when `commit_timeout_ms` is 0
+pub const CLOSE_TIMEOUT_MS: u64 = 2000;
+
+/// Backoff and buffering policy for reconnecting a dropped session
+///
+/// # Example
+/// ```
+/// use raflow_lib::network::ReconnectPolicy;
+/// use std::time::Duration;
+///
+/// let policy = ReconnectPolicy::default();
+/// assert_eq!(policy.delay_for(1), Duration::from_millis(250));
+/// assert_eq!(policy.delay_for(2), Duration::from_millis(500));
+///
+/// assert!(!ReconnectPolicy::disabled().is_enabled());
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Maximum reconnection attempts per drop (0 disables reconnection)
+    pub max_attempts: u32,
+
+    /// Delay before the first attempt in milliseconds
+    pub initial_delay_ms: u64,
+
+    /// Upper bound for the delay between attempts in milliseconds
+    pub max_delay_ms: u64,
+
+    /// Factor applied to the delay after each failed attempt
+    pub multiplier: f64,
+
+    /// Number of unacknowledged audio chunks kept for replay
+    pub replay_buffer_chunks: usize,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_delay_ms: 250,
+            max_delay_ms: 4000,
+            multiplier: 2.0,
+            // 100ms chunks: the last 30 seconds of speech
+            replay_buffer_chunks: 300,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Policy that never reconnects
+    pub fn disabled() -> Self {
+        Self {
+            max_attempts: 0,
+            ..Default::default()
+        }
+    }
+
+    /// Set the maximum number of attempts per drop
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Set the initial and maximum backoff delays in milliseconds
+    pub fn with_delays(mut self, initial_delay_ms: u64, max_delay_ms: u64) -> Self {
+        self.initial_delay_ms = initial_delay_ms;
+        self.max_delay_ms = max_delay_ms;
+        self
+    }
+
+    /// Set the replay buffer capacity in chunks
+    pub fn with_replay_buffer(mut self, chunks: usize) -> Self {
+        self.replay_buffer_chunks = chunks;
+        self
+    }
+
+    /// Whether reconnection is enabled
+    pub fn is_enabled(&self) -> bool {
+        self.max_attempts > 0
+    }
+
+    /// Delay before the given attempt (1-based)
+    pub fn delay_for(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1).min(32) as i32;
+        let delay = self.initial_delay_ms as f64 * self.multiplier.max(1.0).powi(exponent);
+        Duration::from_millis(delay.min(self.max_delay_ms as f64) as u64)
+    }
+}
+
+/// Bounded buffer of audio chunks not yet acknowledged by the server
+///
+/// The server acknowledges audio by committing a transcript; everything
+/// sent before the commit is then dropped from the buffer. Audio still in
+/// flight when the commit arrives is dropped too, which is harmless with
+/// VAD commits since they follow a pause. When the buffer is full, the
+/// oldest chunk is evicted.
+#[derive(Debug, Clone)]
+pub struct ReplayBuffer {
+    chunks: VecDeque<String>,
+    capacity: usize,
+    evicted: u64,
+}
+
+impl ReplayBuffer {
+    /// Create a buffer holding at most `capacity` chunks
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            chunks: VecDeque::with_capacity(capacity.min(1024)),
+            capacity,
+            evicted: 0,
+        }
+    }
+
+    /// Add a chunk, evicting the oldest one if the buffer is full
+    pub fn push(&mut self, chunk: String) {
+        if self.capacity == 0 {
+            self.evicted += 1;
+            return;
+        }
+
+        if self.chunks.len() == self.capacity {
+            self.chunks.pop_front();
+            self.evicted += 1;
+        }
+        self.chunks.push_back(chunk);
+    }
+
+    /// Drop all buffered chunks after the server committed a transcript
+    pub fn acknowledge(&mut self) {
+        self.chunks.clear();
+    }
+
+    /// Buffered chunks, oldest first
+    pub fn chunks(&self) -> impl Iterator<Item = &String> {
+        self.chunks.iter()
+    }
+
+    /// Number of buffered chunks
+    pub fn len(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Whether the buffer is empty
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Number of chunks evicted because the buffer was full
+    pub fn evicted(&self) -> u64 {
+        self.evicted
+    }
+}
+
+/// Events produced by [`reconnecting_task`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConnectionEvent {
+    /// Message received from the server
+    Message(ServerMessage),
+
+    /// The connection dropped; a new attempt starts after `delay`
+    Reconnecting {
+        attempt: u32,
+        delay: Duration,
+        reason: String,
+    },
+
+    /// A new connection is up; buffered audio is replayed on it
+    Reconnected {
+        attempts: u32,
+        replayed_chunks: usize,
+    },
+
+    /// Reconnection was abandoned; the stream has ended
+    ReconnectFailed { attempts: u32, error: String },
+}
+
+/// How a single connection ended
+enum LinkOutcome {
+    /// Audio input ended and the connection closed, or the server reported
+    /// a permanent error
+    Finished(NetworkResult<()>),
+    /// The connection dropped while audio was still flowing, or the server
+    /// ended its session with a retryable error
+    Dropped(NetworkError),
+}
+
+/// Stream audio over a connection, reconnecting when it drops
+///
+/// Forwards Base64-encoded audio from `audio_rx` to the server and server
+/// messages to `event_tx`. When the connection drops while audio is still
+/// flowing, or the server ends its session because of a rate limit or the
+/// session time limit, the task reconnects following `policy`, replays the buffered
+/// unacknowledged audio and sends the committed text as `previous_text`.
+///
+/// Closing `audio_rx` stops the stream gracefully: if audio was sent since
+/// the last committed transcript, a commit is requested and the task waits
+/// up to `config.commit_timeout_ms` for the committed transcript before
+/// sending the close message. The task ends once the
+/// connection is closed, when reconnection fails, or when the server
+/// reports a permanent error such as an exhausted quota.
+///
+/// # Arguments
+/// * `connection` - An established connection
+/// * `api_key` - API key used for reconnection
+/// * `config` - Connection configuration used for reconnection
+/// * `policy` - Backoff and replay buffer policy
+/// * `audio_rx` - Receiver for Base64-encoded audio data
+/// * `event_tx` - Sender for server messages and reconnection events
+///
+/// # Errors
+/// Returns the last connection error if reconnection is abandoned, or the
+/// `NetworkError` matching a permanent server error
+pub async fn reconnecting_task(
+    connection: ScribeConnection,
+    api_key: String,
+    config: ConnectionConfig,
+    policy: ReconnectPolicy,
+    mut audio_rx: mpsc::Receiver<String>,
+    event_tx: mpsc::Sender<ConnectionEvent>,
+) -> NetworkResult<()> {
+    info!("Reconnecting task started");
+
+    let mut replay = ReplayBuffer::new(policy.replay_buffer_chunks);
+    let mut committed_text = String::new();
+    let mut audio_closed = false;
+    let mut connection = connection;
+
+    loop {
+        let outcome = run_link(
+            connection,
+            &config,
+            &mut audio_rx,
+            &mut audio_closed,
+            &mut replay,
+            &mut committed_text,
+            &event_tx,
+        )
+        .await;
+
+        let error = match outcome {
+            LinkOutcome::Finished(result) => {
+                info!("Reconnecting task completed");
+                return result;
+            }
+            LinkOutcome::Dropped(error) => error,
+        };
+
+        warn!("Connection dropped: {}", error);
+
+        if !policy.is_enabled() {
+            return Err(error);
+        }
+
+        if audio_closed && replay.is_empty() {
+            debug!("Audio input already ended and nothing to replay, not reconnecting");
+            return Ok(());
+        }
+
+        connection = match reconnect(
+            &api_key,
+            &config,
+            &policy,
+            &error.to_string(),
+            &mut audio_rx,
+            &mut audio_closed,
+            &mut replay,
+            &event_tx,
+        )
+        .await
+        {
+            Ok(Some(connection)) => connection,
+            Ok(None) => return Ok(()),
+            Err(e) => return Err(e),
+        };
+    }
+}
+
+/// Run one connection until it finishes or drops
+///
+/// The committed text is sent first as context, followed by any audio
+/// buffered from a previous connection.
+async fn run_link(
+    connection: ScribeConnection,
+    config: &ConnectionConfig,
+    audio_rx: &mut mpsc::Receiver<String>,
+    audio_closed: &mut bool,
+    replay: &mut ReplayBuffer,
+    committed_text: &mut String,
+    event_tx: &mpsc::Sender<ConnectionEvent>,
+) -> LinkOutcome {
+    let (writer, reader) = connection.split();
+
+    let (command_tx, command_rx) = mpsc::channel::<OutgoingCommand>(100);
+    let (msg_tx, mut msg_rx) = mpsc::channel::<ServerMessage>(100);
+
+    let sender = tokio::spawn(sender_task(writer, command_rx, config.sample_rate));
+    let receiver = tokio::spawn(receiver_task(reader, msg_tx));
+
+    let commit_timeout = Duration::from_millis(config.commit_timeout_ms);
+
+    // Audio sent since the last commit, including audio replayed after a
+    // reconnect, may hold speech the server has not reported yet
+    let mut pending_speech = !replay.is_empty();
+    let mut commit_deadline: Option<Instant> = None;
+
+    let mut queued = replay_commands(replay, committed_text);
+    if *audio_closed {
+        queued.extend(finish_command(pending_speech));
+        commit_deadline = pending_deadline(pending_speech, commit_timeout);
+    }
+
+    for command in queued {
+        if command_tx.send(command).await.is_err() {
+            receiver.abort();
+            return LinkOutcome::Dropped(sender_failure(sender).await);
+        }
+    }
+
+    let finished_in_time = loop {
+        if *audio_closed && !pending_speech {
+            break true;
+        }
+
+        let deadline = async {
+            match commit_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            audio = audio_rx.recv(), if !*audio_closed => {
+                let command = match audio {
+                    Some(chunk) => {
+                        replay.push(chunk.clone());
+                        pending_speech = true;
+                        OutgoingCommand::Audio(chunk)
+                    }
+                    None => {
+                        debug!("Audio input ended, finishing stream");
+                        *audio_closed = true;
+                        commit_deadline = pending_deadline(pending_speech, commit_timeout);
+                        match finish_command(pending_speech) {
+                            Some(command) => command,
+                            None => continue,
+                        }
+                    }
+                };
+
+                if command_tx.send(command).await.is_err() {
+                    receiver.abort();
+                    return LinkOutcome::Dropped(sender_failure(sender).await);
+                }
+            },
+            message = msg_rx.recv() => match message {
+                Some(message) => {
+                    // Errors such as an exhausted quota end the stream for good;
+                    // rate and session time limits only end this server session
+                    let error = message.to_network_error().filter(|e| {
+                        !e.is_retryable()
+                            || matches!(
+                                e,
+                                NetworkError::RateLimited(_) | NetworkError::SessionTimeout(_)
+                            )
+                    });
+
+                    track_message(&message, replay, committed_text, &mut pending_speech);
+                    let _ = event_tx.send(ConnectionEvent::Message(message)).await;
+
+                    if let Some(error) = error {
+                        sender.abort();
+                        receiver.abort();
+                        if error.is_retryable() {
+                            warn!("Server ended the session: {}", error);
+                            return LinkOutcome::Dropped(error);
+                        }
+                        warn!("Server reported a permanent error: {}", error);
+                        return LinkOutcome::Finished(Err(error));
+                    }
+                }
+                None => {
+                    sender.abort();
+                    let reason = match receiver.await {
+                        Ok(Ok(())) => "connection closed by server".to_string(),
+                        Ok(Err(e)) => e.to_string(),
+                        Err(e) => format!("Receiver task failed: {}", e),
+                    };
+
+                    // Nothing left to recover once the audio is committed
+                    if *audio_closed && replay.is_empty() {
+                        return LinkOutcome::Finished(Ok(()));
+                    }
+                    return LinkOutcome::Dropped(NetworkError::ConnectionFailed(reason));
+                }
+            },
+            _ = deadline => {
+                warn!(
+                    "No committed transcript within {:?}, closing anyway",
+                    commit_timeout
+                );
+                break false;
+            }
+        }
+    };
+
+    if finished_in_time {
+        debug!("Final transcript committed, closing connection");
+    }
+
+    // Ask the server to close, then drain whatever it sends before it does
+    if command_tx.send(OutgoingCommand::Close).await.is_err() {
+        warn!("Sender stopped before the close message was sent");
+    }
+
+    let drain = async {
+        while let Some(message) = msg_rx.recv().await {
+            track_message(&message, replay, committed_text, &mut pending_speech);
+            let _ = event_tx.send(ConnectionEvent::Message(message)).await;
+        }
+    };
+
+    let close_timeout = Duration::from_millis(CLOSE_TIMEOUT_MS);
+    let drained = tokio::time::timeout(close_timeout, drain).await.is_ok();
+
+    if !drained {
+        warn!("Server did not close the connection in time");
+        sender.abort();
+        receiver.abort();
+        return LinkOutcome::Finished(Ok(()));
+    }
+
+    let sender_result = match sender.await {
+        Ok(result) => result,
+        Err(e) => Err(NetworkError::ProtocolError(format!(
+            "Sender task failed: {}",
+            e
+        ))),
+    };
+    let receiver_result = match receiver.await {
+        Ok(result) => result,
+        Err(e) => Err(NetworkError::ProtocolError(format!(
+            "Receiver task failed: {}",
+            e
+        ))),
+    };
+
+    LinkOutcome::Finished(receiver_result.and(sender_result))
+}
+
+/// Commands that resume a session on a fresh connection
+///
+/// The committed text is sent as context whenever there is any, even if all
+/// audio was acknowledged; it is attached to the next audio chunk, replayed
+/// or live.
+fn replay_commands(replay: &ReplayBuffer, committed_text: &str) -> Vec<OutgoingCommand> {
+    if replay.evicted() > 0 {
+        warn!(
+            "Replay buffer overflowed, {} chunks could not be kept",
+            replay.evicted()
+        );
+    }
+
+    let mut commands = Vec::with_capacity(replay.len() + 1);
+    if !committed_text.is_empty() {
+        commands.push(OutgoingCommand::UpdateContext(committed_text.to_string()));
+    }
+    commands.extend(replay.chunks().cloned().map(OutgoingCommand::Audio));
+    commands
+}
+
+/// Command sent once audio input has ended: commit audio sent since the last commit, if any
+fn finish_command(pending_speech: bool) -> Option<OutgoingCommand> {
+    if pending_speech {
+        debug!("Committing pending speech before closing");
+        Some(OutgoingCommand::Commit)
+    } else {
+        None
+    }
+}
+
+/// Deadline for the committed transcript after a finishing commit
+fn pending_deadline(pending_speech: bool, commit_timeout: Duration) -> Option<Instant> {
+    pending_speech.then(|| Instant::now() + commit_timeout)
+}
+
+/// Error reported when the sender task stopped early
+async fn sender_failure(sender: tokio::task::JoinHandle<NetworkResult<()>>) -> NetworkError {
+    let reason = match sender.await {
+        Ok(Err(e)) => e.to_string(),
+        Ok(Ok(())) => "sender stopped".to_string(),
+        Err(e) => format!("Sender task failed: {}", e),
+    };
+    NetworkError::ConnectionFailed(reason)
+}
+
+/// Update acknowledgement state for a server message
+fn track_message(
+    message: &ServerMessage,
+    replay: &mut ReplayBuffer,
+    committed_text: &mut String,
+    pending_speech: &mut bool,
+) {
+    if message.is_committed() {
+        replay.acknowledge();
+        append_context(committed_text, message.text().unwrap_or_default());
+        *pending_speech = false;
+    } else if message.is_partial() && !message.text().unwrap_or_default().trim().is_empty() {
+        *pending_speech = true;
+    }
+}
+
+/// Reconnect with backoff while buffering live audio
+///
+/// Returns the new connection, or `Ok(None)` if audio input ends during
+/// reconnection with nothing left to replay.
+#[allow(clippy::too_many_arguments)]
+async fn reconnect(
+    api_key: &str,
+    config: &ConnectionConfig,
+    policy: &ReconnectPolicy,
+    reason: &str,
+    audio_rx: &mut mpsc::Receiver<String>,
+    audio_closed: &mut bool,
+    replay: &mut ReplayBuffer,
+    event_tx: &mpsc::Sender<ConnectionEvent>,
+) -> NetworkResult<Option<ScribeConnection>> {
+    let mut last_error = NetworkError::ConnectionClosed;
+    let mut reason = reason.to_string();
+
+    for attempt in 1..=policy.max_attempts {
+        let delay = policy.delay_for(attempt);
+        info!("Reconnection attempt {} in {:?}", attempt, delay);
+
+        let _ = event_tx
+            .send(ConnectionEvent::Reconnecting {
+                attempt,
+                delay,
+                reason: reason.clone(),
+            })
+            .await;
+
+        // Keep buffering live audio while waiting and connecting
+        let sleep = tokio::time::sleep(delay);
+        tokio::pin!(sleep);
+        buffer_until(&mut sleep, audio_rx, audio_closed, replay).await;
+
+        if *audio_closed && replay.is_empty() {
+            return Ok(None);
+        }
+
+        let connect = ScribeConnection::connect(api_key, config);
+        tokio::pin!(connect);
+        let result = buffer_until(&mut connect, audio_rx, audio_closed, replay).await;
+
+        match result {
+            Ok(connection) => {
+                info!(
+                    "Reconnected after {} attempt(s), replaying {} chunks",
+                    attempt,
+                    replay.len()
+                );
+                let _ = event_tx
+                    .send(ConnectionEvent::Reconnected {
+                        attempts: attempt,
+                        replayed_chunks: replay.len(),
+                    })
+                    .await;
+                return Ok(Some(connection));
+            }
+            Err(e) if !e.is_retryable() => {
+                warn!("Reconnection failed permanently: {}", e);
+                let _ = event_tx
+                    .send(ConnectionEvent::ReconnectFailed {
+                        attempts: attempt,
+                        error: e.to_string(),
+                    })
+                    .await;
+                return Err(e);
+            }
+            Err(e) => {
+                warn!("Reconnection attempt {} failed: {}", attempt, e);
+                reason = e.to_string();
+                last_error = e;
+            }
+        }
+    }
+
+    let _ = event_tx
+        .send(ConnectionEvent::ReconnectFailed {
+            attempts: policy.max_attempts,
+            error: last_error.to_string(),
+        })
+        .await;
+
+    Err(last_error)
+}
+
+/// Drive `future` to completion while buffering incoming audio
+async fn buffer_until<F: std::future::Future + Unpin>(
+    future: &mut F,
+    audio_rx: &mut mpsc::Receiver<String>,
+    audio_closed: &mut bool,
+    replay: &mut ReplayBuffer,
+) -> F::Output {
+    loop {
+        tokio::select! {
+            output = &mut *future => return output,
+            audio = audio_rx.recv(), if !*audio_closed => match audio {
+                Some(chunk) => replay.push(chunk),
+                None => *audio_closed = true,
+            },
+        }
+    }
+}
+
+/// Append committed text to the context, keeping only the most recent part
+fn append_context(context: &mut String, text: &str) {
+    append_transcript(context, text);
+
+    let len = context.chars().count();
+    if len > MAX_PREVIOUS_TEXT_CHARS {
+        *context = context
+            .chars()
+            .skip(len - MAX_PREVIOUS_TEXT_CHARS)
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_backoff() {
+        let policy = ReconnectPolicy::default().with_delays(100, 1000);
+
+        assert_eq!(policy.delay_for(1), Duration::from_millis(100));
+        assert_eq!(policy.delay_for(2), Duration::from_millis(200));
+        assert_eq!(policy.delay_for(4), Duration::from_millis(800));
+        assert_eq!(policy.delay_for(5), Duration::from_millis(1000));
+        assert_eq!(policy.delay_for(100), Duration::from_millis(1000));
+    }
+
+    #[test]
+    fn test_policy_disabled() {
+        let policy = ReconnectPolicy::disabled();
+        assert!(!policy.is_enabled());
+        assert!(ReconnectPolicy::default().is_enabled());
+        assert!(ReconnectPolicy::default().with_max_attempts(1).is_enabled());
+    }
+
+    #[test]
+    fn test_replay_buffer_bounded() {
+        let mut buffer = ReplayBuffer::new(2);
+        buffer.push("a".to_string());
+        buffer.push("b".to_string());
+        buffer.push("c".to_string());
+
+        assert_eq!(buffer.len(), 2);
+        assert_eq!(buffer.evicted(), 1);
+        assert_eq!(buffer.chunks().collect::<Vec<_>>(), vec!["b", "c"]);
+
+        buffer.acknowledge();
+        assert!(buffer.is_empty());
+    }
+
+    #[test]
+    fn test_replay_buffer_zero_capacity() {
+        let mut buffer = ReplayBuffer::new(0);
+        buffer.push("a".to_string());
+
+        assert!(buffer.is_empty());
+        assert_eq!(buffer.evicted(), 1);
+    }
+
+    #[test]
+    fn test_append_context_keeps_tail() {
+        let mut context = "a".repeat(MAX_PREVIOUS_TEXT_CHARS);
+        append_context(&mut context, "end");
+
+        assert_eq!(context.chars().count(), MAX_PREVIOUS_TEXT_CHARS);
+        assert!(context.ends_with("a end"));
+    }
+}
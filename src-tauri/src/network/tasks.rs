@@ -5,30 +5,51 @@
 
 use crate::network::connection::{WsReader, WsWriter};
 use crate::network::error::{NetworkError, NetworkResult};
-use crate::network::messages::{ClientMessage, InputAudioChunk, ServerMessage};
+use crate::network::messages::{
+    ClientMessage, CloseMessage, CommitMessage, InputAudioChunk, ServerMessage,
+};
 use futures_util::{SinkExt, StreamExt};
 use tokio::sync::mpsc;
 use tokio_tungstenite::tungstenite::Message;
 use tracing::{debug, error, info, warn};
 
-/// Sender task that reads audio data from a channel and sends it via WebSocket
+/// Command consumed by [`sender_task`]
+#[derive(Debug, Clone, PartialEq)]
+pub enum OutgoingCommand {
+    /// Send a Base64-encoded audio chunk
+    Audio(String),
+
+    /// Ask the server to commit the current segment
+    Commit,
+
+    /// Ask the server to close the session, then stop the sender
+    Close,
+
+    /// Send the given text as `previous_text` with the next audio chunk
+    UpdateContext(String),
+}
+
+/// Sender task that reads commands from a channel and sends them via WebSocket
 ///
-/// This task continuously reads Base64-encoded audio chunks from the provided
-/// receiver channel and sends them to the server via WebSocket. It runs until
-/// either the channel is closed or an error occurs.
+/// This task continuously reads [`OutgoingCommand`]s from the provided
+/// receiver channel and turns them into client messages. The first audio
+/// chunk announces `sample_rate`, and a context update is attached to the
+/// next audio chunk as `previous_text`. It runs until a `Close` command is
+/// sent, the channel is closed or an error occurs.
 ///
 /// # Arguments
 /// * `ws_writer` - The write half of the WebSocket stream
-/// * `audio_rx` - Receiver for Base64-encoded audio data
+/// * `command_rx` - Receiver for outgoing commands
+/// * `sample_rate` - Sample rate of the streamed audio, usually `ConnectionConfig::sample_rate`
 ///
 /// # Returns
-/// `Ok(())` if the task completes normally (channel closed), or an error
+/// `Ok(())` if the task completes normally (close command or channel closed), or an error
 ///
 /// # Example
 /// ```no_run
 /// use tokio::sync::mpsc;
 /// use raflow_lib::network::{ScribeConnection, ConnectionConfig};
-/// use raflow_lib::network::tasks::sender_task;
+/// use raflow_lib::network::tasks::{sender_task, OutgoingCommand};
 ///
 /// #[tokio::main]
 /// async fn main() {
@@ -36,58 +57,72 @@ use tracing::{debug, error, info, warn};
 ///     let conn = ScribeConnection::connect("api-key", &config).await.unwrap();
 ///     let (writer, _reader) = conn.split();
 ///
-///     let (audio_tx, audio_rx) = mpsc::channel(100);
+///     let (command_tx, command_rx) = mpsc::channel(100);
 ///
-///     tokio::spawn(async move {
-///         sender_task(writer, audio_rx).await
-///     });
+///     tokio::spawn(sender_task(writer, command_rx, config.sample_rate));
+///
+///     command_tx.send(OutgoingCommand::Audio("AAAA".to_string())).await.unwrap();
+///     command_tx.send(OutgoingCommand::Commit).await.unwrap();
 /// }
 /// ```
 pub async fn sender_task(
     mut ws_writer: WsWriter,
-    mut audio_rx: mpsc::Receiver<String>,
+    mut command_rx: mpsc::Receiver<OutgoingCommand>,
+    sample_rate: u32,
 ) -> NetworkResult<()> {
     info!("Sender task started");
 
     let mut chunk_count = 0u64;
+    let mut pending_context: Option<String> = None;
+
+    while let Some(command) = command_rx.recv().await {
+        let message = match command {
+            OutgoingCommand::Audio(audio_base64) => {
+                chunk_count += 1;
+                debug!(
+                    "Sending audio chunk #{} (size: {} bytes)",
+                    chunk_count,
+                    audio_base64.len()
+                );
+
+                let mut chunk = InputAudioChunk::new(audio_base64);
+
+                // For the first chunk, include sample rate
+                if chunk_count == 1 {
+                    chunk = chunk.with_sample_rate(sample_rate);
+                }
+                if let Some(context) = pending_context.take() {
+                    chunk = chunk.with_previous_text(context);
+                }
 
-    while let Some(audio_base64) = audio_rx.recv().await {
-        chunk_count += 1;
-        debug!(
-            "Sending audio chunk #{} (size: {} bytes)",
-            chunk_count,
-            audio_base64.len()
-        );
-
-        // Create audio chunk message
-        let chunk = InputAudioChunk::new(audio_base64);
-
-        // For the first chunk, include sample rate
-        let message = if chunk_count == 1 {
-            chunk.with_sample_rate(16000)
-        } else {
-            chunk
+                ClientMessage::InputAudioChunk(chunk)
+            }
+            OutgoingCommand::Commit => {
+                debug!("Sending commit");
+                ClientMessage::Commit(CommitMessage::new())
+            }
+            OutgoingCommand::Close => {
+                debug!("Sending close");
+                ClientMessage::Close(CloseMessage::new())
+            }
+            OutgoingCommand::UpdateContext(text) => {
+                debug!("Context updated ({} chars)", text.chars().count());
+                pending_context = Some(text).filter(|text| !text.is_empty());
+                continue;
+            }
         };
 
-        // Serialize to JSON
-        let json = serde_json::to_string(&ClientMessage::InputAudioChunk(message))
-            .map_err(NetworkError::SerializationError)?;
-
-        // Send via WebSocket
-        ws_writer
-            .send(Message::Text(json.into()))
-            .await
-            .map_err(NetworkError::WebSocketError)?;
+        send_message(&mut ws_writer, &message).await?;
 
-        debug!("Audio chunk #{} sent successfully", chunk_count);
+        if matches!(message, ClientMessage::Close(_)) {
+            info!("Close message sent");
+            break;
+        }
     }
 
-    info!(
-        "Sender task completed: {} chunks sent, channel closed",
-        chunk_count
-    );
+    info!("Sender task completed: {} chunks sent", chunk_count);
 
-    // Send close message before shutting down
+    // Close the WebSocket before shutting down
     if let Err(e) = ws_writer.close().await {
         warn!("Failed to close WebSocket writer: {}", e);
     }
@@ -95,6 +130,16 @@ pub async fn sender_task(
     Ok(())
 }
 
+/// Serialize a client message and send it via WebSocket
+async fn send_message(ws_writer: &mut WsWriter, message: &ClientMessage) -> NetworkResult<()> {
+    let json = serde_json::to_string(message).map_err(NetworkError::SerializationError)?;
+
+    ws_writer
+        .send(Message::Text(json.into()))
+        .await
+        .map_err(NetworkError::WebSocketError)
+}
+
 /// Receiver task that reads messages from WebSocket and forwards them to a channel
 ///
 /// This task continuously reads messages from the WebSocket stream, deserializes
@@ -150,6 +195,10 @@ pub async fn receiver_task(
                             std::mem::discriminant(&server_msg)
                         );
 
+                        if let ServerMessage::Unknown { message_type, .. } = &server_msg {
+                            warn!("Received unknown message type: {}", message_type);
+                        }
+
                         // Forward to channel
                         if let Err(e) = message_tx.send(server_msg).await {
                             warn!("Failed to forward message: receiver dropped ({})", e);
@@ -0,0 +1,500 @@
+//! 会话控制器模块
+//!
+//! 应用内唯一的会话编排入口：全局热键、Tauri 命令都通过它启动、停止和取消会话，
+//! 保证同一时刻只有一个 [`RaFlowSession`]。
+//!
+//! 由于 TranscriptionSession 包含 cpal::Stream（不是 Send + Sync），
+//! 控制器在专用线程上持有会话，通过 channel 接收命令。
+//! 开启麦克风预热时，预热麦克风同样由该线程持有，在会话之间保持打开。
+//! 打开麦克风前按 [`DeviceSelector`](crate::audio::DeviceSelector) 的规则匹配输入设备，
+//! 设备被系统重命名或重新编号后仍能找到。
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tauri::{AppHandle, Manager};
+use tokio::sync::{mpsc, oneshot};
+
+use super::{transcription_options, RaFlowSession, SessionConfig};
+use crate::audio::{DeviceMatch, WarmMicrophone};
+use crate::state::{GlobalConfig, StateTransitionContext};
+use crate::text::{ProcessingChain, VoiceCommandInterpreter};
+use crate::transcription::TranscriptionOptions;
+
+/// 会话命令
+#[derive(Debug)]
+enum SessionCommand {
+    /// 启动会话
+    Start {
+        options: TranscriptionOptions,
+        config: SessionConfig,
+        response: oneshot::Sender<Result<(), SessionControllerError>>,
+    },
+    /// 停止会话
+    Stop {
+        response: oneshot::Sender<Result<Option<String>, SessionControllerError>>,
+    },
+    /// 取消会话
+    Cancel {
+        response: oneshot::Sender<Result<(), SessionControllerError>>,
+    },
+    /// 按转写选项打开、重开或关闭预热麦克风
+    Warm { options: TranscriptionOptions },
+}
+
+/// 会话控制器
+///
+/// 管理 RaFlow 会话的生命周期。命令按到达顺序串行执行，
+/// 因此在连接建立期间松开热键也会在启动完成后正确停止。
+pub struct SessionController {
+    /// Tauri 应用句柄
+    app: AppHandle,
+    /// 命令发送器
+    command_tx: mpsc::Sender<SessionCommand>,
+    /// 是否有活跃会话
+    is_active: Arc<AtomicBool>,
+    /// 最近一次打开麦克风时选中的输入设备
+    selected_device: Arc<Mutex<Option<DeviceMatch>>>,
+}
+
+impl SessionController {
+    /// 创建新的会话控制器
+    ///
+    /// 会启动一个专用线程运行会话任务
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - Tauri 应用句柄
+    pub fn new(app: AppHandle) -> Self {
+        let (command_tx, command_rx) = mpsc::channel::<SessionCommand>(16);
+        let is_active = Arc::new(AtomicBool::new(false));
+        let selected_device = Arc::new(Mutex::new(None));
+
+        // 使用专用线程来运行会话任务，因为 TranscriptionSession 不是 Send
+        let app_clone = app.clone();
+        let is_active_clone = Arc::clone(&is_active);
+        let selected_device_clone = Arc::clone(&selected_device);
+
+        std::thread::Builder::new()
+            .name("raflow-session".to_string())
+            .spawn(move || {
+                let rt = tokio::runtime::Builder::new_current_thread()
+                    .enable_all()
+                    .build()
+                    .expect("Failed to create session runtime");
+
+                rt.block_on(session_task(
+                    app_clone,
+                    command_rx,
+                    is_active_clone,
+                    selected_device_clone,
+                ));
+            })
+            .expect("Failed to spawn session thread");
+
+        Self {
+            app,
+            command_tx,
+            is_active,
+            selected_device,
+        }
+    }
+
+    /// 检查是否有活跃会话
+    pub fn is_active(&self) -> bool {
+        self.is_active.load(Ordering::SeqCst)
+    }
+
+    /// 获取最近一次打开麦克风时选中的输入设备及匹配方式
+    ///
+    /// 尚未打开过麦克风时返回 None。会话中途的故障切换通过
+    /// `SessionEvent::DeviceSwitched` 事件通知，不会更新此结果
+    pub fn selected_device(&self) -> Option<DeviceMatch> {
+        self.selected_device
+            .lock()
+            .ok()
+            .and_then(|selected| selected.clone())
+    }
+
+    /// 使用当前应用配置启动会话
+    ///
+    /// 转写选项取自 `AppConfig`，注入行为取自 `BehaviorConfig`
+    pub async fn start_from_config(&self) -> Result<(), SessionControllerError> {
+        let (options, config) = self.options_from_config();
+        self.start_session(options, config).await
+    }
+
+    /// 按当前应用配置同步预热麦克风
+    ///
+    /// 启动时和保存配置后调用：开启预热则打开（或按新设备重开）麦克风，
+    /// 关闭预热则释放麦克风。会话进行中时推迟到下次启动会话
+    pub fn refresh_warm_microphone(&self) {
+        let (options, _) = self.options_from_config();
+
+        if self
+            .command_tx
+            .try_send(SessionCommand::Warm { options })
+            .is_err()
+        {
+            tracing::warn!("Session controller busy, warm microphone not refreshed");
+        }
+    }
+
+    /// 从 `AppConfig` 构建转写选项和会话配置
+    fn options_from_config(&self) -> (TranscriptionOptions, SessionConfig) {
+        match self.app.try_state::<Arc<GlobalConfig>>() {
+            Some(global) => {
+                let app_config = global.get();
+                (
+                    transcription_options(&self.app, &app_config),
+                    SessionConfig::from(&app_config.behavior)
+                        .with_processing(ProcessingChain::from_config(&app_config))
+                        .with_voice_commands(VoiceCommandInterpreter::from_config(&app_config)),
+                )
+            }
+            None => (TranscriptionOptions::new(""), SessionConfig::default()),
+        }
+    }
+
+    /// 启动会话
+    ///
+    /// # Arguments
+    ///
+    /// * `options` - 转写选项（通常由 `TranscriptionOptions::from_config` 构建）
+    /// * `config` - 会话配置
+    pub async fn start_session(
+        &self,
+        options: TranscriptionOptions,
+        config: SessionConfig,
+    ) -> Result<(), SessionControllerError> {
+        if options.api_key.is_empty() {
+            return Err(SessionControllerError::ApiKeyNotSet);
+        }
+
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.command_tx
+            .send(SessionCommand::Start {
+                options,
+                config,
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| SessionControllerError::ChannelClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| SessionControllerError::ChannelClosed)?
+    }
+
+    /// 停止会话并获取最终结果
+    ///
+    /// 文本注入在返回前已按会话配置完成
+    pub async fn stop_session(&self) -> Result<Option<String>, SessionControllerError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.command_tx
+            .send(SessionCommand::Stop {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| SessionControllerError::ChannelClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| SessionControllerError::ChannelClosed)?
+    }
+
+    /// 取消会话
+    ///
+    /// 丢弃本次会话的文本，不执行注入
+    pub async fn cancel_session(&self) -> Result<(), SessionControllerError> {
+        let (response_tx, response_rx) = oneshot::channel();
+
+        self.command_tx
+            .send(SessionCommand::Cancel {
+                response: response_tx,
+            })
+            .await
+            .map_err(|_| SessionControllerError::ChannelClosed)?;
+
+        response_rx
+            .await
+            .map_err(|_| SessionControllerError::ChannelClosed)?
+    }
+}
+
+/// 会话管理任务
+///
+/// 在专用线程中运行，串行处理会话命令
+async fn session_task(
+    app: AppHandle,
+    mut command_rx: mpsc::Receiver<SessionCommand>,
+    is_active: Arc<AtomicBool>,
+    selected_device: Arc<Mutex<Option<DeviceMatch>>>,
+) {
+    let mut current_session: Option<RaFlowSession> = None;
+    let mut warm_microphone: Option<WarmMicrophone> = None;
+
+    while let Some(command) = command_rx.recv().await {
+        match command {
+            SessionCommand::Start {
+                options,
+                config,
+                response,
+            } => {
+                let result = handle_start(
+                    &app,
+                    &mut current_session,
+                    &mut warm_microphone,
+                    &selected_device,
+                    options,
+                    config,
+                )
+                .await;
+                let _ = response.send(result);
+            }
+            SessionCommand::Stop { response } => {
+                let result = handle_stop(&mut current_session).await;
+                let _ = response.send(result);
+            }
+            SessionCommand::Cancel { response } => {
+                let result = handle_cancel(&app, &mut current_session).await;
+                let _ = response.send(result);
+            }
+            SessionCommand::Warm { options } => {
+                // 不打断进行中的会话，下次启动时会重新同步
+                if current_session.is_none() {
+                    let options = match options.warm_pre_roll_ms {
+                        Some(_) => select_input_device(options, &selected_device),
+                        None => options,
+                    };
+                    sync_warm_microphone(&mut warm_microphone, &options);
+                }
+            }
+        }
+
+        is_active.store(current_session.is_some(), Ordering::SeqCst);
+    }
+
+    // 控制器被释放时清理残留会话
+    if let Some(mut session) = current_session.take() {
+        let _ = session.cancel().await;
+    }
+}
+
+/// 处理启动命令
+async fn handle_start(
+    app: &AppHandle,
+    current_session: &mut Option<RaFlowSession>,
+    warm_microphone: &mut Option<WarmMicrophone>,
+    selected_device: &Mutex<Option<DeviceMatch>>,
+    options: TranscriptionOptions,
+    config: SessionConfig,
+) -> Result<(), SessionControllerError> {
+    if let Some(session) = current_session.as_mut() {
+        // 上一个会话已因错误中断，清理后允许重新开始
+        if !session.current_state().is_error() {
+            return Err(SessionControllerError::SessionAlreadyActive);
+        }
+
+        tracing::info!("Discarding failed session before starting a new one");
+        let _ = session.cancel().await;
+        *current_session = None;
+    }
+
+    // 从错误状态恢复，使用户可以直接重试
+    if let Some(ctx) = StateTransitionContext::from_app(app) {
+        if ctx.current().is_error() {
+            let _ = ctx.recover_from_error();
+        }
+    }
+
+    // 预热麦克风可用时从它取音频（含按键前的 pre-roll），否则直接打开设备
+    let options = select_input_device(options, selected_device);
+    sync_warm_microphone(warm_microphone, &options);
+    let result = match warm_microphone.as_ref() {
+        Some(warm) => {
+            let source = Box::new(warm.source());
+            RaFlowSession::start_with_source(app, options, source, config).await
+        }
+        None => RaFlowSession::start_with_options(app, options, config).await,
+    };
+    let session = result.map_err(|e| SessionControllerError::StartFailed(e.to_string()))?;
+
+    *current_session = Some(session);
+    Ok(())
+}
+
+/// 按设备选择规则解析实际使用的输入设备
+///
+/// 返回的选项中 `input_device_id` 为匹配到的设备（使用系统默认设备时为 None），
+/// 并记录选择结果。枚举设备失败或没有任何设备时保留原设备 ID，由打开设备时报告错误
+fn select_input_device(
+    mut options: TranscriptionOptions,
+    selected_device: &Mutex<Option<DeviceMatch>>,
+) -> TranscriptionOptions {
+    let selected = match options.device_selector().resolve() {
+        Ok(selected) => selected,
+        Err(e) => {
+            tracing::warn!(error = %e, "Failed to list input devices for device selection");
+            None
+        }
+    };
+
+    if let Some(selected) = &selected {
+        tracing::info!(
+            device = %selected.device.name,
+            kind = ?selected.kind,
+            "Input device selected"
+        );
+        options.input_device_id = selected.device_id().map(str::to_string);
+    }
+
+    if let Ok(mut current) = selected_device.lock() {
+        *current = selected;
+    }
+
+    options
+}
+
+/// 使预热麦克风与转写选项一致
+///
+/// 未开启预热时释放麦克风；设备、声道、pre-roll 长度或故障切换设置变化时，
+/// 以及设备已断开时重新打开。打开失败只记录日志，会话会退回到直接打开设备
+fn sync_warm_microphone(
+    warm_microphone: &mut Option<WarmMicrophone>,
+    options: &TranscriptionOptions,
+) {
+    let Some(pre_roll_ms) = options.warm_pre_roll_ms else {
+        *warm_microphone = None;
+        return;
+    };
+
+    let device_id = options.input_device_id.as_deref();
+    if let Some(warm) = warm_microphone.as_ref()
+        && warm.is_running()
+        && warm.matches(
+            device_id,
+            options.channel_mode,
+            pre_roll_ms,
+            &options.failover,
+        )
+    {
+        return;
+    }
+
+    // 先释放旧的麦克风，避免同一设备被打开两次
+    *warm_microphone = None;
+    match WarmMicrophone::open(
+        device_id,
+        options.channel_mode,
+        pre_roll_ms,
+        options.failover.clone(),
+    ) {
+        Ok(warm) => *warm_microphone = Some(warm),
+        Err(e) => tracing::warn!(error = %e, "Failed to open warm microphone"),
+    }
+}
+
+/// 处理停止命令
+async fn handle_stop(
+    current_session: &mut Option<RaFlowSession>,
+) -> Result<Option<String>, SessionControllerError> {
+    let mut session = current_session
+        .take()
+        .ok_or(SessionControllerError::NoActiveSession)?;
+
+    session
+        .stop()
+        .await
+        .map_err(|e| SessionControllerError::StopFailed(e.to_string()))
+}
+
+/// 处理取消命令
+async fn handle_cancel(
+    app: &AppHandle,
+    current_session: &mut Option<RaFlowSession>,
+) -> Result<(), SessionControllerError> {
+    match current_session.take() {
+        Some(mut session) => session
+            .cancel()
+            .await
+            .map_err(|e| SessionControllerError::StopFailed(e.to_string())),
+        None => {
+            // 没有会话但状态未回到空闲（例如启动失败后），直接重置
+            if let Some(ctx) = StateTransitionContext::from_app(app) {
+                if !ctx.current().is_idle() {
+                    ctx.complete();
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// 会话控制器错误
+#[derive(Debug, thiserror::Error, Clone, PartialEq)]
+pub enum SessionControllerError {
+    /// API Key 未设置
+    #[error("API key not set")]
+    ApiKeyNotSet,
+
+    /// 会话已经在运行
+    #[error("A session is already active")]
+    SessionAlreadyActive,
+
+    /// 没有活跃的会话
+    #[error("No active session")]
+    NoActiveSession,
+
+    /// 启动会话失败
+    #[error("Failed to start session: {0}")]
+    StartFailed(String),
+
+    /// 停止会话失败
+    #[error("Failed to stop session: {0}")]
+    StopFailed(String),
+
+    /// Channel 已关闭
+    #[error("Session controller channel closed")]
+    ChannelClosed,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_controller_error_display() {
+        let error = SessionControllerError::ApiKeyNotSet;
+        assert!(format!("{}", error).contains("API key not set"));
+
+        let error = SessionControllerError::SessionAlreadyActive;
+        assert!(format!("{}", error).contains("already active"));
+
+        let error = SessionControllerError::NoActiveSession;
+        assert!(format!("{}", error).contains("No active session"));
+
+        let error = SessionControllerError::StartFailed("connection error".to_string());
+        assert!(format!("{}", error).contains("connection error"));
+
+        let error = SessionControllerError::StopFailed("stop error".to_string());
+        assert!(format!("{}", error).contains("stop error"));
+
+        let error = SessionControllerError::ChannelClosed;
+        assert!(format!("{}", error).contains("channel closed"));
+    }
+
+    #[test]
+    fn test_session_controller_error_equality() {
+        assert_eq!(
+            SessionControllerError::ApiKeyNotSet,
+            SessionControllerError::ApiKeyNotSet
+        );
+        assert_ne!(
+            SessionControllerError::ApiKeyNotSet,
+            SessionControllerError::NoActiveSession
+        );
+    }
+}
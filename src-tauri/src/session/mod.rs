@@ -11,20 +11,28 @@
 //!
 //! # 使用示例
 //!
+//! 应用内的热键、Tauri 命令都通过 [`SessionController`] 驱动会话，
+//! 它在专用线程上持有 [`RaFlowSession`]，保证同一时刻只有一个会话：
+//!
 //! ```ignore
-//! use raflow_lib::session::{RaFlowSession, SessionConfig};
+//! use raflow_lib::session::SessionController;
+//!
+//! let controller = SessionController::new(app_handle.clone());
+//!
+//! // 使用当前应用配置启动会话
+//! controller.start_from_config().await?;
 //!
-//! // 创建会话配置
-//! let config = SessionConfig::default();
+//! // 停止会话，返回本次会话的最终文本（已按配置注入）
+//! let text = controller.stop_session().await?;
+//! ```
 //!
-//! // 启动会话
-//! let mut session = RaFlowSession::start(&app_handle, "api-key", config).await?;
+//! 也可以直接使用 [`RaFlowSession`]（需在同一任务中持有）：
 //!
-//! // 等待用户停止
-//! // ...
+//! ```ignore
+//! use raflow_lib::session::{RaFlowSession, SessionConfig};
 //!
-//! // 停止会话
-//! session.stop().await?;
+//! let mut session = RaFlowSession::start(&app_handle, "api-key", SessionConfig::default()).await?;
+//! let text = session.stop().await?;
 //! ```
 //!
 //! # 工作流程
@@ -33,35 +41,61 @@
 //! 1. 用户按下热键
 //!    └── State: Idle -> Connecting
 //!
-//! 2. 建立 WebSocket 连接，启动音频采集
+//! 2. 启动音频采集，建立 WebSocket 连接
+//!    └── 握手期间的音频排队，连接建立后按顺序发送
+//!    └── 预热模式下麦克风常开，先补发按键前的一段音频（pre-roll）
 //!    └── State: Connecting -> Recording(Listening)
 //!
 //! 3. 接收部分转写
 //!    └── State: Recording(Listening) -> Recording(Transcribing)
-//!    └── Event: transcript:partial
+//!    └── Event: session:event (PartialTranscript)
 //!
-//! 4. 用户松开热键
-//!    └── State: Recording -> Processing
+//! 4. 接收最终转写片段（录音期间可能有多段）
+//!    └── 累积到本次会话文本
+//!    └── Event: session:event (CommittedTranscript)
 //!
-//! 5. 接收最终转写
-//!    └── State: Processing -> Injecting
-//!    └── 执行文本注入
+//!    连接意外中断时自动重连，未确认的音频重放到新连接
+//!    └── State: Recording -> Reconnecting -> Recording
+//!    └── Event: session:event (Reconnecting / Reconnected)
 //!
-//! 6. 注入完成
-//!    └── State: Injecting -> Idle
-//!    └── Event: transcript:committed
+//!    客户端 VAD 检测到说话开始/结束，静音期间不发送（或稀疏发送）音频
+//!    └── Event: session:event (SpeechStarted / SpeechEnded)
+//!
+//!    输入设备断开时按配置切换到备用设备继续录音，无可用设备时自动停止
+//!    └── Event: session:event (DeviceSwitched / DeviceLost)
+//!
+//! 5. 用户松开热键（或持续静音达到 auto_stop_silence_secs、输入设备断开后自动停止）
+//!    └── State: Recording -> Processing
+//!    └── 提交未完成的语音，等待最终转写（有超时）后关闭连接
+//!
+//! 6. 按配置的处理链整理文本（去除空白、句末标点、大小写等），
+//!    启用语音命令时把命令短语转换为按键，然后执行文本注入
+//!    └── State: Processing -> Injecting -> Idle
+//!    └── Event: session:event (TextInjected / TextCopied, Stopped)
 //! ```
 
+mod controller;
+
 use std::sync::Arc;
 use std::time::Duration;
 
 use serde::{Deserialize, Serialize};
 use tauri::{AppHandle, Emitter, Manager};
-use tokio::sync::{mpsc, oneshot, Mutex};
-
-use crate::input::{InjectionStrategy, TextInjector};
-use crate::state::{AppState, StateManager, StateTransitionContext};
-use crate::transcription::{TranscriptEvent, TranscriptionError, TranscriptionSession};
+use tokio::sync::Mutex;
+
+use crate::audio::AudioSource;
+use crate::input::{InjectionHistory, InjectionStrategy, InputResult, TextInjector};
+use crate::state::{
+    AppConfig, AppState, BehaviorConfig, ConfigManager, GlobalConfig, StateManager,
+    StateTransitionContext,
+};
+use crate::text::{render_plain, EditAction, ProcessingChain, VoiceCommandInterpreter};
+use crate::transcription::{
+    ArchiveOptions, RetentionPolicy, TranscriptEvent, TranscriptionError, TranscriptionOptions,
+    TranscriptionSession,
+};
+
+pub use controller::{SessionController, SessionControllerError};
 
 /// 会话配置
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -76,6 +110,12 @@ pub struct SessionConfig {
     pub pre_injection_delay_ms: u64,
     /// 是否自动注入（false = 仅复制到剪贴板）
     pub auto_inject: bool,
+    /// 注入前对最终文本执行的处理链（默认不修改文本）
+    #[serde(skip)]
+    pub processing: Arc<ProcessingChain>,
+    /// 语音命令解释器（None = 不解释命令短语）
+    #[serde(skip)]
+    pub voice_commands: Option<Arc<VoiceCommandInterpreter>>,
 }
 
 impl Default for SessionConfig {
@@ -86,6 +126,8 @@ impl Default for SessionConfig {
             paste_delay_ms: 100,
             pre_injection_delay_ms: 50,
             auto_inject: true,
+            processing: Arc::default(),
+            voice_commands: None,
         }
     }
 }
@@ -115,6 +157,65 @@ impl SessionConfig {
             ..Default::default()
         }
     }
+
+    /// 设置注入前的文本处理链
+    pub fn with_processing(mut self, chain: ProcessingChain) -> Self {
+        self.processing = Arc::new(chain);
+        self
+    }
+
+    /// 设置语音命令解释器
+    pub fn with_voice_commands(mut self, interpreter: Option<VoiceCommandInterpreter>) -> Self {
+        self.voice_commands = interpreter.map(Arc::new);
+        self
+    }
+
+    /// 将最终文本转换为编辑操作
+    ///
+    /// 先执行处理链，启用语音命令时再把命令短语转换为按键或标点
+    pub fn edit_actions(&self, text: &str) -> Vec<EditAction> {
+        let text = self.processing.process(text);
+        match &self.voice_commands {
+            Some(interpreter) => interpreter.interpret(&text),
+            None if text.is_empty() => Vec::new(),
+            None => vec![EditAction::Text(text)],
+        }
+    }
+}
+
+impl From<&BehaviorConfig> for SessionConfig {
+    fn from(behavior: &BehaviorConfig) -> Self {
+        Self {
+            injection_strategy: behavior.injection_strategy,
+            auto_threshold: behavior.auto_threshold,
+            paste_delay_ms: behavior.paste_delay_ms,
+            pre_injection_delay_ms: behavior.pre_injection_delay_ms,
+            auto_inject: behavior.auto_inject,
+            processing: Arc::default(),
+            voice_commands: None,
+        }
+    }
+}
+
+/// 根据应用配置构建转写选项
+///
+/// 在 [`TranscriptionOptions::from_config`] 的基础上补充录音归档目录
+/// （默认位于应用数据目录，需要通过应用句柄解析）
+pub(crate) fn transcription_options(app: &AppHandle, config: &AppConfig) -> TranscriptionOptions {
+    let options = TranscriptionOptions::from_config(config);
+    if !config.archive.enabled {
+        return options;
+    }
+
+    match ConfigManager::recordings_dir(app, &config.archive) {
+        Ok(dir) => options.with_archive(
+            ArchiveOptions::new(dir).with_retention(RetentionPolicy::from(&config.archive)),
+        ),
+        Err(e) => {
+            tracing::warn!(error = %e, "Recordings directory unavailable, archive disabled");
+            options
+        }
+    }
 }
 
 /// 会话事件
@@ -135,6 +236,20 @@ pub enum SessionEvent {
     TextCopied { text: String },
     /// 会话结束
     Stopped,
+    /// 会话被取消（转写结果已丢弃）
+    Cancelled,
+    /// 连接中断，正在重连
+    Reconnecting { attempt: u32 },
+    /// 重连成功，录音继续
+    Reconnected,
+    /// 检测到开始说话
+    SpeechStarted,
+    /// 检测到说话结束
+    SpeechEnded,
+    /// 输入设备断开且无可用的备用设备，会话将自动停止
+    DeviceLost { message: String },
+    /// 输入设备断开，已切换到备用设备继续录音
+    DeviceSwitched { device: String },
     /// 错误发生
     Error { message: String },
 }
@@ -151,8 +266,6 @@ pub struct RaFlowSession {
     transcription: Option<TranscriptionSession>,
     /// 状态管理器
     state_manager: Arc<StateManager>,
-    /// 停止信号发送器
-    stop_tx: Option<oneshot::Sender<()>>,
     /// 是否正在运行
     is_running: bool,
     /// 最后的最终转写文本
@@ -162,6 +275,9 @@ pub struct RaFlowSession {
 impl RaFlowSession {
     /// 启动新的 RaFlow 会话
     ///
+    /// 转写参数（语言、模型、输入设备等）取自应用的 `GlobalConfig`，
+    /// 未初始化配置时使用默认值
+    ///
     /// # Arguments
     ///
     /// * `app` - Tauri 应用句柄
@@ -180,6 +296,58 @@ impl RaFlowSession {
         app: &AppHandle,
         api_key: &str,
         config: SessionConfig,
+    ) -> Result<Self, SessionError> {
+        let options = match app.try_state::<Arc<GlobalConfig>>() {
+            Some(global) => transcription_options(app, &global.get()),
+            None => TranscriptionOptions::new(api_key),
+        }
+        .with_api_key(api_key);
+
+        Self::start_with_options(app, options, config).await
+    }
+
+    /// 使用指定的转写选项启动 RaFlow 会话
+    ///
+    /// # Arguments
+    ///
+    /// * `app` - Tauri 应用句柄
+    /// * `options` - 转写选项（通常由 `TranscriptionOptions::from_config` 构建）
+    /// * `config` - 会话配置
+    ///
+    /// # Errors
+    ///
+    /// 同 [`RaFlowSession::start`]。转写会话启动失败时应用状态会进入 Error
+    pub async fn start_with_options(
+        app: &AppHandle,
+        options: TranscriptionOptions,
+        config: SessionConfig,
+    ) -> Result<Self, SessionError> {
+        Self::start_inner(app, options, None, config).await
+    }
+
+    /// 使用指定的音频源启动 RaFlow 会话
+    ///
+    /// 音频取自 `source`（例如预热麦克风的 [`PreRollSource`](crate::audio::PreRollSource)），
+    /// 而不是 `options` 中的输入设备
+    ///
+    /// # Errors
+    ///
+    /// 同 [`RaFlowSession::start_with_options`]
+    pub async fn start_with_source(
+        app: &AppHandle,
+        options: TranscriptionOptions,
+        source: Box<dyn AudioSource>,
+        config: SessionConfig,
+    ) -> Result<Self, SessionError> {
+        Self::start_inner(app, options, Some(source), config).await
+    }
+
+    /// 启动会话（`source` 为 None 时按 `options` 打开输入设备）
+    async fn start_inner(
+        app: &AppHandle,
+        options: TranscriptionOptions,
+        source: Option<Box<dyn AudioSource>>,
+        config: SessionConfig,
     ) -> Result<Self, SessionError> {
         tracing::info!(strategy = ?config.injection_strategy, "Starting RaFlow session");
 
@@ -196,19 +364,12 @@ impl RaFlowSession {
         // 发射事件
         let _ = app.emit("session:connecting", ());
 
-        // 创建共享数据
-        let last_committed = Arc::new(Mutex::new(None::<String>));
-        let last_committed_clone = Arc::clone(&last_committed);
-
-        // 创建用于注入的 channel
-        let (inject_tx, mut inject_rx) = mpsc::channel::<String>(10);
-
         let app_clone = app.clone();
         let state_manager_clone = Arc::clone(&state_manager);
-        let config_clone = config.clone();
+        let processing = Arc::clone(&config.processing);
 
-        // 启动转写会话
-        let transcription = TranscriptionSession::start(api_key, move |event| {
+        // 转写事件处理
+        let on_event = move |event: TranscriptEvent| {
             let ctx = StateTransitionContext::new(&app_clone, Arc::clone(&state_manager_clone));
 
             match event {
@@ -231,42 +392,24 @@ impl RaFlowSession {
                 TranscriptEvent::Partial { text } => {
                     tracing::debug!(text = %text, "Partial transcript");
 
-                    // 更新部分转写
-                    if let Err(e) = ctx.update_partial(text.clone(), 0.5) {
-                        tracing::warn!(error = %e, "Failed to update partial text");
+                    // 松开热键后（Processing）仍可能收到部分转写，此时不再回到 Recording
+                    if ctx.current().is_recording() {
+                        if let Err(e) = ctx.update_partial(text.clone(), 0.5) {
+                            tracing::warn!(error = %e, "Failed to update partial text");
+                        }
                     }
 
                     // 发射事件
-                    let _ = app_clone.emit(
-                        "session:event",
-                        SessionEvent::PartialTranscript { text },
-                    );
+                    let _ =
+                        app_clone.emit("session:event", SessionEvent::PartialTranscript { text });
                 }
                 TranscriptEvent::Committed { text } => {
                     tracing::info!(text = %text, "Committed transcript");
-
-                    // 保存最终文本
-                    {
-                        if let Ok(mut guard) = last_committed_clone.try_lock() {
-                            *guard = Some(text.clone());
-                        }
-                    }
-
-                    // 转换到 Processing 状态
-                    if let Err(e) = ctx.start_processing() {
-                        tracing::error!(error = %e, "Failed to transition to Processing");
-                    }
-
-                    // 发送到注入 channel
-                    if config_clone.auto_inject {
-                        let _ = inject_tx.try_send(text.clone());
-                    }
+                    let text = processing.process(&text);
 
                     // 发射事件
-                    let _ = app_clone.emit(
-                        "session:event",
-                        SessionEvent::CommittedTranscript { text },
-                    );
+                    let _ =
+                        app_clone.emit("session:event", SessionEvent::CommittedTranscript { text });
                 }
                 TranscriptEvent::Error { message } => {
                     tracing::error!(error = %message, "Transcription error");
@@ -279,31 +422,91 @@ impl RaFlowSession {
                     // 发射事件
                     let _ = app_clone.emit("session:event", SessionEvent::Error { message });
                 }
+                TranscriptEvent::Reconnecting { attempt } => {
+                    tracing::warn!(attempt, "Connection lost, reconnecting");
+
+                    // 松开热键后（Processing）仍在重连以发送剩余音频，此时保持 Processing
+                    let current = ctx.current();
+                    if current.is_recording() || current.is_reconnecting() {
+                        if let Err(e) = ctx.start_reconnecting(attempt) {
+                            tracing::warn!(error = %e, "Failed to transition to Reconnecting");
+                        }
+                    }
+
+                    // 发射事件
+                    let _ = app_clone.emit("session:event", SessionEvent::Reconnecting { attempt });
+                }
+                TranscriptEvent::Reconnected => {
+                    tracing::info!("Connection restored");
+
+                    if ctx.current().is_reconnecting() {
+                        if let Err(e) = ctx.resume_recording() {
+                            tracing::warn!(error = %e, "Failed to resume recording");
+                        }
+                    }
+
+                    // 发射事件
+                    let _ = app_clone.emit("session:event", SessionEvent::Reconnected);
+                }
+                TranscriptEvent::SpeechStarted => {
+                    tracing::debug!("Speech started");
+                    let _ = app_clone.emit("session:event", SessionEvent::SpeechStarted);
+                }
+                TranscriptEvent::SpeechEnded => {
+                    tracing::debug!("Speech ended");
+                    let _ = app_clone.emit("session:event", SessionEvent::SpeechEnded);
+                }
+                TranscriptEvent::SilenceTimeout { silence_ms } => {
+                    tracing::info!(silence_ms, "Silence limit reached, stopping session");
+                    Self::request_auto_stop(&app_clone);
+                }
+                TranscriptEvent::AudioLevel { level } => {
+                    // 已在音频管道中限流到约 30 Hz，直接转发给悬浮窗
+                    let _ = app_clone.emit("audio:level", level);
+                }
+                TranscriptEvent::DeviceLost { message } => {
+                    tracing::error!(error = %message, "Input device lost, stopping session");
+
+                    // 不再有音频输入，提交已转写的文本并结束会话
+                    let _ = app_clone.emit("session:event", SessionEvent::DeviceLost { message });
+                    Self::request_auto_stop(&app_clone);
+                }
+                TranscriptEvent::DeviceSwitched { device, message } => {
+                    tracing::warn!(device = %device, reason = %message, "Switched input device");
+                    let _ =
+                        app_clone.emit("session:event", SessionEvent::DeviceSwitched { device });
+                }
                 TranscriptEvent::Closed => {
                     tracing::info!("Transcription session closed");
                 }
             }
-        })
-        .await
-        .map_err(SessionError::TranscriptionError)?;
-
-        // 启动注入处理任务
-        let app_inject = app.clone();
-        let state_manager_inject = Arc::clone(&state_manager);
-        let config_inject = config.clone();
-
-        tokio::spawn(async move {
-            while let Some(text) = inject_rx.recv().await {
-                Self::handle_injection(
-                    &app_inject,
-                    &state_manager_inject,
-                    &text,
-                    &config_inject,
-                )
-                .await;
+        };
+
+        // 启动转写会话
+        let result = match source {
+            Some(source) => {
+                TranscriptionSession::start_with_source(&options, source, on_event).await
             }
-            tracing::debug!("Injection handler stopped");
-        });
+            None => TranscriptionSession::start_with_options(&options, on_event).await,
+        };
+
+        let transcription = match result {
+            Ok(transcription) => transcription,
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to start transcription session");
+
+                // 不能停留在 Connecting，否则后续热键都会被忽略
+                let _ = ctx.report_error(e.to_string());
+                let _ = app.emit(
+                    "session:event",
+                    SessionEvent::Error {
+                        message: e.to_string(),
+                    },
+                );
+
+                return Err(SessionError::TranscriptionError(e));
+            }
+        };
 
         tracing::info!("RaFlow session started successfully");
 
@@ -312,19 +515,40 @@ impl RaFlowSession {
             config,
             transcription: Some(transcription),
             state_manager,
-            stop_tx: None,
             is_running: true,
-            last_committed_text: last_committed,
+            last_committed_text: Arc::new(Mutex::new(None)),
         })
     }
 
+    /// 持续静音或输入设备断开后通过 `SessionController` 自动停止会话
+    ///
+    /// 与松开热键走同一路径，因此停止后照常提交并注入文本
+    fn request_auto_stop(app: &AppHandle) {
+        let Some(controller) = app.try_state::<Arc<SessionController>>() else {
+            tracing::warn!("SessionController not available, cannot auto-stop");
+            return;
+        };
+        let controller = Arc::clone(&controller);
+
+        tauri::async_runtime::spawn(async move {
+            match controller.stop_session().await {
+                Ok(_) => tracing::info!("Session auto-stopped"),
+                Err(SessionControllerError::NoActiveSession) => {
+                    tracing::debug!("Session already stopped before auto-stop");
+                }
+                Err(e) => tracing::error!(error = %e, "Failed to auto-stop session"),
+            }
+        });
+    }
+
     /// 处理文本注入
     async fn handle_injection(
         app: &AppHandle,
         state_manager: &Arc<StateManager>,
-        text: &str,
+        actions: &[EditAction],
         config: &SessionConfig,
     ) {
+        let text = render_plain(actions);
         let ctx = StateTransitionContext::new(app, Arc::clone(state_manager));
 
         // 转换到 Injecting 状态
@@ -339,7 +563,7 @@ impl RaFlowSession {
         }
 
         // 执行注入
-        let result = Self::inject_text(app, text, config).await;
+        let result = Self::inject_actions(app, actions, config).await;
 
         match result {
             Ok(strategy_name) => {
@@ -347,17 +571,12 @@ impl RaFlowSession {
 
                 // 发射事件
                 if config.injection_strategy == InjectionStrategy::ClipboardOnly {
-                    let _ = app.emit(
-                        "session:event",
-                        SessionEvent::TextCopied {
-                            text: text.to_string(),
-                        },
-                    );
+                    let _ = app.emit("session:event", SessionEvent::TextCopied { text });
                 } else {
                     let _ = app.emit(
                         "session:event",
                         SessionEvent::TextInjected {
-                            text: text.to_string(),
+                            text,
                             strategy: strategy_name,
                         },
                     );
@@ -376,9 +595,12 @@ impl RaFlowSession {
     }
 
     /// 执行文本注入
-    async fn inject_text(
+    ///
+    /// 按顺序输入文字、执行语音命令对应的按键。仅复制到剪贴板时无法执行按键，
+    /// 复制渲染后的纯文本
+    async fn inject_actions(
         app: &AppHandle,
-        text: &str,
+        actions: &[EditAction],
         config: &SessionConfig,
     ) -> Result<String, SessionError> {
         let mut injector = TextInjector::with_config(
@@ -389,45 +611,210 @@ impl RaFlowSession {
         )
         .map_err(|e| SessionError::InjectionError(e.to_string()))?;
 
-        injector
-            .inject(text)
-            .await
-            .map_err(|e| SessionError::InjectionError(e.to_string()))?;
+        if config.injection_strategy == InjectionStrategy::ClipboardOnly {
+            injector
+                .inject(&render_plain(actions))
+                .await
+                .map_err(|e| SessionError::InjectionError(e.to_string()))?;
+        } else {
+            let history = Self::get_or_create_injection_history(app);
+            // 焦点窗口变化后，上一段文字不在光标前，不能再按历史退格
+            match crate::input::get_focused_window() {
+                Ok(window) => {
+                    history.set_target(&format!("{}:{}", window.process_id, window.window_id))
+                }
+                Err(e) => {
+                    tracing::debug!("Focused window unknown, clearing injection history: {}", e);
+                    history.clear();
+                }
+            }
+            for action in actions {
+                Self::apply_action(&mut injector, &history, action)
+                    .await
+                    .map_err(|e| SessionError::InjectionError(e.to_string()))?;
+            }
+        }
 
         Ok(config.injection_strategy.display_name().to_string())
     }
 
+    /// 执行单个编辑操作
+    ///
+    /// 输入的文字记入注入历史，之后的 Enter、Tab、Backspace 计入上一段文字，
+    /// "delete that" 按上一段文字的字符数退格删除。全选、撤销后无法推断文本，清空历史
+    async fn apply_action(
+        injector: &mut TextInjector<'_>,
+        history: &InjectionHistory,
+        action: &EditAction,
+    ) -> InputResult<()> {
+        match action {
+            EditAction::Text(text) => {
+                injector.inject(text).await?;
+                history.push(text);
+                Ok(())
+            }
+            EditAction::Enter => {
+                injector.keyboard().press_enter()?;
+                history.append("\n");
+                Ok(())
+            }
+            EditAction::Tab => {
+                injector.keyboard().press_tab()?;
+                history.append("\t");
+                Ok(())
+            }
+            EditAction::Backspace => {
+                injector.keyboard().press_backspace()?;
+                history.backspace();
+                Ok(())
+            }
+            EditAction::SelectAll => {
+                injector.keyboard().select_all()?;
+                history.clear();
+                Ok(())
+            }
+            EditAction::Undo => {
+                injector.keyboard().undo()?;
+                history.clear();
+                Ok(())
+            }
+            EditAction::DeletePrevious => {
+                let Some(segment) = history.pop() else {
+                    tracing::debug!("No injected text to delete");
+                    return Ok(());
+                };
+                tracing::debug!(chars = segment.chars().count(), "Deleting previous segment");
+                for _ in segment.chars() {
+                    injector.keyboard().press_backspace()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
     /// 停止会话
     ///
-    /// 停止转写和所有相关任务
-    pub async fn stop(&mut self) -> Result<(), SessionError> {
+    /// 停止音频采集并等待剩余的最终转写，然后按会话配置注入文本：
+    /// Recording -> Processing -> Injecting -> Idle
+    ///
+    /// 重连过程中停止时，会先完成重连并重放暂存的音频
+    ///
+    /// # Returns
+    ///
+    /// 本次会话的最终文本；没有收到任何最终转写时返回 `None`
+    pub async fn stop(&mut self) -> Result<Option<String>, SessionError> {
         if !self.is_running {
-            return Ok(());
+            return Ok(None);
         }
 
         tracing::info!("Stopping RaFlow session");
 
-        // 停止转写会话
-        if let Some(mut transcription) = self.transcription.take() {
-            transcription
-                .stop()
-                .await
-                .map_err(SessionError::TranscriptionError)?;
+        let ctx = StateTransitionContext::new(&self.app, Arc::clone(&self.state_manager));
+
+        // 转换到 Processing 状态（重连中松开热键时同样进入 Processing）
+        let current = ctx.current();
+        if current.is_recording() || current.is_reconnecting() {
+            if let Err(e) = ctx.start_processing() {
+                tracing::warn!(error = %e, "Failed to transition to Processing");
+            }
         }
 
-        // 重置状态
-        self.state_manager.reset();
+        // 停止转写会话：提交未完成的语音并等待最终转写后再关闭连接
+        let stop_result = match self.transcription.take() {
+            Some(mut transcription) => transcription.stop().await,
+            None => Ok(None),
+        };
+        self.is_running = false;
+
+        // 注入、复制到剪贴板之前按处理链整理文本并解释语音命令
+        let actions = match stop_result {
+            Ok(text) => text
+                .map(|text| self.config.edit_actions(&text))
+                .unwrap_or_default(),
+            Err(e) => {
+                tracing::error!(error = %e, "Failed to stop transcription session");
+                let _ = ctx.report_error(e.to_string());
+                let _ = self.app.emit("session:event", SessionEvent::Stopped);
+                return Err(SessionError::TranscriptionError(e));
+            }
+        };
+
+        let text = (!actions.is_empty()).then(|| render_plain(&actions));
+
+        match &text {
+            Some(text) => {
+                *self.last_committed_text.lock().await = Some(text.clone());
+
+                if !ctx.current().is_processing() {
+                    // 会话期间出错（Error 状态）时保留错误状态供用户查看
+                    tracing::warn!(
+                        current_state = %ctx.current().name(),
+                        "Session not in Processing state, skipping injection"
+                    );
+                } else {
+                    let config = self.injection_config();
+                    Self::handle_injection(&self.app, &self.state_manager, &actions, &config).await;
+                }
+            }
+            None => {
+                tracing::info!("No committed transcript received");
+                if !ctx.current().is_error() {
+                    ctx.complete();
+                }
+            }
+        }
 
         // 发射停止事件
         let _ = self.app.emit("session:event", SessionEvent::Stopped);
 
+        tracing::info!("RaFlow session stopped");
+
+        Ok(text)
+    }
+
+    /// 取消会话
+    ///
+    /// 停止转写并丢弃本次会话的所有文本，不执行注入
+    pub async fn cancel(&mut self) -> Result<(), SessionError> {
+        if !self.is_running {
+            return Ok(());
+        }
+
+        tracing::info!("Cancelling RaFlow session");
+
+        // 丢弃本次会话的文本
+        if let Some(mut transcription) = self.transcription.take() {
+            if let Err(e) = transcription.stop().await {
+                tracing::warn!(error = %e, "Error while cancelling transcription session");
+            }
+        }
         self.is_running = false;
 
-        tracing::info!("RaFlow session stopped");
+        // 重置状态
+        self.state_manager.reset();
+
+        // 发射取消事件
+        let _ = self.app.emit("session:event", SessionEvent::Cancelled);
+
+        tracing::info!("RaFlow session cancelled");
 
         Ok(())
     }
 
+    /// 本次停止时使用的注入配置
+    ///
+    /// 关闭自动注入时仅复制到剪贴板
+    fn injection_config(&self) -> SessionConfig {
+        if self.config.auto_inject {
+            self.config.clone()
+        } else {
+            SessionConfig {
+                injection_strategy: InjectionStrategy::ClipboardOnly,
+                ..self.config.clone()
+            }
+        }
+    }
+
     /// 检查会话是否正在运行
     pub fn is_running(&self) -> bool {
         self.is_running
@@ -464,6 +851,17 @@ impl RaFlowSession {
         Ok(manager)
     }
 
+    /// 获取或创建注入历史（跨会话共享）
+    fn get_or_create_injection_history(app: &AppHandle) -> Arc<InjectionHistory> {
+        if let Some(history) = app.try_state::<Arc<InjectionHistory>>() {
+            return Arc::clone(&history);
+        }
+
+        let history = Arc::new(InjectionHistory::default());
+        app.manage(Arc::clone(&history));
+        history
+    }
+
     /// 手动触发文本注入
     ///
     /// 用于在 ClipboardOnly 模式下手动注入已复制的文本
@@ -471,7 +869,8 @@ impl RaFlowSession {
         let text = self.last_committed_text.lock().await.clone();
 
         if let Some(text) = text {
-            Self::handle_injection(&self.app, &self.state_manager, &text, &self.config).await;
+            let actions = [EditAction::Text(text)];
+            Self::handle_injection(&self.app, &self.state_manager, &actions, &self.config).await;
             Ok(())
         } else {
             Err(SessionError::NoTextToInject)
@@ -479,15 +878,6 @@ impl RaFlowSession {
     }
 }
 
-impl Drop for RaFlowSession {
-    fn drop(&mut self) {
-        // 尝试同步停止
-        if let Some(tx) = self.stop_tx.take() {
-            let _ = tx.send(());
-        }
-    }
-}
-
 /// 会话错误
 #[derive(Debug, thiserror::Error)]
 pub enum SessionError {
@@ -546,6 +936,63 @@ mod tests {
         assert!(config.auto_inject);
     }
 
+    #[test]
+    fn test_session_config_from_behavior() {
+        let behavior = BehaviorConfig {
+            injection_strategy: InjectionStrategy::Keyboard,
+            auto_threshold: 42,
+            paste_delay_ms: 10,
+            pre_injection_delay_ms: 5,
+            auto_inject: false,
+            ..Default::default()
+        };
+
+        let config = SessionConfig::from(&behavior);
+        assert_eq!(config.injection_strategy, InjectionStrategy::Keyboard);
+        assert_eq!(config.auto_threshold, 42);
+        assert_eq!(config.paste_delay_ms, 10);
+        assert_eq!(config.pre_injection_delay_ms, 5);
+        assert!(!config.auto_inject);
+    }
+
+    #[test]
+    fn test_session_config_with_processing() {
+        use crate::text::Trim;
+
+        assert!(SessionConfig::default().processing.is_empty());
+
+        let config = SessionConfig::keyboard_only()
+            .with_processing(ProcessingChain::new().with_processor(Trim));
+        assert_eq!(config.processing.process("  text "), "text");
+        assert_eq!(config.injection_strategy, InjectionStrategy::Keyboard);
+    }
+
+    #[test]
+    fn test_session_config_edit_actions() {
+        use crate::text::{Trim, VoiceCommand};
+
+        let config =
+            SessionConfig::default().with_processing(ProcessingChain::new().with_processor(Trim));
+        assert!(config.voice_commands.is_none());
+        assert_eq!(
+            config.edit_actions(" hello new line world "),
+            vec![EditAction::Text("hello new line world".to_string())]
+        );
+        assert!(config.edit_actions("   ").is_empty());
+
+        let config = config.with_voice_commands(Some(VoiceCommandInterpreter::new(
+            &VoiceCommand::defaults(),
+        )));
+        assert_eq!(
+            config.edit_actions(" hello. New line. world "),
+            vec![
+                EditAction::Text("hello.".to_string()),
+                EditAction::Enter,
+                EditAction::Text("world".to_string()),
+            ]
+        );
+    }
+
     #[test]
     fn test_session_config_serialization() {
         let config = SessionConfig::default();
@@ -617,6 +1064,22 @@ mod tests {
         assert!(json.contains("Stopped"));
     }
 
+    #[test]
+    fn test_session_event_cancelled() {
+        let event = SessionEvent::Cancelled;
+        let json = serde_json::to_string(&event).unwrap();
+        assert!(json.contains("Cancelled"));
+    }
+
+    #[test]
+    fn test_session_event_speech_activity() {
+        let json = serde_json::to_string(&SessionEvent::SpeechStarted).unwrap();
+        assert!(json.contains("SpeechStarted"));
+
+        let json = serde_json::to_string(&SessionEvent::SpeechEnded).unwrap();
+        assert!(json.contains("SpeechEnded"));
+    }
+
     #[test]
     fn test_session_event_error() {
         let event = SessionEvent::Error {
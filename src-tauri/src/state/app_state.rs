@@ -78,6 +78,12 @@ pub enum AppState {
     /// 正在录音和转写
     Recording(RecordingState),
 
+    /// 连接中断，正在重新连接（录音继续，音频暂存待重放）
+    Reconnecting {
+        /// 当前重连尝试次数（从 1 开始）
+        attempt: u32,
+    },
+
     /// 正在处理最终转写结果
     Processing,
 
@@ -112,6 +118,11 @@ impl AppState {
         })
     }
 
+    /// 创建重连中状态
+    pub fn reconnecting(attempt: u32) -> Self {
+        Self::Reconnecting { attempt }
+    }
+
     /// 创建处理中状态
     pub fn processing() -> Self {
         Self::Processing
@@ -142,6 +153,11 @@ impl AppState {
         matches!(self, Self::Recording(_))
     }
 
+    /// 检查是否在重连中
+    pub fn is_reconnecting(&self) -> bool {
+        matches!(self, Self::Reconnecting { .. })
+    }
+
     /// 检查是否在处理中
     pub fn is_processing(&self) -> bool {
         matches!(self, Self::Processing)
@@ -165,6 +181,14 @@ impl AppState {
         }
     }
 
+    /// 获取重连尝试次数（如果处于重连状态）
+    pub fn reconnect_attempt(&self) -> Option<u32> {
+        match self {
+            Self::Reconnecting { attempt } => Some(*attempt),
+            _ => None,
+        }
+    }
+
     /// 获取错误消息（如果处于错误状态）
     pub fn error_message(&self) -> Option<&str> {
         match self {
@@ -180,6 +204,7 @@ impl AppState {
             Self::Connecting => "Connecting",
             Self::Recording(RecordingState::Listening) => "Recording::Listening",
             Self::Recording(RecordingState::Transcribing { .. }) => "Recording::Transcribing",
+            Self::Reconnecting { .. } => "Reconnecting",
             Self::Processing => "Processing",
             Self::Injecting => "Injecting",
             Self::Error(_) => "Error",
@@ -377,6 +402,13 @@ impl StateManager {
             (Recording(_), Processing) => true,
             (Recording(_), Idle) => true,
             (Recording(_), Recording(_)) => true, // 允许子状态切换
+            (Recording(_), Reconnecting { .. }) => true,
+
+            // 从 Reconnecting 可以恢复录音、重试、停止（Processing）或取消（Idle）
+            (Reconnecting { .. }, Reconnecting { .. }) => true,
+            (Reconnecting { .. }, Recording(_)) => true,
+            (Reconnecting { .. }, Processing) => true,
+            (Reconnecting { .. }, Idle) => true,
 
             // 从 Processing 可以转换到 Injecting 或 Idle（超时/取消）
             (Processing, Injecting) => true,
@@ -536,6 +568,32 @@ mod tests {
         assert!(manager.transition(AppState::idle()).is_ok());
     }
 
+    #[test]
+    fn test_reconnecting_transitions() {
+        let manager = StateManager::new();
+
+        // 只能从录音中进入重连
+        assert!(manager.transition(AppState::reconnecting(1)).is_err());
+
+        manager.transition(AppState::connecting()).unwrap();
+        manager.transition(AppState::recording_listening()).unwrap();
+
+        assert!(manager.transition(AppState::reconnecting(1)).is_ok());
+        assert!(manager.transition(AppState::reconnecting(2)).is_ok());
+        assert_eq!(manager.current().reconnect_attempt(), Some(2));
+        assert_eq!(manager.current().name(), "Reconnecting");
+
+        // 重连成功后恢复录音
+        assert!(manager.transition(AppState::recording_listening()).is_ok());
+
+        // 重连期间松开热键
+        manager.transition(AppState::reconnecting(1)).unwrap();
+        assert!(manager.transition(AppState::processing()).is_ok());
+
+        // 处理中不能进入重连
+        assert!(manager.transition(AppState::reconnecting(1)).is_err());
+    }
+
     #[test]
     fn test_force_set() {
         let manager = StateManager::new();
@@ -24,6 +24,7 @@
 //! ConfigManager::save(&app_handle, &config)?;
 //! ```
 
+use std::collections::BTreeMap;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -33,7 +34,16 @@ use tauri::{AppHandle, Manager, Runtime};
 use thiserror::Error;
 
 use crate::hotkey::HotkeyConfig;
+use crate::audio::{
+    DEFAULT_AGC_TARGET_RMS, DEFAULT_VAD_HANGOVER_MS, DEFAULT_VAD_PRE_ROLL_MS,
+    DEFAULT_WARM_PRE_ROLL_MS,
+};
 use crate::input::InjectionStrategy;
+use crate::network::{DEFAULT_BASE_URL, DEFAULT_COMMIT_TIMEOUT_MS};
+use crate::text::{
+    Capitalization, CjkOptions, DisfluencyLevel, ProcessingStage, PunctuationPolicy,
+    ReplacementRule, VoiceCommand,
+};
 
 /// 配置错误类型
 #[derive(Error, Debug)]
@@ -72,6 +82,12 @@ pub struct AppConfig {
     pub hotkeys: HotkeyConfig,
     /// 行为配置
     pub behavior: BehaviorConfig,
+    /// 录音归档配置
+    pub archive: ArchiveConfig,
+    /// 文本后处理配置
+    pub processing: ProcessingConfig,
+    /// 语音编辑命令配置
+    pub voice_commands: VoiceCommandConfig,
 }
 
 impl Default for AppConfig {
@@ -81,6 +97,9 @@ impl Default for AppConfig {
             audio: AudioConfig::default(),
             hotkeys: HotkeyConfig::default(),
             behavior: BehaviorConfig::default(),
+            archive: ArchiveConfig::default(),
+            processing: ProcessingConfig::default(),
+            voice_commands: VoiceCommandConfig::default(),
         }
     }
 }
@@ -92,6 +111,8 @@ pub struct ApiConfig {
     /// ElevenLabs API 密钥（加密存储）
     #[serde(default)]
     pub api_key: String,
+    /// WebSocket 端点地址（支持 wss:// 和 ws://，可指向代理或本地服务）
+    pub base_url: String,
     /// 模型 ID
     pub model_id: String,
     /// 语言代码（可选，自动检测）
@@ -100,16 +121,20 @@ pub struct ApiConfig {
     pub include_timestamps: bool,
     /// VAD 提交策略
     pub vad_commit_strategy: Option<String>,
+    /// 停止时等待最终转写的超时时间（毫秒），超时后直接关闭连接
+    pub commit_timeout_ms: u64,
 }
 
 impl Default for ApiConfig {
     fn default() -> Self {
         Self {
             api_key: String::new(),
+            base_url: DEFAULT_BASE_URL.to_string(),
             model_id: "scribe_v2_realtime".to_string(),
             language_code: Some("zh".to_string()),
             include_timestamps: false,
             vad_commit_strategy: None,
+            commit_timeout_ms: DEFAULT_COMMIT_TIMEOUT_MS,
         }
     }
 }
@@ -120,14 +145,36 @@ impl Default for ApiConfig {
 pub struct AudioConfig {
     /// 输入设备 ID（None 表示默认设备）
     pub input_device_id: Option<String>,
-    /// 输入设备名称（仅供显示）
+    /// 输入设备名称（用于显示，设备 ID 变化时按名称重新匹配设备）
     pub input_device_name: Option<String>,
-    /// 音量增益（0.5 - 2.0）
+    /// 设备优先级列表（ID 或名称）：所选设备找不到，或录音中断开并启用切换时按顺序尝试，
+    /// 都不可用时使用系统默认设备
+    pub preferred_device_ids: Vec<String>,
+    /// 多声道设备使用的声道索引（从 0 开始，None 表示混合所有声道）
+    pub input_channel: Option<u16>,
+    /// 输入设备断开时是否切换到其他设备继续录音（否则结束会话）
+    pub device_failover: bool,
+    /// 音量增益（0.5 - 2.0），下次会话开始时生效
     pub gain: f32,
-    /// 是否启用噪声抑制（预留）
+    /// 是否启用自动增益控制（将音量归一到目标电平，并限幅防止削波）
+    pub agc_enabled: bool,
+    /// 自动增益控制的目标 RMS 电平（0.0 - 1.0）
+    pub agc_target_level: f32,
+    /// 是否启用噪声抑制（谱减法，重采样前处理），下次会话开始时生效
     pub noise_suppression: bool,
-    /// 静音阈值（0.0 - 1.0）
+    /// 静音阈值（0.0 - 1.0），低于该电平的音频视为静音
     pub silence_threshold: f32,
+    /// 是否启用客户端语音活动检测（静音时不发送或稀疏发送音频），默认关闭，
+    /// 安静的麦克风可能被误判为静音
+    pub vad_enabled: bool,
+    /// 语音结束后继续发送音频的时长（毫秒），避免截断尾音
+    pub vad_hangover_ms: u64,
+    /// 语音开始前补发的静音时长（毫秒），避免截断首字
+    pub vad_pre_roll_ms: u64,
+    /// 是否在会话之间保持麦克风开启（预热模式），按下热键前的音频也会被发送
+    pub warm_microphone: bool,
+    /// 预热模式下会话开始时补发的按键前音频时长（毫秒）
+    pub warm_pre_roll_ms: u64,
 }
 
 impl Default for AudioConfig {
@@ -135,9 +182,19 @@ impl Default for AudioConfig {
         Self {
             input_device_id: None,
             input_device_name: None,
+            preferred_device_ids: Vec::new(),
+            input_channel: None,
+            device_failover: false,
             gain: 1.0,
+            agc_enabled: false,
+            agc_target_level: DEFAULT_AGC_TARGET_RMS,
             noise_suppression: false,
             silence_threshold: 0.01,
+            vad_enabled: false,
+            vad_hangover_ms: DEFAULT_VAD_HANGOVER_MS,
+            vad_pre_roll_ms: DEFAULT_VAD_PRE_ROLL_MS,
+            warm_microphone: false,
+            warm_pre_roll_ms: DEFAULT_WARM_PRE_ROLL_MS,
         }
     }
 }
@@ -164,6 +221,10 @@ pub struct BehaviorConfig {
     pub minimize_to_tray: bool,
     /// 处理超时时间（秒）
     pub processing_timeout_secs: u64,
+    /// 持续静音多少秒后自动停止会话（0 表示不自动停止，适用于免按住模式）
+    pub auto_stop_silence_secs: u64,
+    /// 语气词与口吃过滤程度（在文本后处理的 `disfluency` 步骤中生效）
+    pub disfluency: DisfluencyLevel,
 }
 
 impl Default for BehaviorConfig {
@@ -178,6 +239,98 @@ impl Default for BehaviorConfig {
             auto_start: false,
             minimize_to_tray: true,
             processing_timeout_secs: 30,
+            auto_stop_silence_secs: 0,
+            disfluency: DisfluencyLevel::Off,
+        }
+    }
+}
+
+/// 录音归档配置
+///
+/// 开启后每次会话的 16kHz 音频保存为 WAV（以 Scribe session_id 命名），
+/// 并附带记录部分/最终转写及时间信息的 JSON 文件，用于排查识别错误和构建评测集。
+/// 各项保留上限为 0 表示不限制
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ArchiveConfig {
+    /// 是否归档会话录音
+    pub enabled: bool,
+    /// 归档目录（None 表示应用数据目录下的 `recordings`）
+    pub directory: Option<String>,
+    /// 最多保留的录音数量
+    pub max_recordings: usize,
+    /// 录音最长保留天数
+    pub max_age_days: u64,
+    /// 归档目录总大小上限（MB）
+    pub max_total_mb: u64,
+}
+
+impl Default for ArchiveConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            max_recordings: 500,
+            max_age_days: 30,
+            max_total_mb: 1024,
+        }
+    }
+}
+
+/// 文本后处理配置
+///
+/// 每次会话的最终文本在注入或复制到剪贴板之前，
+/// 由 [`ProcessingChain`](crate::text::ProcessingChain) 按 `stages` 的顺序依次处理
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ProcessingConfig {
+    /// 是否启用文本后处理
+    pub enabled: bool,
+    /// 处理步骤及执行顺序
+    pub stages: Vec<ProcessingStage>,
+    /// 句末标点策略
+    pub trailing_punctuation: PunctuationPolicy,
+    /// 大小写策略
+    pub capitalization: Capitalization,
+    /// 替换词典规则（按顺序应用）
+    pub replacements: Vec<ReplacementRule>,
+    /// 按语言代码配置的 CJK 规范化选项
+    ///
+    /// 按 `api.language_code` 查找，未指定语言时使用 `"auto"`
+    pub cjk: BTreeMap<String, CjkOptions>,
+}
+
+impl Default for ProcessingConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            stages: ProcessingStage::defaults(),
+            trailing_punctuation: PunctuationPolicy::Keep,
+            capitalization: Capitalization::Keep,
+            replacements: Vec::new(),
+            cjk: CjkOptions::defaults(),
+        }
+    }
+}
+
+/// 语音编辑命令配置
+///
+/// 启用后，最终文本中单独成句的命令短语（如 "new line"、"句号"）会转换为按键或标点，
+/// 由 [`VoiceCommandInterpreter`](crate::text::VoiceCommandInterpreter) 解释
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct VoiceCommandConfig {
+    /// 是否启用语音命令
+    pub enabled: bool,
+    /// 命令短语表（可编辑）
+    pub commands: Vec<VoiceCommand>,
+}
+
+impl Default for VoiceCommandConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            commands: VoiceCommand::defaults(),
         }
     }
 }
@@ -267,6 +420,27 @@ impl ConfigManager {
             .map_err(|e| ConfigError::Path(e.to_string()))
     }
 
+    /// 获取录音归档目录
+    ///
+    /// 配置了目录时使用该目录，否则为应用数据目录下的 `recordings`
+    pub fn recordings_dir<R: Runtime>(
+        app: &AppHandle<R>,
+        archive: &ArchiveConfig,
+    ) -> ConfigResult<PathBuf> {
+        if let Some(dir) = archive
+            .directory
+            .as_deref()
+            .filter(|d| !d.trim().is_empty())
+        {
+            return Ok(PathBuf::from(dir.trim()));
+        }
+
+        app.path()
+            .app_data_dir()
+            .map(|dir| dir.join("recordings"))
+            .map_err(|e| ConfigError::Path(e.to_string()))
+    }
+
     /// 检查配置文件是否存在
     pub fn exists<R: Runtime>(app: &AppHandle<R>) -> ConfigResult<bool> {
         let path = Self::config_path(app)?;
@@ -408,8 +582,10 @@ mod tests {
         let config = ApiConfig::default();
 
         assert!(config.api_key.is_empty());
+        assert_eq!(config.base_url, DEFAULT_BASE_URL);
         assert_eq!(config.model_id, "scribe_v2_realtime");
         assert!(!config.include_timestamps);
+        assert_eq!(config.commit_timeout_ms, DEFAULT_COMMIT_TIMEOUT_MS);
     }
 
     #[test]
@@ -417,9 +593,19 @@ mod tests {
         let config = AudioConfig::default();
 
         assert!(config.input_device_id.is_none());
+        assert!(config.preferred_device_ids.is_empty());
+        assert!(config.input_channel.is_none());
         assert_eq!(config.gain, 1.0);
+        assert!(!config.agc_enabled);
+        assert_eq!(config.agc_target_level, DEFAULT_AGC_TARGET_RMS);
         assert!(!config.noise_suppression);
         assert_eq!(config.silence_threshold, 0.01);
+        assert!(!config.vad_enabled);
+        assert_eq!(config.vad_hangover_ms, DEFAULT_VAD_HANGOVER_MS);
+        assert_eq!(config.vad_pre_roll_ms, DEFAULT_VAD_PRE_ROLL_MS);
+        assert!(!config.warm_microphone);
+        assert_eq!(config.warm_pre_roll_ms, DEFAULT_WARM_PRE_ROLL_MS);
+        assert!(!config.device_failover);
     }
 
     #[test]
@@ -433,6 +619,50 @@ mod tests {
         assert!(config.show_overlay);
         assert!(!config.auto_start);
         assert!(config.minimize_to_tray);
+        assert_eq!(config.auto_stop_silence_secs, 0);
+        assert_eq!(config.disfluency, DisfluencyLevel::Off);
+    }
+
+    #[test]
+    fn test_archive_config_default() {
+        let config = ArchiveConfig::default();
+
+        assert!(!config.enabled);
+        assert!(config.directory.is_none());
+        assert_eq!(config.max_recordings, 500);
+        assert_eq!(config.max_age_days, 30);
+        assert_eq!(config.max_total_mb, 1024);
+        assert!(!AppConfig::default().archive.enabled);
+    }
+
+    #[test]
+    fn test_processing_config_default() {
+        let config = ProcessingConfig::default();
+
+        assert!(config.enabled);
+        assert_eq!(config.stages, ProcessingStage::defaults());
+        assert_eq!(config.trailing_punctuation, PunctuationPolicy::Keep);
+        assert_eq!(config.capitalization, Capitalization::Keep);
+        assert!(config.replacements.is_empty());
+        assert_eq!(config.cjk, CjkOptions::defaults());
+    }
+
+    #[test]
+    fn test_processing_config_partial_json() {
+        let config: AppConfig =
+            serde_json::from_str(r#"{"processing": {"capitalization": "sentence"}}"#).unwrap();
+
+        assert!(config.processing.enabled);
+        assert_eq!(config.processing.stages, ProcessingStage::defaults());
+        assert_eq!(config.processing.capitalization, Capitalization::Sentence);
+    }
+
+    #[test]
+    fn test_voice_command_config_default() {
+        let config = AppConfig::default().voice_commands;
+
+        assert!(!config.enabled);
+        assert_eq!(config.commands, VoiceCommand::defaults());
     }
 
     #[test]
@@ -482,6 +712,8 @@ mod tests {
 
         assert_eq!(config.api.api_key, "test-key");
         assert_eq!(config.api.model_id, "scribe_v2_realtime"); // 默认值
+        assert_eq!(config.api.base_url, DEFAULT_BASE_URL); // 默认值
+        assert_eq!(config.api.commit_timeout_ms, DEFAULT_COMMIT_TIMEOUT_MS); // 默认值
         assert_eq!(config.audio.gain, 1.0); // 默认值
         assert!(config.behavior.show_overlay); // 默认值
     }
@@ -16,8 +16,8 @@ mod transitions;
 
 pub use app_state::{AppState, RecordingState, StateManager};
 pub use config::{
-    init_config, ApiConfig, AppConfig, AudioConfig, BehaviorConfig, ConfigError, ConfigManager,
-    ConfigResult, GlobalConfig,
+    init_config, ApiConfig, AppConfig, ArchiveConfig, AudioConfig, BehaviorConfig, ConfigError,
+    ConfigManager, ConfigResult, GlobalConfig, ProcessingConfig, VoiceCommandConfig,
 };
 pub use error::{StateError, StateResult};
 pub use transitions::{
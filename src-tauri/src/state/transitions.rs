@@ -30,6 +30,8 @@ pub struct StateChangeEvent {
     pub is_connecting: bool,
     /// 是否录音中
     pub is_recording: bool,
+    /// 是否重连中
+    pub is_reconnecting: bool,
     /// 是否处理中
     pub is_processing: bool,
     /// 是否注入中
@@ -49,6 +51,7 @@ impl From<&AppState> for StateChangeEvent {
             is_idle: state.is_idle(),
             is_connecting: state.is_connecting(),
             is_recording: state.is_recording(),
+            is_reconnecting: state.is_reconnecting(),
             is_processing: state.is_processing(),
             is_injecting: state.is_injecting(),
             is_error: state.is_error(),
@@ -125,6 +128,9 @@ impl StateEventEmitter {
                     let _ = app.emit("transcript:partial", text);
                 }
             }
+            AppState::Reconnecting { attempt } => {
+                let _ = app.emit("app:reconnecting", attempt);
+            }
             AppState::Processing => {
                 let _ = app.emit("app:processing", ());
             }
@@ -306,9 +312,31 @@ impl StateTransitionContext {
         self.transition_with_event(AppState::recording_transcribing(text, confidence))
     }
 
+    /// 连接中断，开始重连
+    ///
+    /// 从 Recording（或上一次重连尝试）转换到 Reconnecting
+    pub fn start_reconnecting(&self, attempt: u32) -> Result<(), TransitionError> {
+        self.transition_with_event(AppState::reconnecting(attempt))
+    }
+
+    /// 重连成功，恢复录音
+    ///
+    /// 从 Reconnecting 转换到 Recording(Listening)
+    pub fn resume_recording(&self) -> Result<(), TransitionError> {
+        let current = self.current();
+        if current.is_reconnecting() {
+            self.transition_with_event(AppState::recording_listening())
+        } else {
+            Err(TransitionError::InvalidState {
+                current: current.name().to_string(),
+                action: "resume recording".to_string(),
+            })
+        }
+    }
+
     /// 开始处理
     ///
-    /// 从 Recording 转换到 Processing
+    /// 从 Recording 或 Reconnecting 转换到 Processing
     pub fn start_processing(&self) -> Result<(), TransitionError> {
         self.transition_with_event(AppState::processing())
     }
@@ -329,10 +357,10 @@ impl StateTransitionContext {
 
     /// 取消当前操作
     ///
-    /// 从 Connecting/Recording 转换到 Idle
+    /// 从 Connecting/Recording/Reconnecting 转换到 Idle
     pub fn cancel(&self) -> Result<(), TransitionError> {
         let current = self.current();
-        if current.is_connecting() || current.is_recording() {
+        if current.is_connecting() || current.is_recording() || current.is_reconnecting() {
             self.transition_with_event(AppState::idle())
         } else {
             Err(TransitionError::InvalidState {
@@ -470,6 +498,18 @@ mod tests {
         assert_eq!(event.partial_text, Some("hello world".to_string()));
     }
 
+    #[test]
+    fn test_state_change_event_from_reconnecting() {
+        let state = AppState::reconnecting(2);
+        let event = StateChangeEvent::from(&state);
+
+        assert_eq!(event.state, "Reconnecting");
+        assert!(event.is_reconnecting);
+        assert!(!event.is_recording);
+        assert!(!event.is_idle);
+        assert!(event.error_message.is_none());
+    }
+
     #[test]
     fn test_state_change_event_from_processing() {
         let state = AppState::processing();
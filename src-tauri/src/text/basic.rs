@@ -0,0 +1,339 @@
+//! 内置文本处理步骤
+//!
+//! 去除首尾空白、空白规范化、句末标点和大小写处理
+
+use serde::{Deserialize, Serialize};
+
+use super::processor::TextProcessor;
+
+/// 可以去掉的句末标点（问号、感叹号带有语气，始终保留）
+const REMOVABLE_TRAILING: &[char] = &['.', '。', ',', '，', ';', '；', '、', '：', ':'];
+
+/// 空格后不应出现的标点（前面的空格会被去掉）
+const CLOSING_PUNCTUATION: &[char] = &[
+    ',', '.', '!', '?', ';', ':', ')', ']', '}', '，', '。', '！', '？', '；', '：', '、', '）',
+    '」', '』', '》',
+];
+
+/// 英文句末标点（后面跟空白时开始新句子）
+const SENTENCE_END: &[char] = &['.', '!', '?'];
+
+/// 中日文句末标点（后面直接开始新句子）
+const CJK_SENTENCE_END: &[char] = &['。', '！', '？'];
+
+/// 判断字符是否为中日韩文字
+pub(crate) fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30FF}'     // 平假名、片假名
+        | '\u{3400}'..='\u{4DBF}'   // CJK 扩展 A
+        | '\u{4E00}'..='\u{9FFF}'   // CJK 统一汉字
+        | '\u{AC00}'..='\u{D7AF}'   // 韩文音节
+        | '\u{F900}'..='\u{FAFF}'   // CJK 兼容汉字
+        | '\u{20000}'..='\u{2FA1F}' // CJK 扩展 B 及以后
+    )
+}
+
+/// 判断字符是否属于由空格分隔的单词（中日韩文字不算）
+pub(crate) fn is_word_char(c: char) -> bool {
+    (c.is_alphanumeric() || c == '_') && !is_cjk(c)
+}
+
+/// 去除首尾空白
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Trim;
+
+impl TextProcessor for Trim {
+    fn name(&self) -> &str {
+        "trim"
+    }
+
+    fn process(&self, text: &str) -> String {
+        text.trim().to_string()
+    }
+}
+
+/// 空白规范化
+///
+/// 每行内的连续空白合并为一个空格并去掉行首行尾空白，
+/// 去掉逗号、句号等标点前多余的空格。换行保留
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizeWhitespace;
+
+impl TextProcessor for NormalizeWhitespace {
+    fn name(&self) -> &str {
+        "whitespace"
+    }
+
+    fn process(&self, text: &str) -> String {
+        text.split('\n')
+            .map(|line| {
+                let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+                remove_space_before_punctuation(&line)
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// 去掉标点前的单个空格
+fn remove_space_before_punctuation(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == ' '
+            && chars
+                .peek()
+                .is_some_and(|next| CLOSING_PUNCTUATION.contains(next))
+        {
+            continue;
+        }
+        result.push(c);
+    }
+
+    result
+}
+
+/// 句末标点策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PunctuationPolicy {
+    /// 保持原样
+    #[default]
+    Keep,
+    /// 去掉句末的句号、逗号等（适合聊天输入），问号和感叹号保留
+    Remove,
+    /// 没有句末标点时补上（中日韩文字补 `。`，其他补 `.`）
+    Ensure,
+}
+
+/// 句末标点处理
+#[derive(Debug, Clone, Copy)]
+pub struct TrailingPunctuation {
+    policy: PunctuationPolicy,
+}
+
+impl TrailingPunctuation {
+    /// 使用指定策略创建
+    pub fn new(policy: PunctuationPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+impl TextProcessor for TrailingPunctuation {
+    fn name(&self) -> &str {
+        "trailing_punctuation"
+    }
+
+    fn process(&self, text: &str) -> String {
+        match self.policy {
+            PunctuationPolicy::Keep => text.to_string(),
+            PunctuationPolicy::Remove => text
+                .trim_end()
+                .trim_end_matches(REMOVABLE_TRAILING)
+                .to_string(),
+            PunctuationPolicy::Ensure => {
+                let trimmed = text.trim_end();
+                match trimmed.chars().last() {
+                    Some(last) if is_cjk(last) => format!("{}。", trimmed),
+                    Some(last) if last.is_alphanumeric() => format!("{}.", trimmed),
+                    _ => trimmed.to_string(),
+                }
+            }
+        }
+    }
+}
+
+/// 大小写策略
+///
+/// 只影响有大小写之分的文字，中日韩文字不受影响
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Capitalization {
+    /// 保持原样
+    #[default]
+    Keep,
+    /// 每句首字母大写
+    Sentence,
+    /// 全部小写
+    Lower,
+}
+
+/// 大小写处理
+#[derive(Debug, Clone, Copy)]
+pub struct Capitalize {
+    mode: Capitalization,
+}
+
+impl Capitalize {
+    /// 使用指定策略创建
+    pub fn new(mode: Capitalization) -> Self {
+        Self { mode }
+    }
+}
+
+impl TextProcessor for Capitalize {
+    fn name(&self) -> &str {
+        "capitalization"
+    }
+
+    fn process(&self, text: &str) -> String {
+        match self.mode {
+            Capitalization::Keep => text.to_string(),
+            Capitalization::Sentence => capitalize_sentences(text),
+            Capitalization::Lower => text.to_lowercase(),
+        }
+    }
+}
+
+/// 句首字母大写
+///
+/// 文本开头、`.!?` 加空白之后、`。！？` 之后为句首；
+/// `example.com`、`3.5` 这类没有空白的点不会被当作句末
+fn capitalize_sentences(text: &str) -> String {
+    #[derive(PartialEq)]
+    enum Position {
+        SentenceStart,
+        AfterSentenceEnd,
+        InSentence,
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut position = Position::SentenceStart;
+
+    for c in text.chars() {
+        if position == Position::SentenceStart && c.is_alphabetic() {
+            result.extend(c.to_uppercase());
+            position = Position::InSentence;
+            continue;
+        }
+
+        result.push(c);
+        position = if CJK_SENTENCE_END.contains(&c) {
+            Position::SentenceStart
+        } else if SENTENCE_END.contains(&c) {
+            Position::AfterSentenceEnd
+        } else if c.is_whitespace() && position != Position::InSentence {
+            Position::SentenceStart
+        } else if c.is_alphanumeric() || position == Position::AfterSentenceEnd {
+            Position::InSentence
+        } else {
+            position
+        };
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(Trim.process("  hello \n"), "hello");
+        assert_eq!(Trim.process("   "), "");
+    }
+
+    #[test]
+    fn test_whitespace_collapses_runs() {
+        let p = NormalizeWhitespace;
+
+        assert_eq!(p.process("hello    world\tagain"), "hello world again");
+        assert_eq!(p.process("  padded  "), "padded");
+        assert_eq!(p.process("line one  \n  line two"), "line one\nline two");
+    }
+
+    #[test]
+    fn test_whitespace_before_punctuation() {
+        let p = NormalizeWhitespace;
+
+        assert_eq!(p.process("Hello , world !"), "Hello, world!");
+        assert_eq!(p.process("你好 ，世界 。"), "你好，世界。");
+        assert_eq!(p.process("(see above )"), "(see above)");
+    }
+
+    #[test]
+    fn test_trailing_punctuation_keep() {
+        let p = TrailingPunctuation::new(PunctuationPolicy::Keep);
+        assert_eq!(p.process("Done."), "Done.");
+    }
+
+    #[test]
+    fn test_trailing_punctuation_remove() {
+        let p = TrailingPunctuation::new(PunctuationPolicy::Remove);
+
+        assert_eq!(p.process("Sounds good."), "Sounds good");
+        assert_eq!(p.process("好的。"), "好的");
+        assert_eq!(p.process("Wait... "), "Wait");
+        assert_eq!(p.process("Really?"), "Really?");
+        assert_eq!(p.process("太好了！"), "太好了！");
+        assert_eq!(p.process("Version 3.5"), "Version 3.5");
+    }
+
+    #[test]
+    fn test_trailing_punctuation_ensure() {
+        let p = TrailingPunctuation::new(PunctuationPolicy::Ensure);
+
+        assert_eq!(p.process("Sounds good"), "Sounds good.");
+        assert_eq!(p.process("好的"), "好的。");
+        assert_eq!(p.process("Really?"), "Really?");
+        assert_eq!(p.process("Done. "), "Done.");
+        assert_eq!(p.process("(see above)"), "(see above)");
+        assert_eq!(p.process(""), "");
+    }
+
+    #[test]
+    fn test_capitalize_sentences() {
+        let p = Capitalize::new(Capitalization::Sentence);
+
+        assert_eq!(
+            p.process("hello world. how are you? fine!"),
+            "Hello world. How are you? Fine!"
+        );
+        assert_eq!(p.process("\"quoted\" start"), "\"Quoted\" start");
+        assert_eq!(p.process("line one\nline two"), "Line one\nline two");
+        assert_eq!(p.process("first.\nsecond"), "First.\nSecond");
+    }
+
+    #[test]
+    fn test_capitalize_ignores_inner_dots() {
+        let p = Capitalize::new(Capitalization::Sentence);
+
+        assert_eq!(
+            p.process("visit example.com today"),
+            "Visit example.com today"
+        );
+        assert_eq!(p.process("it costs 3.5 dollars"), "It costs 3.5 dollars");
+        assert_eq!(p.process("2 apples. then more"), "2 apples. Then more");
+    }
+
+    #[test]
+    fn test_capitalize_mixed_cjk() {
+        let p = Capitalize::new(Capitalization::Sentence);
+
+        assert_eq!(p.process("你好。hello world"), "你好。Hello world");
+        assert_eq!(p.process("使用 rust 编写"), "使用 rust 编写");
+    }
+
+    #[test]
+    fn test_capitalize_lower_and_keep() {
+        assert_eq!(
+            Capitalize::new(Capitalization::Lower).process("Hello World"),
+            "hello world"
+        );
+        assert_eq!(
+            Capitalize::new(Capitalization::Keep).process("Hello World"),
+            "Hello World"
+        );
+    }
+
+    #[test]
+    fn test_is_cjk() {
+        assert!(is_cjk('中'));
+        assert!(is_cjk('か'));
+        assert!(is_cjk('한'));
+        assert!(!is_cjk('a'));
+        assert!(!is_cjk('。'));
+    }
+}
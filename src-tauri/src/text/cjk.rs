@@ -0,0 +1,508 @@
+//! 中日韩文本规范化
+//!
+//! - 标点宽度：中文语境中的半角标点转为全角，或全部转为半角
+//! - 中英文间距：在中日韩文字与英文、数字之间加空格（pangu 风格）
+//! - 简繁转换：按内置对照表 `data/zh_variants.txt` 转换，多字词条优先
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+
+use super::basic::{is_cjk, is_word_char};
+use super::processor::TextProcessor;
+
+/// 语言设置为自动检测时使用的配置键
+pub const AUTO_LANGUAGE: &str = "auto";
+
+/// 内置简繁对照表
+const ZH_VARIANTS: &str = include_str!("data/zh_variants.txt");
+
+/// 半角与全角标点对照
+const PUNCTUATION_PAIRS: &[(char, char)] = &[
+    (',', '，'),
+    ('.', '。'),
+    ('!', '！'),
+    ('?', '？'),
+    (';', '；'),
+    (':', '：'),
+    ('(', '（'),
+    (')', '）'),
+];
+
+/// 标点宽度策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PunctuationWidth {
+    /// 保持原样
+    #[default]
+    Keep,
+    /// 与中日韩文字相邻的半角标点转为全角
+    FullWidth,
+    /// 全角标点转为半角（句读后补空格）
+    HalfWidth,
+}
+
+/// 简繁转换方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScriptConversion {
+    /// 不转换
+    #[default]
+    Keep,
+    /// 转为简体
+    Simplified,
+    /// 转为繁体
+    Traditional,
+}
+
+/// 单个语言的规范化选项
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CjkOptions {
+    /// 标点宽度
+    pub punctuation: PunctuationWidth,
+    /// 是否在中日韩文字与英文、数字之间加空格
+    pub spacing: bool,
+    /// 简繁转换
+    pub script: ScriptConversion,
+}
+
+impl CjkOptions {
+    /// 中文默认选项：全角标点、中英文间加空格
+    pub fn chinese() -> Self {
+        Self {
+            punctuation: PunctuationWidth::FullWidth,
+            spacing: true,
+            script: ScriptConversion::Keep,
+        }
+    }
+
+    /// 日文默认选项：全角标点，不加空格
+    pub fn japanese() -> Self {
+        Self {
+            punctuation: PunctuationWidth::FullWidth,
+            spacing: false,
+            script: ScriptConversion::Keep,
+        }
+    }
+
+    /// 内置的按语言配置
+    ///
+    /// 自动检测时按中文处理：只有与中日韩文字相邻的内容会被修改，纯英文文本不受影响
+    pub fn defaults() -> BTreeMap<String, Self> {
+        BTreeMap::from([
+            (AUTO_LANGUAGE.to_string(), Self::chinese()),
+            ("zh".to_string(), Self::chinese()),
+            ("ja".to_string(), Self::japanese()),
+        ])
+    }
+
+    /// 是否不做任何修改
+    pub fn is_noop(&self) -> bool {
+        self.punctuation == PunctuationWidth::Keep
+            && !self.spacing
+            && self.script == ScriptConversion::Keep
+    }
+
+    /// 查找语言对应的选项
+    ///
+    /// 依次尝试完整语言代码（`zh-tw`）、主语言（`zh`）；
+    /// 未指定语言（自动检测）时使用 [`AUTO_LANGUAGE`]
+    pub fn for_language<'a>(
+        options: &'a BTreeMap<String, Self>,
+        language: Option<&str>,
+    ) -> Option<&'a Self> {
+        let Some(language) = language else {
+            return options.get(AUTO_LANGUAGE);
+        };
+
+        let language = language.to_lowercase();
+        let primary = language.split(['-', '_']).next().unwrap_or_default();
+        options.get(&language).or_else(|| options.get(primary))
+    }
+}
+
+/// 中日韩文本规范化
+#[derive(Debug, Clone, Copy)]
+pub struct CjkNormalize {
+    options: CjkOptions,
+}
+
+impl CjkNormalize {
+    /// 使用指定选项创建
+    pub fn new(options: CjkOptions) -> Self {
+        Self { options }
+    }
+}
+
+impl TextProcessor for CjkNormalize {
+    fn name(&self) -> &str {
+        "cjk"
+    }
+
+    fn process(&self, text: &str) -> String {
+        let mut text = match self.options.script {
+            ScriptConversion::Keep => text.to_string(),
+            ScriptConversion::Simplified => variants().to_simplified.convert(text),
+            ScriptConversion::Traditional => variants().to_traditional.convert(text),
+        };
+
+        text = match self.options.punctuation {
+            PunctuationWidth::Keep => text,
+            PunctuationWidth::FullWidth => to_full_width(&text),
+            PunctuationWidth::HalfWidth => to_half_width(&text),
+        };
+
+        if self.options.spacing {
+            text = add_cjk_spacing(&text);
+        }
+
+        text
+    }
+}
+
+/// 单向的字形对照
+#[derive(Debug, Default)]
+struct VariantMap {
+    phrases: HashMap<String, String>,
+    chars: HashMap<char, char>,
+    /// 最长词条的字数
+    max_phrase_len: usize,
+}
+
+impl VariantMap {
+    fn insert(&mut self, from: &str, to: &str) {
+        let mut from_chars = from.chars();
+        let mut to_chars = to.chars();
+        match (
+            from_chars.next(),
+            from_chars.next(),
+            to_chars.next(),
+            to_chars.next(),
+        ) {
+            (Some(f), None, Some(t), None) => {
+                self.chars.entry(f).or_insert(t);
+            }
+            _ => {
+                self.max_phrase_len = self.max_phrase_len.max(from.chars().count());
+                self.phrases
+                    .entry(from.to_string())
+                    .or_insert_with(|| to.to_string());
+            }
+        }
+    }
+
+    /// 按最长词条优先、再按单字转换
+    fn convert(&self, text: &str) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let mut result = String::with_capacity(text.len());
+        let mut i = 0;
+
+        'outer: while i < chars.len() {
+            let longest = self.max_phrase_len.min(chars.len() - i);
+            for len in (2..=longest).rev() {
+                let candidate: String = chars[i..i + len].iter().collect();
+                if let Some(target) = self.phrases.get(&candidate) {
+                    result.push_str(target);
+                    i += len;
+                    continue 'outer;
+                }
+            }
+
+            result.push(self.chars.get(&chars[i]).copied().unwrap_or(chars[i]));
+            i += 1;
+        }
+
+        result
+    }
+}
+
+/// 简繁双向对照
+#[derive(Debug, Default)]
+struct Variants {
+    to_traditional: VariantMap,
+    to_simplified: VariantMap,
+}
+
+/// 解析内置对照表（首次使用时加载）
+fn variants() -> &'static Variants {
+    static VARIANTS: OnceLock<Variants> = OnceLock::new();
+
+    VARIANTS.get_or_init(|| {
+        let mut variants = Variants::default();
+        for line in ZH_VARIANTS.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let Some((simplified, traditional)) = line.split_once(' ') else {
+                continue;
+            };
+            let traditional = traditional.trim();
+
+            variants.to_traditional.insert(simplified, traditional);
+            variants.to_simplified.insert(traditional, simplified);
+        }
+        variants
+    })
+}
+
+/// 与中日韩文字相邻的半角标点转为全角，并去掉标点两侧的空格
+///
+/// `3.5`、`example.com`、`...` 等两侧没有中日韩文字的标点保持不变
+fn to_full_width(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let full = PUNCTUATION_PAIRS
+            .iter()
+            .find(|(half, _)| *half == c)
+            .map(|(_, full)| *full);
+
+        let Some(full) = full else {
+            result.push(c);
+            i += 1;
+            continue;
+        };
+
+        let prev = chars[..i].iter().rev().find(|c| **c != ' ').copied();
+        let next = chars[i + 1..].iter().find(|c| **c != ' ').copied();
+        let near_cjk = match c {
+            '(' => next.is_some_and(is_cjk),
+            ')' => prev.is_some_and(is_cjk),
+            '.' if chars.get(i + 1) == Some(&'.') || (i > 0 && chars[i - 1] == '.') => false,
+            _ => prev.is_some_and(is_cjk) || next.is_some_and(is_cjk),
+        };
+
+        if !near_cjk {
+            result.push(c);
+            i += 1;
+            continue;
+        }
+
+        let kept = result.trim_end_matches(' ').len();
+        result.truncate(kept);
+        result.push(full);
+        i += 1;
+        while chars.get(i) == Some(&' ') {
+            i += 1;
+        }
+    }
+
+    result
+}
+
+/// 全角标点转为半角，句读后补一个空格
+fn to_half_width(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+
+    for (i, &c) in chars.iter().enumerate() {
+        let half = PUNCTUATION_PAIRS
+            .iter()
+            .find(|(_, full)| *full == c)
+            .map(|(half, _)| *half);
+
+        let Some(half) = half else {
+            result.push(c);
+            continue;
+        };
+
+        result.push(half);
+        let needs_space = !matches!(half, '(' | ')')
+            && chars.get(i + 1).is_some_and(|next| {
+                !next.is_whitespace()
+                    && !PUNCTUATION_PAIRS
+                        .iter()
+                        .any(|(h, f)| h == next || f == next)
+            });
+        if needs_space {
+            result.push(' ');
+        }
+    }
+
+    result
+}
+
+/// 在中日韩文字与英文、数字之间加空格
+fn add_cjk_spacing(text: &str) -> String {
+    let mut result = String::with_capacity(text.len() + 8);
+    let mut prev: Option<char> = None;
+
+    for c in text.chars() {
+        let boundary =
+            prev.is_some_and(|p| (is_cjk(p) && is_word_char(c)) || (is_word_char(p) && is_cjk(c)));
+        if boundary {
+            result.push(' ');
+        }
+        result.push(c);
+        prev = Some(c);
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn normalize(options: CjkOptions, text: &str) -> String {
+        CjkNormalize::new(options).process(text)
+    }
+
+    fn punctuation(width: PunctuationWidth) -> CjkOptions {
+        CjkOptions {
+            punctuation: width,
+            ..Default::default()
+        }
+    }
+
+    fn script(conversion: ScriptConversion) -> CjkOptions {
+        CjkOptions {
+            script: conversion,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_full_width_in_chinese_context() {
+        let options = punctuation(PunctuationWidth::FullWidth);
+
+        assert_eq!(normalize(options, "你好, 世界."), "你好，世界。");
+        assert_eq!(normalize(options, "真的吗?太好了!"), "真的吗？太好了！");
+        assert_eq!(
+            normalize(options, "时间: 十点 (明天)"),
+            "时间：十点（明天）"
+        );
+        assert_eq!(normalize(options, "我用 Rust, 很好"), "我用 Rust，很好");
+    }
+
+    #[test]
+    fn test_full_width_leaves_latin_alone() {
+        let options = punctuation(PunctuationWidth::FullWidth);
+
+        assert_eq!(normalize(options, "Hello, world."), "Hello, world.");
+        assert_eq!(normalize(options, "版本 3.5 发布"), "版本 3.5 发布");
+        assert_eq!(
+            normalize(options, "访问 example.com 查看"),
+            "访问 example.com 查看"
+        );
+        assert_eq!(normalize(options, "等等..."), "等等...");
+    }
+
+    #[test]
+    fn test_half_width() {
+        let options = punctuation(PunctuationWidth::HalfWidth);
+
+        assert_eq!(
+            normalize(options, "你好，世界。我们（测试）"),
+            "你好, 世界. 我们(测试)"
+        );
+        assert_eq!(normalize(options, "真的吗？！"), "真的吗?!");
+    }
+
+    #[test]
+    fn test_cjk_spacing() {
+        let options = CjkOptions {
+            spacing: true,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            normalize(options, "我用Rust写了3个CLI工具"),
+            "我用 Rust 写了 3 个 CLI 工具"
+        );
+        assert_eq!(normalize(options, "已经是 iPhone 了"), "已经是 iPhone 了");
+        assert_eq!(normalize(options, "你好，Tom。"), "你好，Tom。");
+        assert_eq!(normalize(options, "日本語とEnglish"), "日本語と English");
+    }
+
+    #[test]
+    fn test_chinese_defaults() {
+        assert_eq!(
+            normalize(CjkOptions::chinese(), "我们用Python, 效率很高."),
+            "我们用 Python，效率很高。"
+        );
+        assert_eq!(
+            normalize(CjkOptions::chinese(), "Plain English, untouched."),
+            "Plain English, untouched."
+        );
+    }
+
+    #[test]
+    fn test_to_traditional() {
+        let options = script(ScriptConversion::Traditional);
+
+        assert_eq!(normalize(options, "这是简体中文"), "這是簡體中文");
+        assert_eq!(normalize(options, "我们开发软件"), "我們開發軟件");
+    }
+
+    #[test]
+    fn test_to_traditional_prefers_phrases() {
+        let options = script(ScriptConversion::Traditional);
+
+        assert_eq!(normalize(options, "头发很干净"), "頭髮很乾淨");
+        assert_eq!(normalize(options, "出发去吃面条"), "出發去吃麵條");
+        assert_eq!(normalize(options, "面对复杂的系统"), "面對複雜的系統");
+    }
+
+    #[test]
+    fn test_to_simplified() {
+        let options = script(ScriptConversion::Simplified);
+
+        assert_eq!(normalize(options, "這是繁體中文"), "这是繁体中文");
+        assert_eq!(normalize(options, "頭髮與麵條"), "头发与面条");
+        assert_eq!(normalize(options, "English 不變"), "English 不变");
+    }
+
+    #[test]
+    fn test_variant_table_is_consistent() {
+        let variants = variants();
+
+        assert!(variants.to_traditional.chars.len() > 500);
+        assert!(variants.to_traditional.max_phrase_len >= 2);
+        // 一简对多繁时简体转繁体保持原字
+        assert_eq!(variants.to_traditional.chars.get(&'面'), Some(&'面'));
+        assert_eq!(variants.to_simplified.chars.get(&'麵'), Some(&'面'));
+    }
+
+    #[test]
+    fn test_for_language() {
+        let options = CjkOptions::defaults();
+
+        assert_eq!(
+            CjkOptions::for_language(&options, Some("zh")),
+            Some(&CjkOptions::chinese())
+        );
+        assert_eq!(
+            CjkOptions::for_language(&options, Some("zh-TW")),
+            Some(&CjkOptions::chinese())
+        );
+        assert_eq!(
+            CjkOptions::for_language(&options, Some("ja")),
+            Some(&CjkOptions::japanese())
+        );
+        assert_eq!(
+            CjkOptions::for_language(&options, None),
+            Some(&CjkOptions::chinese())
+        );
+        assert_eq!(CjkOptions::for_language(&options, Some("en")), None);
+    }
+
+    #[test]
+    fn test_options_serialization() {
+        let json = serde_json::to_string(&CjkOptions::chinese()).unwrap();
+        assert_eq!(
+            json,
+            r#"{"punctuation":"full_width","spacing":true,"script":"keep"}"#
+        );
+
+        let options: CjkOptions = serde_json::from_str(r#"{"script": "traditional"}"#).unwrap();
+        assert_eq!(options, script(ScriptConversion::Traditional));
+        assert!(CjkOptions::default().is_noop());
+    }
+}
@@ -0,0 +1,566 @@
+//! 语气词与口吃过滤
+//!
+//! 去掉转写结果中的语气词（"um"、"uh"、"嗯"、"那个"）并合并紧邻的重复词（"I I think"）。
+//! 引号（`"…"`、`“…”`、`「…」`、`『…』`）内的内容保持原样。
+//!
+//! 过滤程度由 [`DisfluencyLevel`] 控制：
+//!
+//! - `light` - 只去掉语音停顿（um、uh、嗯、呃），合并空格分隔的重复英文单词。
+//!   数字后的 "mm"、"um" 视为单位保留，"had had"、"that that" 等合乎语法的重复不合并
+//! - `strict` - 另外去掉后跟停顿的口头禅（"you know,"、"那个，"），
+//!   合并用逗号或连字符分隔的重复单词，以及重复的中文词语（"我我我觉得"）。
+//!   中文叠词（"研究研究"）也会被合并，因此默认不启用
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use super::basic::{is_cjk, is_word_char};
+use super::processor::TextProcessor;
+
+/// 语气词后可以一并去掉的停顿标点
+const PAUSES: &[char] = &[',', '，', '、'];
+
+/// 句末标点
+const TERMINALS: &[char] = &['.', '!', '?', '。', '！', '？'];
+
+/// 保护内容不被修改的引号（开，闭）
+const QUOTES: &[(char, char)] = &[('"', '"'), ('“', '”'), ('「', '」'), ('『', '』')];
+
+/// 重复出现也合乎语法的英文单词（"I had had enough"、"I know that that works"）
+const VALID_REPEATS: &[&str] = &["had", "that", "is", "do", "very", "really", "no", "bye"];
+
+/// 中文重复词语的最大长度（字数）
+const MAX_CJK_REPEAT_LEN: usize = 4;
+
+/// 过滤程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisfluencyLevel {
+    /// 不过滤
+    #[default]
+    Off,
+    /// 去掉语音停顿，合并重复单词
+    Light,
+    /// 另外去掉口头禅和重复的中文词语
+    Strict,
+}
+
+/// 内置语气词
+struct Filler {
+    /// 正则表达式（不区分大小写）
+    pattern: &'static str,
+    /// 启用的最低过滤程度
+    level: DisfluencyLevel,
+    /// 是否只在后面跟停顿标点时去掉（"那个人" 中的 "那个" 不是语气词）
+    pause_only: bool,
+}
+
+const fn filler(pattern: &'static str, level: DisfluencyLevel, pause_only: bool) -> Filler {
+    Filler {
+        pattern,
+        level,
+        pause_only,
+    }
+}
+
+/// 英文语气词
+const ENGLISH_FILLERS: &[Filler] = &[
+    filler("u+h+", DisfluencyLevel::Light, false),
+    filler("u+h*m+", DisfluencyLevel::Light, false),
+    // 全大写的 "ER"（急诊室）不是语气词
+    filler("erm+|(?-i:[Ee]r)", DisfluencyLevel::Light, false),
+    filler("h+m+|m{3,}", DisfluencyLevel::Light, false),
+    filler("mm", DisfluencyLevel::Light, true),
+    filler(r"you\s+know", DisfluencyLevel::Strict, true),
+    filler(r"i\s+mean", DisfluencyLevel::Strict, true),
+    filler("like", DisfluencyLevel::Strict, true),
+    filler("basically", DisfluencyLevel::Strict, true),
+];
+
+/// 中文语气词（"额" 也是 "金额" 中的字，只在后跟停顿时去掉）
+const CHINESE_FILLERS: &[Filler] = &[
+    filler("嗯+", DisfluencyLevel::Light, false),
+    filler("呃+", DisfluencyLevel::Light, false),
+    filler("额", DisfluencyLevel::Light, true),
+    filler("就是说", DisfluencyLevel::Strict, true),
+    filler("那个|这个", DisfluencyLevel::Strict, true),
+    filler("然后呢", DisfluencyLevel::Strict, true),
+];
+
+/// 语言对应的语气词列表
+///
+/// 未指定语言（自动检测）时同时使用中英文列表；
+/// 没有内置列表的语言只合并重复词
+fn fillers_for(language: Option<&str>) -> Vec<&'static Filler> {
+    let primary = language.map(|code| {
+        code.split(['-', '_'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase()
+    });
+
+    let lists: &[&[Filler]] = match primary.as_deref() {
+        None => &[ENGLISH_FILLERS, CHINESE_FILLERS],
+        Some("en") => &[ENGLISH_FILLERS],
+        Some("zh") => &[CHINESE_FILLERS],
+        Some(_) => &[],
+    };
+    lists.iter().flat_map(|list| list.iter()).collect()
+}
+
+/// 编译后的语气词
+#[derive(Debug)]
+struct CompiledFiller {
+    regex: Regex,
+    pause_only: bool,
+}
+
+/// 语气词与口吃过滤
+#[derive(Debug)]
+pub struct DisfluencyFilter {
+    level: DisfluencyLevel,
+    fillers: Vec<CompiledFiller>,
+}
+
+impl DisfluencyFilter {
+    /// 按过滤程度和转写语言创建
+    pub fn new(level: DisfluencyLevel, language: Option<&str>) -> Self {
+        let fillers = if level == DisfluencyLevel::Off {
+            Vec::new()
+        } else {
+            fillers_for(language)
+                .into_iter()
+                .filter(|filler| filler.level <= level)
+                .map(|filler| CompiledFiller {
+                    regex: RegexBuilder::new(filler.pattern)
+                        .case_insensitive(true)
+                        .build()
+                        .expect("built-in filler pattern is valid"),
+                    pause_only: filler.pause_only,
+                })
+                .collect()
+        };
+
+        Self { level, fillers }
+    }
+
+    /// 过滤程度
+    pub fn level(&self) -> DisfluencyLevel {
+        self.level
+    }
+
+    /// 处理引号外的一段文本
+    fn process_unquoted(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for filler in &self.fillers {
+            text = remove_filler(&text, filler);
+        }
+
+        let strict = self.level == DisfluencyLevel::Strict;
+        text = collapse_word_repeats(&text, strict);
+        if strict {
+            text = collapse_cjk_repeats(&text);
+        }
+        text
+    }
+}
+
+impl TextProcessor for DisfluencyFilter {
+    fn name(&self) -> &str {
+        "disfluency"
+    }
+
+    fn process(&self, text: &str) -> String {
+        if self.level == DisfluencyLevel::Off {
+            return text.to_string();
+        }
+
+        split_quoted(text)
+            .into_iter()
+            .map(|(segment, quoted)| {
+                if quoted {
+                    segment.to_string()
+                } else {
+                    self.process_unquoted(segment)
+                }
+            })
+            .collect()
+    }
+}
+
+/// 按引号切分文本，返回（片段，是否在引号内）
+///
+/// 没有闭合的引号视为延续到文本末尾
+fn split_quoted(text: &str) -> Vec<(&str, bool)> {
+    let mut segments = Vec::new();
+    let mut start = 0;
+    let mut chars = text.char_indices();
+
+    while let Some((i, c)) = chars.next() {
+        let Some(&(_, close)) = QUOTES.iter().find(|(open, _)| *open == c) else {
+            continue;
+        };
+
+        if i > start {
+            segments.push((&text[start..i], false));
+        }
+        let end = chars
+            .find(|&(_, c)| c == close)
+            .map_or(text.len(), |(j, c)| j + c.len_utf8());
+        segments.push((&text[i..end], true));
+        start = end;
+    }
+
+    if start < text.len() {
+        segments.push((&text[start..], false));
+    }
+    segments
+}
+
+/// 字符是否把语气词连成单词的一部分（"uh-huh"、"summer"）
+fn joins_word(c: char) -> bool {
+    is_word_char(c) || c == '-' || c == '\''
+}
+
+/// 去掉一种语气词及其后的停顿标点
+fn remove_filler(text: &str, filler: &CompiledFiller) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+
+    for m in filler.regex.find_iter(text) {
+        let prev = text[..m.start()].chars().next_back();
+        let rest = &text[m.end()..];
+        if prev.is_some_and(joins_word) || rest.chars().next().is_some_and(joins_word) {
+            continue;
+        }
+        // "10 mm"、"5 um" 中是单位
+        let after_number = text[..m.start()]
+            .trim_end_matches(' ')
+            .ends_with(|c: char| c.is_ascii_digit());
+        if after_number {
+            continue;
+        }
+
+        let rest = rest.trim_start_matches(' ');
+        let next = rest.chars().next();
+        let followed_by_pause = next.is_some_and(|c| PAUSES.contains(&c));
+        if filler.pause_only && !followed_by_pause {
+            continue;
+        }
+
+        result.push_str(&text[last..m.start()]);
+        let mut end = text.len() - rest.len();
+
+        if followed_by_pause {
+            // "So, um, I think" -> "So I think"：两侧都是停顿时一并去掉
+            end += next.map_or(0, char::len_utf8);
+            end = text.len() - text[end..].trim_start_matches(' ').len();
+            let before = result.trim_end_matches(' ');
+            if let Some(pause) = before.chars().next_back().filter(|c| PAUSES.contains(c)) {
+                let kept = before.len() - pause.len_utf8();
+                result.truncate(kept);
+                let kept = result.trim_end_matches(' ').len();
+                result.truncate(kept);
+            }
+        } else if next.is_none_or(|c| TERMINALS.contains(&c)) {
+            // "That's it, um." -> "That's it."
+            let kept = result.trim_end_matches([' ', ',', '，', '、']).len();
+            result.truncate(kept);
+            if result.is_empty() {
+                end += next.map_or(0, char::len_utf8);
+            }
+        } else if result.is_empty() || result.ends_with(' ') {
+            end = text.len() - rest.len();
+        } else {
+            end = m.end();
+        }
+
+        let following = text[end..].chars().next();
+        if result.chars().next_back().is_some_and(is_word_char)
+            && following.is_some_and(is_word_char)
+        {
+            result.push(' ');
+        }
+
+        // 句首的 "Um, so we go" -> "So we go"
+        let sentence_start = result
+            .trim_end()
+            .chars()
+            .next_back()
+            .is_none_or(|c| TERMINALS.contains(&c));
+        if m.as_str().starts_with(char::is_uppercase)
+            && sentence_start
+            && let Some(c) = following.filter(|c| c.is_lowercase())
+        {
+            result.extend(c.to_uppercase());
+            end += c.len_utf8();
+        }
+
+        last = end;
+    }
+
+    result.push_str(&text[last..]);
+    result
+}
+
+/// 合并紧邻的重复英文单词
+///
+/// 宽松模式下单词之间只能是空白；严格模式还允许逗号和连字符（"I, I think"、"I-I think"）。
+/// 纯数字和 [`VALID_REPEATS`] 中的单词不合并
+fn collapse_word_repeats(text: &str, strict: bool) -> String {
+    let separator = |c: char| c.is_whitespace() || (strict && matches!(c, ',' | '-'));
+
+    let mut words = Vec::new();
+    let mut start = None;
+    for (i, c) in text.char_indices() {
+        let in_word = is_word_char(c) || (c == '\'' && start.is_some());
+        match (in_word, start) {
+            (true, None) => start = Some(i),
+            (false, Some(s)) => {
+                words.push((s, i));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        words.push((s, text.len()));
+    }
+
+    let mut result = String::with_capacity(text.len());
+    let mut last = 0;
+    for pair in words.windows(2) {
+        let (prev, word) = (pair[0], pair[1]);
+        let prev_text = &text[prev.0..prev.1];
+        let gap = &text[prev.1..word.0];
+
+        let repeated = prev_text.eq_ignore_ascii_case(&text[word.0..word.1])
+            && !prev_text.chars().all(|c| c.is_ascii_digit())
+            && !VALID_REPEATS
+                .iter()
+                .any(|word| prev_text.eq_ignore_ascii_case(word))
+            && gap.chars().all(separator);
+        if repeated {
+            result.push_str(&text[last..prev.1]);
+            last = word.1;
+        }
+    }
+
+    result.push_str(&text[last..]);
+    result
+}
+
+/// 合并重复的中文词语
+///
+/// 单字重复三次以上合并（"我我我" -> "我"，"谢谢" 保留），
+/// 两到四字的词语重复两次以上合并，中间可以有逗号或空格（"就是，就是" -> "就是"）
+fn collapse_cjk_repeats(text: &str) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    let mut result = String::with_capacity(text.len());
+    let mut i = 0;
+
+    'outer: while i < chars.len() {
+        if !is_cjk(chars[i]) {
+            result.push(chars[i]);
+            i += 1;
+            continue;
+        }
+
+        for len in (1..=MAX_CJK_REPEAT_LEN).rev() {
+            let Some(unit) = chars.get(i..i + len) else {
+                continue;
+            };
+            if !unit.iter().all(|c| is_cjk(*c)) {
+                continue;
+            }
+
+            let mut end = i + len;
+            let mut count = 1;
+            loop {
+                let mut next = end;
+                if len > 1
+                    && chars
+                        .get(next)
+                        .is_some_and(|c| matches!(c, ' ' | '，' | ','))
+                {
+                    next += 1;
+                }
+                if chars.get(next..next + len) != Some(unit) {
+                    break;
+                }
+                end = next + len;
+                count += 1;
+            }
+
+            let min_count = if len == 1 { 3 } else { 2 };
+            if count >= min_count {
+                result.extend(unit);
+                i = end;
+                continue 'outer;
+            }
+        }
+
+        result.push(chars[i]);
+        i += 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn filter(level: DisfluencyLevel, language: &str, text: &str) -> String {
+        DisfluencyFilter::new(level, Some(language)).process(text)
+    }
+
+    #[test]
+    fn test_off_keeps_text() {
+        let filter = DisfluencyFilter::new(DisfluencyLevel::Off, None);
+
+        assert_eq!(filter.process("um, I I think 嗯"), "um, I I think 嗯");
+    }
+
+    #[test]
+    fn test_english_light() {
+        let level = DisfluencyLevel::Light;
+
+        assert_eq!(filter(level, "en", "So um I think"), "So I think");
+        assert_eq!(filter(level, "en", "So, um, I think"), "So I think");
+        assert_eq!(filter(level, "en", "Um, so we go."), "So we go.");
+        assert_eq!(filter(level, "en", "That's it, uh."), "That's it.");
+        assert_eq!(
+            filter(level, "en", "I I think it works"),
+            "I think it works"
+        );
+        assert_eq!(filter(level, "en", "Hmm, the the plan"), "The plan");
+        assert_eq!(filter(level, "en", "Uhh."), "");
+    }
+
+    #[test]
+    fn test_english_keeps_words() {
+        let level = DisfluencyLevel::Strict;
+
+        assert_eq!(filter(level, "en", "Summer umbrella"), "Summer umbrella");
+        assert_eq!(filter(level, "en", "uh-huh, sure"), "uh-huh, sure");
+        assert_eq!(filter(level, "en", "I like it"), "I like it");
+        assert_eq!(
+            filter(level, "en", "You know the answer"),
+            "You know the answer"
+        );
+        assert_eq!(filter(level, "en", "Room 1 1 is open"), "Room 1 1 is open");
+    }
+
+    #[test]
+    fn test_english_light_keeps_content() {
+        let level = DisfluencyLevel::Light;
+
+        assert_eq!(filter(level, "en", "A 10 mm bolt"), "A 10 mm bolt");
+        assert_eq!(
+            filter(level, "en", "It is 10mm, not 5 um."),
+            "It is 10mm, not 5 um."
+        );
+        assert_eq!(
+            filter(level, "en", "We went to the ER"),
+            "We went to the ER"
+        );
+        assert_eq!(filter(level, "en", "Er, the ER is busy"), "The ER is busy");
+        assert_eq!(filter(level, "en", "Mm, okay mmm"), "Okay");
+        assert_eq!(filter(level, "en", "I had had enough"), "I had had enough");
+        assert_eq!(
+            filter(level, "en", "I know that that works"),
+            "I know that that works"
+        );
+    }
+
+    #[test]
+    fn test_english_strict() {
+        assert_eq!(
+            filter(DisfluencyLevel::Light, "en", "It's, you know, fine"),
+            "It's, you know, fine"
+        );
+
+        let level = DisfluencyLevel::Strict;
+        assert_eq!(filter(level, "en", "It's, you know, fine"), "It's fine");
+        assert_eq!(filter(level, "en", "I mean, we could try"), "We could try");
+        assert_eq!(filter(level, "en", "I, I think so"), "I think so");
+        assert_eq!(filter(level, "en", "We-we can"), "We can");
+    }
+
+    #[test]
+    fn test_chinese_light() {
+        let level = DisfluencyLevel::Light;
+
+        assert_eq!(filter(level, "zh", "嗯，我觉得可以"), "我觉得可以");
+        assert_eq!(filter(level, "zh", "我觉得，呃，可以"), "我觉得可以");
+        assert_eq!(filter(level, "zh", "我嗯嗯觉得可以。"), "我觉得可以。");
+        assert_eq!(filter(level, "zh", "就这样，嗯。"), "就这样。");
+        assert_eq!(filter(level, "zh", "额，金额不对"), "金额不对");
+        assert_eq!(filter(level, "zh", "那个，我我我觉得"), "那个，我我我觉得");
+    }
+
+    #[test]
+    fn test_chinese_strict() {
+        let level = DisfluencyLevel::Strict;
+
+        assert_eq!(filter(level, "zh", "那个，我我我觉得"), "我觉得");
+        assert_eq!(filter(level, "zh", "我想那个，明天再说"), "我想明天再说");
+        assert_eq!(filter(level, "zh", "那个人很好"), "那个人很好");
+        assert_eq!(
+            filter(level, "zh", "就是，就是说我们走吧"),
+            "就是说我们走吧"
+        );
+        assert_eq!(filter(level, "zh", "我觉得我觉得没问题"), "我觉得没问题");
+        assert_eq!(filter(level, "zh", "谢谢你"), "谢谢你");
+    }
+
+    #[test]
+    fn test_quotes_are_kept() {
+        let level = DisfluencyLevel::Strict;
+
+        assert_eq!(
+            filter(level, "en", r#"Um, he said "um, the the end" twice"#),
+            r#"He said "um, the the end" twice"#
+        );
+        assert_eq!(
+            filter(level, "zh", "嗯，他说“嗯，那个，我我我”就走了"),
+            "他说“嗯，那个，我我我”就走了"
+        );
+        assert_eq!(filter(level, "zh", "书名是「嗯嗯」"), "书名是「嗯嗯」");
+        assert_eq!(filter(level, "en", r#"uh "unclosed um"#), r#""unclosed um"#);
+    }
+
+    #[test]
+    fn test_language_selection() {
+        let level = DisfluencyLevel::Light;
+
+        assert_eq!(filter(level, "en", "嗯，OK um"), "嗯，OK");
+        assert_eq!(filter(level, "zh-CN", "嗯，OK um"), "OK um");
+        assert_eq!(filter(level, "fr", "euh, the the"), "euh, the");
+        assert_eq!(
+            DisfluencyFilter::new(level, None).process("嗯，OK um"),
+            "OK"
+        );
+    }
+
+    #[test]
+    fn test_split_quoted() {
+        assert_eq!(
+            split_quoted(r#"a "b" c"#),
+            vec![("a ", false), (r#""b""#, true), (" c", false)]
+        );
+        assert_eq!(split_quoted("「开"), vec![("「开", true)]);
+        assert!(split_quoted("").is_empty());
+    }
+
+    #[test]
+    fn test_level_serialization() {
+        let json = serde_json::to_string(&DisfluencyLevel::Strict).unwrap();
+        assert_eq!(json, r#""strict""#);
+
+        let level: DisfluencyLevel = serde_json::from_str(r#""light""#).unwrap();
+        assert_eq!(level, DisfluencyLevel::Light);
+        assert!(DisfluencyLevel::Off < DisfluencyLevel::Light);
+    }
+}
@@ -0,0 +1,48 @@
+//! 文本后处理模块
+//!
+//! 在最终转写注入到目标应用、复制到剪贴板之前对文本进行整理
+//!
+//! # 子模块
+//!
+//! - [`processor`] - `TextProcessor` 接口和按配置构建的处理链
+//! - [`basic`] - 内置处理步骤（去除首尾空白、空白规范化、句末标点、大小写）
+//! - [`cjk`] - 中日韩文本规范化（标点宽度、中英文间距、简繁转换）
+//! - [`disfluency`] - 语气词与口吃过滤（"um"、"嗯"、重复词）
+//! - [`replace`] - 用户定义的替换词典（字面、整词、正则规则）
+//! - [`voice`] - 语音编辑命令（"new line"、"句号"、"delete that" 等）
+//!
+//! # 使用示例
+//!
+//! ```
+//! use raflow_lib::text::{Capitalization, ProcessingChain, PunctuationPolicy};
+//! use raflow_lib::text::{NormalizeWhitespace, TrailingPunctuation, Trim, Capitalize};
+//!
+//! let chain = ProcessingChain::new()
+//!     .with_processor(Trim)
+//!     .with_processor(NormalizeWhitespace)
+//!     .with_processor(TrailingPunctuation::new(PunctuationPolicy::Remove))
+//!     .with_processor(Capitalize::new(Capitalization::Sentence));
+//!
+//! assert_eq!(chain.process("  hello   world. "), "Hello world");
+//! ```
+//!
+//! 会话中使用的处理链由 [`ProcessingChain::from_config`] 按 `AppConfig` 构建，
+//! 每个步骤都是纯函数，不依赖 Tauri，可单独测试。
+
+pub mod basic;
+pub mod cjk;
+pub mod disfluency;
+pub mod processor;
+pub mod replace;
+pub mod voice;
+
+pub use basic::{
+    Capitalization, Capitalize, NormalizeWhitespace, PunctuationPolicy, TrailingPunctuation, Trim,
+};
+pub use cjk::{CjkNormalize, CjkOptions, PunctuationWidth, ScriptConversion, AUTO_LANGUAGE};
+pub use disfluency::{DisfluencyFilter, DisfluencyLevel};
+pub use processor::{ProcessingChain, ProcessingStage, TextProcessor};
+pub use replace::{
+    export_rules, import_rules, MatchMode, ReplacementDictionary, ReplacementError, ReplacementRule,
+};
+pub use voice::{render_plain, EditAction, VoiceAction, VoiceCommand, VoiceCommandInterpreter};
@@ -0,0 +1,311 @@
+//! 文本处理链
+//!
+//! [`TextProcessor`] 是单个处理步骤的接口，[`ProcessingChain`] 按顺序执行多个步骤
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::basic::{Capitalization, Capitalize, NormalizeWhitespace, PunctuationPolicy};
+use super::basic::{TrailingPunctuation, Trim};
+use super::cjk::{CjkNormalize, CjkOptions};
+use super::disfluency::{DisfluencyFilter, DisfluencyLevel};
+use super::replace::ReplacementDictionary;
+use crate::state::AppConfig;
+
+/// 文本处理步骤
+///
+/// 实现应为纯函数：相同输入总是得到相同输出，不依赖外部状态
+pub trait TextProcessor: Send + Sync {
+    /// 步骤名称（用于日志）
+    fn name(&self) -> &str;
+
+    /// 处理文本并返回结果
+    fn process(&self, text: &str) -> String;
+}
+
+/// 可在配置中启用的内置处理步骤
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProcessingStage {
+    /// 去除首尾空白
+    Trim,
+    /// 按 `BehaviorConfig::disfluency` 去掉语气词、合并重复词
+    Disfluency,
+    /// 合并连续空白，去掉标点前的空格
+    Whitespace,
+    /// 按替换词典修正专有名词和术语
+    Replacements,
+    /// 按当前语言的 `CjkOptions` 处理标点宽度、中英文间距和简繁转换
+    Cjk,
+    /// 按 `PunctuationPolicy` 处理句末标点
+    TrailingPunctuation,
+    /// 按 `Capitalization` 处理大小写
+    Capitalization,
+}
+
+impl ProcessingStage {
+    /// 默认启用的步骤及顺序
+    pub fn defaults() -> Vec<Self> {
+        vec![
+            Self::Trim,
+            Self::Disfluency,
+            Self::Whitespace,
+            Self::Replacements,
+            Self::Cjk,
+            Self::TrailingPunctuation,
+            Self::Capitalization,
+        ]
+    }
+}
+
+/// 文本处理链
+///
+/// 按添加顺序依次执行各步骤，前一步的输出作为后一步的输入
+#[derive(Default)]
+pub struct ProcessingChain {
+    processors: Vec<Box<dyn TextProcessor>>,
+}
+
+impl ProcessingChain {
+    /// 创建空的处理链（不修改文本）
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 根据应用配置构建处理链
+    ///
+    /// 按 `ProcessingConfig::stages` 的顺序添加步骤；关闭后处理时返回空链，
+    /// 策略为 `Keep` 或 `Off` 的步骤、没有有效规则的替换词典和当前语言没有 CJK 选项时不会添加
+    pub fn from_config(config: &AppConfig) -> Self {
+        let processing = &config.processing;
+        let mut chain = Self::new();
+        if !processing.enabled {
+            return chain;
+        }
+
+        for stage in &processing.stages {
+            match stage {
+                ProcessingStage::Trim => chain.push(Box::new(Trim)),
+                ProcessingStage::Disfluency => {
+                    if config.behavior.disfluency != DisfluencyLevel::Off {
+                        chain.push(Box::new(DisfluencyFilter::new(
+                            config.behavior.disfluency,
+                            config.api.language_code.as_deref(),
+                        )));
+                    }
+                }
+                ProcessingStage::Whitespace => chain.push(Box::new(NormalizeWhitespace)),
+                ProcessingStage::Replacements => {
+                    let dictionary = ReplacementDictionary::lenient(&processing.replacements);
+                    if !dictionary.is_empty() {
+                        chain.push(Box::new(dictionary));
+                    }
+                }
+                ProcessingStage::Cjk => {
+                    let options = CjkOptions::for_language(
+                        &processing.cjk,
+                        config.api.language_code.as_deref(),
+                    );
+                    if let Some(options) = options.filter(|o| !o.is_noop()) {
+                        chain.push(Box::new(CjkNormalize::new(*options)));
+                    }
+                }
+                ProcessingStage::TrailingPunctuation => {
+                    if processing.trailing_punctuation != PunctuationPolicy::Keep {
+                        chain.push(Box::new(TrailingPunctuation::new(
+                            processing.trailing_punctuation,
+                        )));
+                    }
+                }
+                ProcessingStage::Capitalization => {
+                    if processing.capitalization != Capitalization::Keep {
+                        chain.push(Box::new(Capitalize::new(processing.capitalization)));
+                    }
+                }
+            }
+        }
+
+        chain
+    }
+
+    /// 在末尾添加步骤
+    pub fn with_processor(mut self, processor: impl TextProcessor + 'static) -> Self {
+        self.push(Box::new(processor));
+        self
+    }
+
+    /// 在末尾添加步骤
+    pub fn push(&mut self, processor: Box<dyn TextProcessor>) {
+        self.processors.push(processor);
+    }
+
+    /// 按顺序执行所有步骤
+    pub fn process(&self, text: &str) -> String {
+        let mut text = text.to_string();
+        for processor in &self.processors {
+            text = processor.process(&text);
+        }
+        text
+    }
+
+    /// 各步骤的名称（按执行顺序）
+    pub fn names(&self) -> Vec<&str> {
+        self.processors.iter().map(|p| p.name()).collect()
+    }
+
+    /// 步骤数量
+    pub fn len(&self) -> usize {
+        self.processors.len()
+    }
+
+    /// 是否没有任何步骤
+    pub fn is_empty(&self) -> bool {
+        self.processors.is_empty()
+    }
+}
+
+impl fmt::Debug for ProcessingChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::text::ScriptConversion;
+
+    /// 给文本加上标记，用于验证执行顺序
+    struct Tag(&'static str);
+
+    impl TextProcessor for Tag {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn process(&self, text: &str) -> String {
+            format!("{}{}", text, self.0)
+        }
+    }
+
+    #[test]
+    fn test_empty_chain_keeps_text() {
+        let chain = ProcessingChain::new();
+
+        assert!(chain.is_empty());
+        assert_eq!(chain.process("  as is  "), "  as is  ");
+    }
+
+    #[test]
+    fn test_chain_runs_in_order() {
+        let chain = ProcessingChain::new()
+            .with_processor(Tag("a"))
+            .with_processor(Tag("b"))
+            .with_processor(Tag("c"));
+
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain.names(), vec!["a", "b", "c"]);
+        assert_eq!(chain.process("x"), "xabc");
+        assert_eq!(format!("{:?}", chain), r#"["a", "b", "c"]"#);
+    }
+
+    #[test]
+    fn test_from_default_config() {
+        let chain = ProcessingChain::from_config(&AppConfig::default());
+
+        assert_eq!(chain.names(), vec!["trim", "whitespace", "cjk"]);
+        assert_eq!(chain.process("  hello   world ,  ok  "), "hello world, ok");
+        assert_eq!(chain.process(" 我用Rust , 很好. "), "我用 Rust，很好。");
+    }
+
+    #[test]
+    fn test_from_config_cjk_follows_language() {
+        let mut config = AppConfig::default();
+        config.api.language_code = Some("en".to_string());
+        assert_eq!(
+            ProcessingChain::from_config(&config).names(),
+            vec!["trim", "whitespace"]
+        );
+
+        config.api.language_code = Some("zh-TW".to_string());
+        config.processing.cjk.get_mut("zh").unwrap().script = ScriptConversion::Traditional;
+        let chain = ProcessingChain::from_config(&config);
+
+        assert_eq!(chain.names(), vec!["trim", "whitespace", "cjk"]);
+        assert_eq!(chain.process("这是简体, 对吧?"), "這是簡體，對吧？");
+    }
+
+    #[test]
+    fn test_from_config_follows_stage_order() {
+        let mut config = AppConfig::default();
+        config.processing.stages = vec![
+            ProcessingStage::Capitalization,
+            ProcessingStage::TrailingPunctuation,
+            ProcessingStage::Trim,
+        ];
+        config.processing.trailing_punctuation = PunctuationPolicy::Ensure;
+        config.processing.capitalization = Capitalization::Sentence;
+
+        let chain = ProcessingChain::from_config(&config);
+
+        assert_eq!(
+            chain.names(),
+            vec!["capitalization", "trailing_punctuation", "trim"]
+        );
+        assert_eq!(chain.process(" hello world "), "Hello world.");
+    }
+
+    #[test]
+    fn test_from_config_with_replacements() {
+        use crate::text::ReplacementRule;
+
+        let mut config = AppConfig::default();
+        config.api.language_code = Some("en".to_string());
+        config.processing.replacements = vec![
+            ReplacementRule::whole_word("ray flow", "RaFlow"),
+            ReplacementRule::regex("(unclosed", "ignored"),
+        ];
+
+        let chain = ProcessingChain::from_config(&config);
+
+        assert_eq!(chain.names(), vec!["trim", "whitespace", "replacements"]);
+        assert_eq!(chain.process("  open  Ray Flow now "), "open RaFlow now");
+    }
+
+    #[test]
+    fn test_from_config_with_disfluency() {
+        use crate::text::DisfluencyLevel;
+
+        let mut config = AppConfig::default();
+        config.behavior.disfluency = DisfluencyLevel::Light;
+
+        let chain = ProcessingChain::from_config(&config);
+
+        assert_eq!(
+            chain.names(),
+            vec!["trim", "disfluency", "whitespace", "cjk"]
+        );
+        assert_eq!(chain.process(" 嗯，我用Rust 写的 "), "我用 Rust 写的");
+    }
+
+    #[test]
+    fn test_from_config_disabled() {
+        let mut config = AppConfig::default();
+        config.processing.enabled = false;
+
+        assert!(ProcessingChain::from_config(&config).is_empty());
+    }
+
+    #[test]
+    fn test_stage_serialization() {
+        let json = serde_json::to_string(&ProcessingStage::defaults()).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"["trim","disfluency","whitespace","replacements","cjk","#,
+                r#""trailing_punctuation","capitalization"]"#
+            )
+        );
+    }
+}
@@ -0,0 +1,424 @@
+//! 替换词典
+//!
+//! 按用户定义的规则修正转写结果中的专有名词和术语，支持三种匹配方式：
+//!
+//! - `literal` - 字面匹配（出现在任何位置都替换）
+//! - `whole_word` - 整词匹配（`app` 不会匹配 `apple`；与中日韩文字相邻视为词边界）
+//! - `regex` - 正则匹配，替换文本中可用 `$1`、`${name}` 引用捕获组
+//!
+//! 默认不区分大小写
+
+use std::fs;
+use std::path::Path;
+
+use regex::{NoExpand, Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use super::basic::is_word_char;
+use super::processor::TextProcessor;
+
+/// 替换词典错误
+#[derive(Error, Debug)]
+pub enum ReplacementError {
+    /// 匹配内容为空
+    #[error("Replacement pattern is empty")]
+    EmptyPattern,
+
+    /// 正则表达式无效
+    #[error("Invalid pattern '{pattern}': {source}")]
+    InvalidPattern {
+        pattern: String,
+        #[source]
+        source: regex::Error,
+    },
+
+    /// IO 错误
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON 序列化/反序列化错误
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// 匹配方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchMode {
+    /// 字面匹配
+    #[default]
+    Literal,
+    /// 整词匹配
+    WholeWord,
+    /// 正则匹配
+    Regex,
+}
+
+/// 替换规则
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplacementRule {
+    /// 要匹配的内容（`regex` 模式下为正则表达式）
+    pub pattern: String,
+    /// 替换为的内容
+    pub replacement: String,
+    /// 匹配方式
+    #[serde(default)]
+    pub mode: MatchMode,
+    /// 是否区分大小写
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// 是否启用
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+impl ReplacementRule {
+    /// 创建字面匹配规则
+    pub fn literal(pattern: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self {
+            pattern: pattern.into(),
+            replacement: replacement.into(),
+            mode: MatchMode::Literal,
+            case_sensitive: false,
+            enabled: true,
+        }
+    }
+
+    /// 创建整词匹配规则
+    pub fn whole_word(pattern: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self {
+            mode: MatchMode::WholeWord,
+            ..Self::literal(pattern, replacement)
+        }
+    }
+
+    /// 创建正则匹配规则
+    pub fn regex(pattern: impl Into<String>, replacement: impl Into<String>) -> Self {
+        Self {
+            mode: MatchMode::Regex,
+            ..Self::literal(pattern, replacement)
+        }
+    }
+
+    /// 设置是否区分大小写
+    pub fn with_case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// 检查规则是否可以编译
+    pub fn validate(&self) -> Result<(), ReplacementError> {
+        CompiledRule::compile(self).map(|_| ())
+    }
+}
+
+/// 编译后的规则
+#[derive(Debug)]
+struct CompiledRule {
+    regex: Regex,
+    replacement: String,
+    mode: MatchMode,
+}
+
+impl CompiledRule {
+    fn compile(rule: &ReplacementRule) -> Result<Self, ReplacementError> {
+        if rule.pattern.is_empty() {
+            return Err(ReplacementError::EmptyPattern);
+        }
+
+        let source = match rule.mode {
+            MatchMode::Literal | MatchMode::WholeWord => regex::escape(&rule.pattern),
+            MatchMode::Regex => rule.pattern.clone(),
+        };
+
+        let regex = RegexBuilder::new(&source)
+            .case_insensitive(!rule.case_sensitive)
+            .build()
+            .map_err(|source| ReplacementError::InvalidPattern {
+                pattern: rule.pattern.clone(),
+                source,
+            })?;
+
+        Ok(Self {
+            regex,
+            replacement: rule.replacement.clone(),
+            mode: rule.mode,
+        })
+    }
+
+    fn apply(&self, text: &str) -> String {
+        match self.mode {
+            MatchMode::Literal => self
+                .regex
+                .replace_all(text, NoExpand(&self.replacement))
+                .into_owned(),
+            MatchMode::WholeWord => self.replace_whole_words(text),
+            MatchMode::Regex => self
+                .regex
+                .replace_all(text, self.replacement.as_str())
+                .into_owned(),
+        }
+    }
+
+    /// 只替换两侧都在词边界上的匹配
+    ///
+    /// 不使用正则的 `\b`：它把中日韩文字也当作单词字符，
+    /// `部署到k8s集群` 中的 `k8s` 就无法匹配
+    fn replace_whole_words(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut last = 0;
+
+        for m in self.regex.find_iter(text) {
+            let before = text[..m.start()].chars().next_back();
+            let after = text[m.end()..].chars().next();
+            let first = m.as_str().chars().next();
+            let end = m.as_str().chars().next_back();
+
+            let joined = |outer: Option<char>, inner: Option<char>| {
+                outer.is_some_and(is_word_char) && inner.is_some_and(is_word_char)
+            };
+            if joined(before, first) || joined(after, end) {
+                continue;
+            }
+
+            result.push_str(&text[last..m.start()]);
+            result.push_str(&self.replacement);
+            last = m.end();
+        }
+
+        result.push_str(&text[last..]);
+        result
+    }
+}
+
+/// 替换词典
+///
+/// 按规则顺序依次替换，后面的规则作用于前面规则的结果
+#[derive(Debug, Default)]
+pub struct ReplacementDictionary {
+    rules: Vec<CompiledRule>,
+}
+
+impl ReplacementDictionary {
+    /// 编译规则，任一规则无效时返回错误
+    ///
+    /// 未启用的规则会被跳过
+    pub fn new(rules: &[ReplacementRule]) -> Result<Self, ReplacementError> {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .map(CompiledRule::compile)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { rules })
+    }
+
+    /// 编译规则，跳过无效的规则（记录警告）
+    ///
+    /// 用于按已保存的配置构建处理链，单条规则错误不影响其他规则
+    pub fn lenient(rules: &[ReplacementRule]) -> Self {
+        let rules = rules
+            .iter()
+            .filter(|rule| rule.enabled)
+            .filter_map(|rule| match CompiledRule::compile(rule) {
+                Ok(compiled) => Some(compiled),
+                Err(e) => {
+                    tracing::warn!(pattern = %rule.pattern, error = %e, "Skipping replacement rule");
+                    None
+                }
+            })
+            .collect();
+
+        Self { rules }
+    }
+
+    /// 有效规则数量
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// 是否没有有效规则
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+impl TextProcessor for ReplacementDictionary {
+    fn name(&self) -> &str {
+        "replacements"
+    }
+
+    fn process(&self, text: &str) -> String {
+        self.rules
+            .iter()
+            .fold(text.to_string(), |text, rule| rule.apply(&text))
+    }
+}
+
+/// 从 JSON 文件导入规则
+///
+/// 文件内容为规则数组，导入前会检查每条规则是否有效
+pub fn import_rules(path: &Path) -> Result<Vec<ReplacementRule>, ReplacementError> {
+    let content = fs::read_to_string(path)?;
+    let rules: Vec<ReplacementRule> = serde_json::from_str(&content)?;
+
+    for rule in &rules {
+        rule.validate()?;
+    }
+
+    Ok(rules)
+}
+
+/// 将规则导出为 JSON 文件
+pub fn export_rules(path: &Path, rules: &[ReplacementRule]) -> Result<(), ReplacementError> {
+    let content = serde_json::to_string_pretty(rules)?;
+    fs::write(path, content)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn apply(rules: &[ReplacementRule], text: &str) -> String {
+        ReplacementDictionary::new(rules).unwrap().process(text)
+    }
+
+    #[test]
+    fn test_literal_is_case_insensitive() {
+        let rules = [ReplacementRule::literal("rayflow", "RaFlow")];
+
+        assert_eq!(
+            apply(&rules, "Rayflow and RAYFLOW and rayflows"),
+            "RaFlow and RaFlow and RaFlows"
+        );
+    }
+
+    #[test]
+    fn test_literal_case_sensitive() {
+        let rules = [ReplacementRule::literal("api", "API").with_case_sensitive(true)];
+
+        assert_eq!(apply(&rules, "api Api"), "API Api");
+    }
+
+    #[test]
+    fn test_literal_does_not_expand_captures() {
+        let rules = [ReplacementRule::literal("price", "$1 (USD)")];
+
+        assert_eq!(apply(&rules, "the price"), "the $1 (USD)");
+    }
+
+    #[test]
+    fn test_whole_word() {
+        let rules = [ReplacementRule::whole_word("app", "application")];
+
+        assert_eq!(
+            apply(&rules, "the app, an apple, App."),
+            "the application, an apple, application."
+        );
+    }
+
+    #[test]
+    fn test_whole_word_cjk() {
+        let rules = [
+            ReplacementRule::whole_word("瑞福", "RaFlow"),
+            ReplacementRule::whole_word("k8s", "Kubernetes"),
+        ];
+
+        assert_eq!(
+            apply(&rules, "打开瑞福，部署到k8s集群"),
+            "打开RaFlow，部署到Kubernetes集群"
+        );
+    }
+
+    #[test]
+    fn test_regex_with_capture_groups() {
+        let rules = [
+            ReplacementRule::regex(r"version (\d+) point (\d+)", "v$1.$2"),
+            ReplacementRule::regex(r"(?P<user>\w+) at example dot com", "${user}@example.com"),
+        ];
+
+        assert_eq!(
+            apply(&rules, "Version 2 point 5 from alice at example dot com"),
+            "v2.5 from alice@example.com"
+        );
+    }
+
+    #[test]
+    fn test_rules_apply_in_order() {
+        let rules = [
+            ReplacementRule::literal("eleven labs", "ElevenLabs"),
+            ReplacementRule::whole_word("elevenlabs", "ElevenLabs Inc."),
+        ];
+
+        assert_eq!(apply(&rules, "eleven labs"), "ElevenLabs Inc.");
+    }
+
+    #[test]
+    fn test_disabled_rules_are_skipped() {
+        let mut rule = ReplacementRule::literal("foo", "bar");
+        rule.enabled = false;
+
+        let dictionary = ReplacementDictionary::new(&[rule]).unwrap();
+        assert!(dictionary.is_empty());
+        assert_eq!(dictionary.process("foo"), "foo");
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(matches!(
+            ReplacementRule::regex("(unclosed", "x").validate(),
+            Err(ReplacementError::InvalidPattern { .. })
+        ));
+        assert!(matches!(
+            ReplacementRule::literal("", "x").validate(),
+            Err(ReplacementError::EmptyPattern)
+        ));
+        assert!(ReplacementRule::literal("(unclosed", "x")
+            .validate()
+            .is_ok());
+    }
+
+    #[test]
+    fn test_lenient_skips_invalid_rules() {
+        let rules = [
+            ReplacementRule::regex("(unclosed", "x"),
+            ReplacementRule::literal("foo", "bar"),
+        ];
+
+        assert!(ReplacementDictionary::new(&rules).is_err());
+
+        let dictionary = ReplacementDictionary::lenient(&rules);
+        assert_eq!(dictionary.len(), 1);
+        assert_eq!(dictionary.process("foo"), "bar");
+    }
+
+    #[test]
+    fn test_rule_deserialization_defaults() {
+        let rule: ReplacementRule =
+            serde_json::from_str(r#"{"pattern": "foo", "replacement": "bar"}"#).unwrap();
+
+        assert_eq!(rule, ReplacementRule::literal("foo", "bar"));
+    }
+
+    #[test]
+    fn test_import_export_roundtrip() {
+        let path =
+            std::env::temp_dir().join(format!("raflow_replacements_{}.json", std::process::id()));
+        let rules = vec![
+            ReplacementRule::literal("rayflow", "RaFlow"),
+            ReplacementRule::regex(r"(\d+) percent", "$1%").with_case_sensitive(true),
+        ];
+
+        export_rules(&path, &rules).unwrap();
+        let imported = import_rules(&path).unwrap();
+        let _ = fs::remove_file(&path);
+
+        assert_eq!(imported, rules);
+    }
+}
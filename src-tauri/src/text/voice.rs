@@ -0,0 +1,590 @@
+//! 语音编辑命令
+//!
+//! 识别最终转写中的口述命令（如 "new line"、"句号"、"delete that"），
+//! 把文本拆分为要输入的文字和要执行的编辑操作，而不是把命令词原样输入
+//!
+//! 命令短语不区分大小写，并且只有单独成句（整段话，或前后都是文本开头结尾或标点）
+//! 时才算命令，"the trial period ends"、"我们需要撤销这个申请" 中的短语按原文输入。
+//! 连续的几个命令（"New line. New line."）一起算作一句。
+//! Scribe 附在命令词后面的标点（如 `New line.`）会一并去掉
+
+use std::collections::HashMap;
+use std::ops::Range;
+
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+use crate::state::AppConfig;
+
+/// 句读标点：划分命令短语所在的句子，也是 Scribe 可能附在命令词前后的标点
+const COMMAND_PUNCTUATION: &[char] = &[
+    '.', ',', '!', '?', ';', ':', '。', '，', '！', '？', '；', '：', '、',
+];
+
+/// 命令短语对应的动作（用于配置）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VoiceAction {
+    /// 插入文本（通常是标点），会去掉前面的空格
+    Insert(String),
+    /// 按 Enter
+    Enter,
+    /// 按 Tab
+    Tab,
+    /// 按 Backspace
+    Backspace,
+    /// 全选
+    SelectAll,
+    /// 删除上一段文字
+    DeleteThat,
+    /// 撤销
+    Undo,
+}
+
+/// 命令短语
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VoiceCommand {
+    /// 口述短语
+    pub phrase: String,
+    /// 对应的动作
+    pub action: VoiceAction,
+}
+
+impl VoiceCommand {
+    /// 创建命令短语
+    pub fn new(phrase: impl Into<String>, action: VoiceAction) -> Self {
+        Self {
+            phrase: phrase.into(),
+            action,
+        }
+    }
+
+    /// 内置的中英文命令短语表
+    pub fn defaults() -> Vec<Self> {
+        use VoiceAction::*;
+
+        let insert = |s: &str| Insert(s.to_string());
+        [
+            // English
+            ("new line", Enter),
+            ("press enter", Enter),
+            ("press tab", Tab),
+            ("backspace", Backspace),
+            ("select all", SelectAll),
+            ("delete that", DeleteThat),
+            ("scratch that", DeleteThat),
+            ("undo that", Undo),
+            ("period", insert(".")),
+            ("full stop", insert(".")),
+            ("comma", insert(",")),
+            ("question mark", insert("?")),
+            ("exclamation mark", insert("!")),
+            ("exclamation point", insert("!")),
+            ("colon", insert(":")),
+            ("semicolon", insert(";")),
+            // 中文
+            ("换行", Enter),
+            ("另起一行", Enter),
+            ("退格", Backspace),
+            ("全选", SelectAll),
+            ("删掉这句", DeleteThat),
+            ("删除上一句", DeleteThat),
+            ("撤销", Undo),
+            ("句号", insert("。")),
+            ("逗号", insert("，")),
+            ("问号", insert("？")),
+            ("感叹号", insert("！")),
+            ("冒号", insert("：")),
+            ("分号", insert("；")),
+            ("顿号", insert("、")),
+        ]
+        .into_iter()
+        .map(|(phrase, action)| Self::new(phrase, action))
+        .collect()
+    }
+}
+
+/// 解释后的编辑操作
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EditAction {
+    /// 输入文字
+    Text(String),
+    /// 按 Enter
+    Enter,
+    /// 按 Tab
+    Tab,
+    /// 按 Backspace
+    Backspace,
+    /// 全选
+    SelectAll,
+    /// 删除之前注入的最后一段文字
+    DeletePrevious,
+    /// 撤销（系统撤销快捷键）
+    Undo,
+}
+
+/// 语音命令解释器
+#[derive(Debug)]
+pub struct VoiceCommandInterpreter {
+    /// 匹配所有短语的正则（没有短语时为 None）
+    regex: Option<Regex>,
+    /// 规范化后的短语 -> 动作
+    actions: HashMap<String, VoiceAction>,
+}
+
+impl VoiceCommandInterpreter {
+    /// 根据命令短语表创建解释器
+    ///
+    /// 空短语会被忽略；重复的短语以后出现的为准
+    pub fn new(commands: &[VoiceCommand]) -> Self {
+        let actions: HashMap<String, VoiceAction> = commands
+            .iter()
+            .map(|command| (normalize_phrase(&command.phrase), command.action.clone()))
+            .filter(|(phrase, _)| !phrase.is_empty())
+            .collect();
+
+        // 长短语优先，"new line" 不会被更短的短语截断
+        let mut phrases: Vec<&String> = actions.keys().collect();
+        phrases.sort_by(|a, b| b.len().cmp(&a.len()).then(a.cmp(b)));
+
+        let regex = (!phrases.is_empty()).then(|| {
+            let alternation = phrases
+                .iter()
+                .map(|phrase| {
+                    phrase
+                        .split(' ')
+                        .map(regex::escape)
+                        .collect::<Vec<_>>()
+                        .join(r"\s+")
+                })
+                .collect::<Vec<_>>()
+                .join("|");
+
+            RegexBuilder::new(&alternation)
+                .case_insensitive(true)
+                .build()
+                .expect("escaped phrases always form a valid regex")
+        });
+
+        Self { regex, actions }
+    }
+
+    /// 根据应用配置创建解释器，未启用语音命令时返回 None
+    pub fn from_config(config: &AppConfig) -> Option<Self> {
+        let voice = &config.voice_commands;
+        voice.enabled.then(|| Self::new(&voice.commands))
+    }
+
+    /// 将文本解释为编辑操作序列
+    ///
+    /// 同一段话中的 "delete that" 直接丢弃前面尚未输入的文字；
+    /// 开头就说 "delete that" 时删除之前注入的最后一段文字
+    pub fn interpret(&self, text: &str) -> Vec<EditAction> {
+        let Some(regex) = &self.regex else {
+            return text_action(text);
+        };
+
+        let matches: Vec<(Range<usize>, &VoiceAction)> = regex
+            .find_iter(text)
+            .filter_map(|m| {
+                let action = self.actions.get(&normalize_phrase(m.as_str()))?;
+                Some((m.range(), action))
+            })
+            .collect();
+
+        let mut actions = Vec::new();
+        let mut pending = String::new();
+        let mut last = 0;
+
+        for (range, action) in clause_commands(text, matches) {
+            if range.start < last {
+                continue;
+            }
+
+            pending.push_str(&text[last..range.start]);
+            let mut end = skip_chars(text, range.end, is_command_punctuation);
+
+            match action {
+                VoiceAction::Insert(insert) => {
+                    let kept = pending
+                        .trim_end()
+                        .trim_end_matches(COMMAND_PUNCTUATION)
+                        .len();
+                    pending.truncate(kept);
+                    pending.push_str(insert);
+                }
+                VoiceAction::DeleteThat => {
+                    if !pending.trim().is_empty() {
+                        pending.clear();
+                    } else if matches!(
+                        actions.last(),
+                        Some(EditAction::Text(_) | EditAction::Enter | EditAction::Tab)
+                    ) {
+                        actions.pop();
+                        // 删掉按键后，前面的文字与后面的文字接续，保留原文中两者之间的空白
+                        if let Some(EditAction::Text(previous)) = actions.last() {
+                            pending = previous.clone();
+                            actions.pop();
+                            last = end;
+                            continue;
+                        }
+                    } else {
+                        actions.push(EditAction::DeletePrevious);
+                    }
+                    end = skip_chars(text, end, char::is_whitespace);
+                }
+                _ => {
+                    flush(&mut actions, &mut pending);
+                    actions.push(match action {
+                        VoiceAction::Enter => EditAction::Enter,
+                        VoiceAction::Tab => EditAction::Tab,
+                        VoiceAction::Backspace => EditAction::Backspace,
+                        VoiceAction::SelectAll => EditAction::SelectAll,
+                        _ => EditAction::Undo,
+                    });
+                    end = skip_chars(text, end, char::is_whitespace);
+                }
+            }
+
+            last = end;
+        }
+
+        pending.push_str(&text[last..]);
+        flush(&mut actions, &mut pending);
+        actions
+    }
+}
+
+/// 只包含文字的操作序列
+fn text_action(text: &str) -> Vec<EditAction> {
+    if text.is_empty() {
+        Vec::new()
+    } else {
+        vec![EditAction::Text(text.to_string())]
+    }
+}
+
+/// 把尚未输入的文字作为一个操作加入序列
+fn flush(actions: &mut Vec<EditAction>, pending: &mut String) {
+    let text = pending.trim_end();
+    if !text.trim_start().is_empty() {
+        actions.push(EditAction::Text(text.to_string()));
+    }
+    pending.clear();
+}
+
+/// 小写并合并空白，作为短语表的键
+fn normalize_phrase(phrase: &str) -> String {
+    phrase
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+/// 是否是句读标点
+fn is_command_punctuation(c: char) -> bool {
+    COMMAND_PUNCTUATION.contains(&c)
+}
+
+/// 只保留单独成句的命令短语
+///
+/// 之间只有空白和标点的相邻短语组成一组；一组短语之前是文本开头或标点、
+/// 之后是文本结尾或标点时才算命令，否则整组按原文输入
+fn clause_commands<'a>(
+    text: &str,
+    matches: Vec<(Range<usize>, &'a VoiceAction)>,
+) -> Vec<(Range<usize>, &'a VoiceAction)> {
+    let mut commands = Vec::new();
+    let mut first = 0;
+
+    while first < matches.len() {
+        let mut last = first;
+        while let Some((next, _)) = matches.get(last + 1)
+            && is_clause_gap(&text[matches[last].0.end..next.start])
+        {
+            last += 1;
+        }
+
+        if at_clause_start(&text[..matches[first].0.start])
+            && at_clause_end(&text[matches[last].0.end..])
+        {
+            commands.extend_from_slice(&matches[first..=last]);
+        }
+        first = last + 1;
+    }
+
+    commands
+}
+
+/// 短语之间是否只有空白和标点
+fn is_clause_gap(gap: &str) -> bool {
+    gap.chars()
+        .all(|c| c.is_whitespace() || is_command_punctuation(c))
+}
+
+/// 短语前面是否是文本开头或标点
+fn at_clause_start(before: &str) -> bool {
+    before
+        .trim_end()
+        .chars()
+        .next_back()
+        .is_none_or(is_command_punctuation)
+}
+
+/// 短语后面是否是文本结尾或标点
+fn at_clause_end(after: &str) -> bool {
+    after
+        .trim_start()
+        .chars()
+        .next()
+        .is_none_or(is_command_punctuation)
+}
+
+/// 从 `from` 开始跳过满足条件的字符，返回新的位置
+fn skip_chars(text: &str, from: usize, skip: impl Fn(char) -> bool) -> usize {
+    text[from..]
+        .char_indices()
+        .find(|&(_, c)| !skip(c))
+        .map_or(text.len(), |(i, _)| from + i)
+}
+
+/// 将编辑操作渲染为纯文本
+///
+/// 用于仅复制到剪贴板、事件和历史记录：Enter、Tab 转为换行符和制表符，
+/// Backspace 删除前一个字符，其余无法用文本表示的操作被忽略
+pub fn render_plain(actions: &[EditAction]) -> String {
+    let mut text = String::new();
+    for action in actions {
+        match action {
+            EditAction::Text(segment) => text.push_str(segment),
+            EditAction::Enter => text.push('\n'),
+            EditAction::Tab => text.push('\t'),
+            EditAction::Backspace => {
+                text.pop();
+            }
+            EditAction::SelectAll | EditAction::DeletePrevious | EditAction::Undo => {}
+        }
+    }
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn interpret(text: &str) -> Vec<EditAction> {
+        VoiceCommandInterpreter::new(&VoiceCommand::defaults()).interpret(text)
+    }
+
+    fn text(s: &str) -> EditAction {
+        EditAction::Text(s.to_string())
+    }
+
+    #[test]
+    fn test_plain_text_is_unchanged() {
+        assert_eq!(interpret("hello world"), vec![text("hello world")]);
+        assert_eq!(interpret(""), vec![]);
+    }
+
+    #[test]
+    fn test_new_line_en() {
+        assert_eq!(
+            interpret("Dear team, new line, thanks for the update"),
+            vec![
+                text("Dear team,"),
+                EditAction::Enter,
+                text("thanks for the update")
+            ]
+        );
+        assert_eq!(
+            interpret("Hello. New line. World."),
+            vec![text("Hello."), EditAction::Enter, text("World.")]
+        );
+        assert_eq!(interpret("New line"), vec![EditAction::Enter]);
+    }
+
+    #[test]
+    fn test_new_line_zh() {
+        assert_eq!(
+            interpret("你好，换行，世界"),
+            vec![text("你好，"), EditAction::Enter, text("世界")]
+        );
+        assert_eq!(
+            interpret("第一行。换行。第二行"),
+            vec![text("第一行。"), EditAction::Enter, text("第二行")]
+        );
+    }
+
+    #[test]
+    fn test_punctuation_en() {
+        assert_eq!(
+            interpret("See you soon. Period."),
+            vec![text("See you soon.")]
+        );
+        assert_eq!(
+            interpret("Wait, comma, what? Question mark."),
+            vec![text("Wait, what?")]
+        );
+        // Scribe 已经补上的标点不会重复
+        assert_eq!(interpret("Done, period."), vec![text("Done.")]);
+    }
+
+    #[test]
+    fn test_punctuation_zh() {
+        assert_eq!(
+            interpret("今天天气很好，逗号，我们出去吧。句号。"),
+            vec![text("今天天气很好，我们出去吧。")]
+        );
+    }
+
+    #[test]
+    fn test_phrases_need_whole_clause() {
+        for sentence in [
+            "the periodic table has a semicolonic shape",
+            "The trial period ends tomorrow.",
+            "Please select all the files, then press tab twice.",
+            "Press the backspace key, new line breaks are fine.",
+            "I want to delete that file.",
+            "see you soon period",
+            "我们需要撤销这个申请",
+            "请全选以后再复制。",
+            "按退格键就行",
+            "你好换行世界",
+        ] {
+            assert_eq!(interpret(sentence), vec![text(sentence)], "{}", sentence);
+        }
+    }
+
+    #[test]
+    fn test_phrases_are_case_and_space_insensitive() {
+        assert_eq!(
+            interpret("one. NEW   LINE. two"),
+            vec![text("one."), EditAction::Enter, text("two")]
+        );
+    }
+
+    #[test]
+    fn test_consecutive_commands_form_one_clause() {
+        assert_eq!(
+            interpret("select all backspace"),
+            vec![EditAction::SelectAll, EditAction::Backspace]
+        );
+        assert_eq!(
+            interpret("Name. Press tab. 张三。撤销"),
+            vec![
+                text("Name."),
+                EditAction::Tab,
+                text("张三。"),
+                EditAction::Undo
+            ]
+        );
+        // 一组短语中只要有一头接着普通文字，整组都按原文输入
+        assert_eq!(
+            interpret("New line, new line and more"),
+            vec![text("New line, new line and more")]
+        );
+    }
+
+    #[test]
+    fn test_delete_that_drops_pending_text() {
+        assert_eq!(
+            interpret("First line. New line. Oops wrong. Delete that. Right."),
+            vec![text("First line."), EditAction::Enter, text("Right.")]
+        );
+        assert_eq!(interpret("这句不要了。删掉这句。"), vec![]);
+    }
+
+    #[test]
+    fn test_delete_that_removes_previous_action() {
+        assert_eq!(
+            interpret("Hello. New line. Delete that. World."),
+            vec![text("Hello. World.")]
+        );
+        assert_eq!(
+            interpret("First. New line. Second. Press tab. Delete that. Third."),
+            vec![text("First."), EditAction::Enter, text("Second. Third.")]
+        );
+        assert_eq!(
+            interpret("你好。换行。删掉这句。世界。"),
+            vec![text("你好。世界。")]
+        );
+        assert_eq!(
+            interpret("Hello. New line. Delete that."),
+            vec![text("Hello.")]
+        );
+    }
+
+    #[test]
+    fn test_delete_that_at_start_deletes_previous_segment() {
+        assert_eq!(
+            interpret("Delete that. Scratch that."),
+            vec![EditAction::DeletePrevious, EditAction::DeletePrevious]
+        );
+        assert_eq!(
+            interpret("删除上一句，重新说"),
+            vec![EditAction::DeletePrevious, text("重新说")]
+        );
+    }
+
+    #[test]
+    fn test_custom_phrase_table() {
+        let interpreter = VoiceCommandInterpreter::new(&[
+            VoiceCommand::new("smiley", VoiceAction::Insert(":)".to_string())),
+            VoiceCommand::new("  ", VoiceAction::Enter),
+        ]);
+
+        assert_eq!(
+            interpreter.interpret("Great. Smiley."),
+            vec![text("Great:)")]
+        );
+        assert_eq!(interpreter.interpret("new line"), vec![text("new line")]);
+    }
+
+    #[test]
+    fn test_empty_table() {
+        let interpreter = VoiceCommandInterpreter::new(&[]);
+        assert_eq!(interpreter.interpret("new line"), vec![text("new line")]);
+    }
+
+    #[test]
+    fn test_from_config() {
+        let mut config = AppConfig::default();
+        assert!(VoiceCommandInterpreter::from_config(&config).is_none());
+
+        config.voice_commands.enabled = true;
+        let interpreter = VoiceCommandInterpreter::from_config(&config).unwrap();
+        assert_eq!(
+            interpreter.interpret("a, new line, b"),
+            vec![text("a,"), EditAction::Enter, text("b")]
+        );
+    }
+
+    #[test]
+    fn test_render_plain() {
+        let actions = vec![
+            text("ab"),
+            EditAction::Enter,
+            text("cd"),
+            EditAction::Backspace,
+            EditAction::Tab,
+            EditAction::Undo,
+        ];
+
+        assert_eq!(render_plain(&actions), "ab\nc\t");
+    }
+
+    #[test]
+    fn test_action_serialization() {
+        let json = serde_json::to_string(&VoiceCommand::new(
+            "period",
+            VoiceAction::Insert(".".to_string()),
+        ))
+        .unwrap();
+        assert_eq!(json, r#"{"phrase":"period","action":{"insert":"."}}"#);
+
+        let command: VoiceCommand =
+            serde_json::from_str(r#"{"phrase": "new line", "action": "enter"}"#).unwrap();
+        assert_eq!(command, VoiceCommand::new("new line", VoiceAction::Enter));
+    }
+}
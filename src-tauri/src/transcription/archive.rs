@@ -0,0 +1,514 @@
+/// Session audio archive
+///
+/// Records each session's 16kHz audio as a WAV file named by the Scribe
+/// session ID, next to a JSON sidecar with the partial and committed
+/// transcripts and their timing, and prunes old recordings.
+
+use crate::audio::WavWriter;
+use crate::state::ArchiveConfig;
+use crate::transcription::TranscriptEvent;
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufWriter};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// How many recordings to keep in the archive directory
+///
+/// A recording is removed together with its sidecar as soon as any limit is
+/// exceeded, oldest first. `None` means no limit.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct RetentionPolicy {
+    /// Maximum number of recordings
+    pub max_recordings: Option<usize>,
+
+    /// Maximum age of a recording
+    pub max_age: Option<Duration>,
+
+    /// Maximum total size of recordings and sidecars, in bytes
+    pub max_total_bytes: Option<u64>,
+}
+
+impl RetentionPolicy {
+    /// Keep at most `count` recordings
+    pub fn with_max_recordings(mut self, count: usize) -> Self {
+        self.max_recordings = Some(count);
+        self
+    }
+
+    /// Remove recordings older than `age`
+    pub fn with_max_age(mut self, age: Duration) -> Self {
+        self.max_age = Some(age);
+        self
+    }
+
+    /// Keep the archive below `bytes` in total
+    pub fn with_max_total_bytes(mut self, bytes: u64) -> Self {
+        self.max_total_bytes = Some(bytes);
+        self
+    }
+}
+
+impl From<&ArchiveConfig> for RetentionPolicy {
+    /// Zero in the configuration means no limit
+    fn from(config: &ArchiveConfig) -> Self {
+        Self {
+            max_recordings: (config.max_recordings > 0).then_some(config.max_recordings),
+            max_age: (config.max_age_days > 0)
+                .then(|| Duration::from_secs(config.max_age_days * 24 * 60 * 60)),
+            max_total_bytes: (config.max_total_mb > 0).then(|| config.max_total_mb * 1024 * 1024),
+        }
+    }
+}
+
+/// Where and how long session recordings are kept
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArchiveOptions {
+    /// Directory holding the recordings and sidecars
+    pub dir: PathBuf,
+
+    /// Retention limits applied after each session
+    pub retention: RetentionPolicy,
+}
+
+impl ArchiveOptions {
+    /// Archive into `dir` without retention limits
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            retention: RetentionPolicy::default(),
+        }
+    }
+
+    /// Set the retention limits
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+}
+
+/// Transcript text received at `offset_ms` after the session started
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimedTranscript {
+    /// Milliseconds since audio capture started
+    pub offset_ms: u64,
+
+    /// Transcript text
+    pub text: String,
+}
+
+/// JSON sidecar stored next to each recording
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveSidecar {
+    /// Scribe session ID (None if the server never started a session)
+    pub session_id: Option<String>,
+
+    /// File name of the WAV recording
+    pub audio_file: String,
+
+    /// Sample rate of the recording
+    pub sample_rate: u32,
+
+    /// Model used for the session
+    pub model_id: String,
+
+    /// Language code requested for the session
+    pub language_code: Option<String>,
+
+    /// Start of audio capture, in milliseconds since the Unix epoch
+    pub started_at_ms: u64,
+
+    /// Wall-clock length of the session, in milliseconds
+    pub elapsed_ms: u64,
+
+    /// Partial transcripts in the order they were received
+    pub partials: Vec<TimedTranscript>,
+
+    /// Committed transcripts in the order they were received
+    pub committed: Vec<TimedTranscript>,
+
+    /// Final text returned by the session
+    pub text: Option<String>,
+}
+
+/// Archive of one running session
+///
+/// Audio is recorded under a temporary name because the session ID is only
+/// known once the server has answered; [`finish`](Self::finish) renames it.
+pub(crate) struct SessionArchive {
+    options: ArchiveOptions,
+    pending_path: PathBuf,
+    started: Instant,
+    sidecar: ArchiveSidecar,
+}
+
+impl SessionArchive {
+    /// Prepare the archive directory and the WAV writer for a new session
+    pub fn begin(
+        options: &ArchiveOptions,
+        model_id: &str,
+        language_code: Option<&str>,
+        sample_rate: u32,
+    ) -> io::Result<(Self, WavWriter)> {
+        fs::create_dir_all(&options.dir)?;
+
+        let started_at_ms = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let pending_path = options.dir.join(format!("pending-{}.wav", started_at_ms));
+        let writer = WavWriter::new(BufWriter::new(File::create(&pending_path)?), sample_rate)
+            .map_err(io::Error::other)?;
+
+        debug!("Archiving session audio to {}", pending_path.display());
+
+        let archive = Self {
+            options: options.clone(),
+            pending_path,
+            started: Instant::now(),
+            sidecar: ArchiveSidecar {
+                session_id: None,
+                audio_file: String::new(),
+                sample_rate,
+                model_id: model_id.to_string(),
+                language_code: language_code.map(str::to_string),
+                started_at_ms,
+                elapsed_ms: 0,
+                partials: Vec::new(),
+                committed: Vec::new(),
+                text: None,
+            },
+        };
+        Ok((archive, writer))
+    }
+
+    /// Note a transcription event in the sidecar
+    pub fn record(&mut self, event: &TranscriptEvent) {
+        let offset_ms = self.started.elapsed().as_millis() as u64;
+        let timed = |text: &str| TimedTranscript {
+            offset_ms,
+            text: text.to_string(),
+        };
+
+        match event {
+            TranscriptEvent::SessionStarted { session_id } => {
+                self.sidecar
+                    .session_id
+                    .get_or_insert_with(|| session_id.clone());
+            }
+            TranscriptEvent::Partial { text } => self.sidecar.partials.push(timed(text)),
+            TranscriptEvent::Committed { text } => self.sidecar.committed.push(timed(text)),
+            _ => {}
+        }
+    }
+
+    /// Name the recording after the session, write the sidecar and prune
+    ///
+    /// Must be called after the audio pipeline has stopped, so that the WAV
+    /// file is complete. Returns the path of the recording.
+    pub fn finish(mut self, text: Option<String>) -> io::Result<PathBuf> {
+        let stem = match &self.sidecar.session_id {
+            Some(session_id) => sanitize_file_stem(session_id),
+            None => format!("local-{}", self.sidecar.started_at_ms),
+        };
+        let audio_path = self.options.dir.join(format!("{}.wav", stem));
+        fs::rename(&self.pending_path, &audio_path)?;
+
+        self.sidecar.audio_file = format!("{}.wav", stem);
+        self.sidecar.elapsed_ms = self.started.elapsed().as_millis() as u64;
+        self.sidecar.text = text;
+
+        let json = serde_json::to_string_pretty(&self.sidecar).map_err(io::Error::other)?;
+        fs::write(audio_path.with_extension("json"), json)?;
+
+        info!("Session archived: {}", audio_path.display());
+
+        match prune_recordings(&self.options.dir, &self.options.retention) {
+            Ok(0) => {}
+            Ok(removed) => info!("Pruned {} old recording(s)", removed),
+            Err(e) => warn!("Failed to prune recordings: {}", e),
+        }
+
+        Ok(audio_path)
+    }
+
+    /// Remove the recording of a session that failed to start
+    pub fn discard(self) {
+        if let Err(e) = fs::remove_file(&self.pending_path) {
+            warn!("Failed to remove {}: {}", self.pending_path.display(), e);
+        }
+    }
+}
+
+/// Delete the oldest recordings in `dir` until `policy` is met
+///
+/// Only recordings RaFlow wrote are considered: a `.wav` file counts when
+/// its `.json` sidecar names it as the audio file, so other audio in a
+/// shared directory is never touched. The sidecar is removed with the
+/// recording. Returns the number of recordings removed.
+///
+/// # Errors
+/// Returns error if the directory cannot be read. Files that cannot be
+/// removed are skipped.
+pub fn prune_recordings(dir: &Path, policy: &RetentionPolicy) -> io::Result<usize> {
+    let mut recordings = Vec::new();
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "wav") {
+            continue;
+        }
+
+        let Some(sidecar_len) = sidecar_len(&path) else {
+            continue;
+        };
+        let metadata = fs::metadata(&path)?;
+        let modified = metadata.modified().unwrap_or(UNIX_EPOCH);
+        recordings.push((modified, metadata.len() + sidecar_len, path));
+    }
+
+    // Newest first
+    recordings.sort_by_key(|recording| std::cmp::Reverse(recording.0));
+
+    let now = SystemTime::now();
+    let mut kept = 0;
+    let mut total_bytes = 0;
+    let mut removed = 0;
+
+    for (modified, size, path) in recordings {
+        let too_many = policy.max_recordings.is_some_and(|max| kept >= max);
+        let too_old = policy
+            .max_age
+            .is_some_and(|max| now.duration_since(modified).unwrap_or_default() > max);
+        let too_big = policy
+            .max_total_bytes
+            .is_some_and(|max| total_bytes + size > max);
+
+        if too_many || too_old || too_big {
+            debug!("Removing old recording {}", path.display());
+            match fs::remove_file(&path) {
+                Ok(()) => {
+                    let _ = fs::remove_file(path.with_extension("json"));
+                    removed += 1;
+                }
+                Err(e) => warn!("Failed to remove {}: {}", path.display(), e),
+            }
+        } else {
+            kept += 1;
+            total_bytes += size;
+        }
+    }
+
+    Ok(removed)
+}
+
+/// Size of the sidecar written for the recording at `path`
+///
+/// None if there is no sidecar, or it does not belong to this recording.
+fn sidecar_len(path: &Path) -> Option<u64> {
+    let json = fs::read_to_string(path.with_extension("json")).ok()?;
+    let sidecar: ArchiveSidecar = serde_json::from_str(&json).ok()?;
+    let file_name = path.file_name()?.to_str()?;
+    (sidecar.audio_file == file_name).then_some(json.len() as u64)
+}
+
+/// Keep a session ID safe to use as a file name
+fn sanitize_file_stem(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Fresh directory under the system temp dir
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("raflow-archive-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(dir: &Path, name: &str, len: usize) {
+        fs::write(dir.join(name), vec![0u8; len]).unwrap();
+        // Distinct modification times for ordering
+        std::thread::sleep(Duration::from_millis(20));
+    }
+
+    /// Write a recording with its sidecar, as `SessionArchive::finish` does
+    fn record(dir: &Path, stem: &str, len: usize) {
+        let sidecar = ArchiveSidecar {
+            session_id: Some(stem.to_string()),
+            audio_file: format!("{}.wav", stem),
+            sample_rate: 16000,
+            model_id: "m".to_string(),
+            language_code: None,
+            started_at_ms: 0,
+            elapsed_ms: 0,
+            partials: Vec::new(),
+            committed: Vec::new(),
+            text: None,
+        };
+        fs::write(
+            dir.join(format!("{}.json", stem)),
+            serde_json::to_string(&sidecar).unwrap(),
+        )
+        .unwrap();
+        touch(dir, &format!("{}.wav", stem), len);
+    }
+
+    #[test]
+    fn test_retention_from_config() {
+        let config = ArchiveConfig {
+            enabled: true,
+            directory: None,
+            max_recordings: 10,
+            max_age_days: 0,
+            max_total_mb: 1,
+        };
+
+        let policy = RetentionPolicy::from(&config);
+        assert_eq!(policy.max_recordings, Some(10));
+        assert_eq!(policy.max_age, None);
+        assert_eq!(policy.max_total_bytes, Some(1024 * 1024));
+    }
+
+    #[test]
+    fn test_sanitize_file_stem() {
+        assert_eq!(sanitize_file_stem("sess_abc-123"), "sess_abc-123");
+        assert_eq!(sanitize_file_stem("../a b"), "___a_b");
+    }
+
+    #[test]
+    fn test_session_archive_finish() {
+        let dir = temp_dir("finish");
+        let options = ArchiveOptions::new(&dir);
+
+        let (mut archive, mut writer) =
+            SessionArchive::begin(&options, "scribe_v2_realtime", Some("en"), 16000).unwrap();
+        writer.write_samples(&[0; 1600]).unwrap();
+        writer.finalize().unwrap();
+
+        archive.record(&TranscriptEvent::SessionStarted {
+            session_id: "sess-1".to_string(),
+        });
+        archive.record(&TranscriptEvent::Partial {
+            text: "hel".to_string(),
+        });
+        archive.record(&TranscriptEvent::Committed {
+            text: "hello".to_string(),
+        });
+        // Later session IDs (after a reconnect) do not rename the recording
+        archive.record(&TranscriptEvent::SessionStarted {
+            session_id: "sess-2".to_string(),
+        });
+
+        let path = archive.finish(Some("hello".to_string())).unwrap();
+        assert_eq!(path, dir.join("sess-1.wav"));
+        assert_eq!(fs::metadata(&path).unwrap().len(), 44 + 3200);
+
+        let json = fs::read_to_string(dir.join("sess-1.json")).unwrap();
+        let sidecar: ArchiveSidecar = serde_json::from_str(&json).unwrap();
+        assert_eq!(sidecar.session_id, Some("sess-1".to_string()));
+        assert_eq!(sidecar.audio_file, "sess-1.wav");
+        assert_eq!(sidecar.language_code, Some("en".to_string()));
+        assert_eq!(sidecar.partials.len(), 1);
+        assert_eq!(sidecar.committed[0].text, "hello");
+        assert_eq!(sidecar.text, Some("hello".to_string()));
+
+        // No pending file is left behind
+        let files: Vec<_> = fs::read_dir(&dir).unwrap().collect();
+        assert_eq!(files.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_session_archive_discard() {
+        let dir = temp_dir("discard");
+        let (archive, _writer) =
+            SessionArchive::begin(&ArchiveOptions::new(&dir), "m", None, 16000).unwrap();
+
+        archive.discard();
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_by_count() {
+        let dir = temp_dir("count");
+        for name in ["a", "b", "c"] {
+            record(&dir, name, 100);
+        }
+
+        let policy = RetentionPolicy::default().with_max_recordings(2);
+        assert_eq!(prune_recordings(&dir, &policy).unwrap(), 1);
+
+        // The oldest recording goes, with its sidecar
+        assert!(!dir.join("a.wav").exists());
+        assert!(!dir.join("a.json").exists());
+        assert!(dir.join("b.wav").exists());
+        assert!(dir.join("c.json").exists());
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_by_size_and_age() {
+        let dir = temp_dir("size");
+        record(&dir, "old", 600);
+        record(&dir, "new", 600);
+
+        let policy = RetentionPolicy::default().with_max_total_bytes(1000);
+        assert_eq!(prune_recordings(&dir, &policy).unwrap(), 1);
+        assert!(dir.join("new.wav").exists());
+
+        // Nothing is younger than zero seconds
+        let policy = RetentionPolicy::default().with_max_age(Duration::ZERO);
+        assert_eq!(prune_recordings(&dir, &policy).unwrap(), 1);
+        assert!(!dir.join("new.wav").exists());
+
+        // Without limits nothing is removed
+        record(&dir, "kept", 10);
+        assert_eq!(
+            prune_recordings(&dir, &RetentionPolicy::default()).unwrap(),
+            0
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prune_keeps_foreign_files() {
+        let dir = temp_dir("foreign");
+        touch(&dir, "song.wav", 100);
+        // A sidecar that is not RaFlow's, or names another file, claims nothing
+        touch(&dir, "memo.json", 10);
+        touch(&dir, "memo.wav", 100);
+        record(&dir, "take", 100);
+        fs::rename(dir.join("take.json"), dir.join("clip.json")).unwrap();
+        touch(&dir, "clip.wav", 100);
+        record(&dir, "sess", 100);
+
+        let policy = RetentionPolicy::default().with_max_recordings(0);
+        assert_eq!(prune_recordings(&dir, &policy).unwrap(), 1);
+
+        assert!(!dir.join("sess.wav").exists());
+        assert!(!dir.join("sess.json").exists());
+        for name in ["song.wav", "memo.wav", "memo.json", "take.wav", "clip.wav"] {
+            assert!(dir.join(name).exists(), "{} was removed", name);
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -3,14 +3,28 @@
 /// This module integrates the audio pipeline and network communication
 /// to provide a complete speech-to-text transcription service.
 
-use crate::audio::AudioPipeline;
-use crate::network::tasks::{receiver_task, sender_task};
-use crate::network::{ConnectionConfig, NetworkError, ScribeConnection, ServerMessage};
-use std::sync::Arc;
+use crate::audio::{
+    AudioLevel, AudioPipeline, AudioSource, ChannelMode, DeviceEvent, DeviceSelector,
+    FailoverPolicy, GainConfig, SupervisedCapture, VadConfig, VadEvent,
+};
+use crate::network::{
+    append_transcript, reconnecting_task, ConnectionConfig, ConnectionEvent, NetworkError,
+    ReconnectPolicy, ScribeConnection, ServerMessage, DEFAULT_BASE_URL, DEFAULT_COMMIT_TIMEOUT_MS,
+};
+use crate::state::AppConfig;
+use archive::SessionArchive;
+use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tracing::{debug, error, info, warn};
 
+/// Session audio archive (WAV recordings with transcript sidecars)
+pub mod archive;
+
+pub use archive::{
+    prune_recordings, ArchiveOptions, ArchiveSidecar, RetentionPolicy, TimedTranscript,
+};
+
 /// Events emitted during transcription
 ///
 /// These events represent the different types of messages received
@@ -29,16 +43,349 @@ pub enum TranscriptEvent {
     /// Error occurred during transcription
     Error { message: String },
 
+    /// Connection dropped; reconnection attempt `attempt` is pending
+    Reconnecting { attempt: u32 },
+
+    /// Connection restored and buffered audio replayed
+    Reconnected,
+
+    /// Voice activity detection heard speech start
+    SpeechStarted,
+
+    /// Voice activity detection heard speech end (after the hangover)
+    SpeechEnded,
+
+    /// Continuous silence reached the configured auto-stop limit
+    SilenceTimeout { silence_ms: u64 },
+
+    /// Input level of the microphone (about 30 times per second)
+    AudioLevel { level: AudioLevel },
+
+    /// The capture device was lost and no replacement could be opened;
+    /// no more audio is recorded
+    DeviceLost { message: String },
+
+    /// The capture device was lost and recording continues on `device`
+    DeviceSwitched { device: String, message: String },
+
     /// Connection closed
     Closed,
 }
 
+/// Options used to start a transcription session
+///
+/// Collects everything a session needs from the user's settings: the API
+/// credentials and Scribe parameters from `ApiConfig`, the capture device,
+/// channel and failover, noise suppression, gain and voice activity detection
+/// from `AudioConfig`, and the silence auto-stop from `BehaviorConfig`.
+///
+/// # Example
+/// ```
+/// use raflow_lib::transcription::TranscriptionOptions;
+///
+/// let options = TranscriptionOptions::new("your-api-key")
+///     .with_language("zh")
+///     .with_timestamps()
+///     .with_vad_strategy("auto");
+///
+/// assert_eq!(options.language_code, Some("zh".to_string()));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct TranscriptionOptions {
+    /// ElevenLabs API key
+    pub api_key: String,
+
+    /// WebSocket endpoint URL (`wss://` or `ws://`)
+    pub base_url: String,
+
+    /// Model ID (default: "scribe_v2_realtime")
+    pub model_id: String,
+
+    /// Language code (None for automatic detection)
+    pub language_code: Option<String>,
+
+    /// Whether to request word-level timestamps
+    pub include_timestamps: bool,
+
+    /// Voice activity detection commit strategy
+    pub vad_commit_strategy: Option<String>,
+
+    /// Audio input device ID (None for the default device)
+    pub input_device_id: Option<String>,
+
+    /// Saved name of the input device, used to find it again if its ID changes
+    pub input_device_name: Option<String>,
+
+    /// Devices (IDs or names) to use, in order, if the input device is missing
+    pub preferred_device_ids: Vec<String>,
+
+    /// Devices to switch to if the input device is lost mid-session
+    pub failover: FailoverPolicy,
+
+    /// Reconnection policy for dropped connections
+    pub reconnect: ReconnectPolicy,
+
+    /// How long `stop` waits for the final committed transcript, in milliseconds
+    pub commit_timeout_ms: u64,
+
+    /// How multi-channel input devices are reduced to mono
+    pub channel_mode: ChannelMode,
+
+    /// Whether background noise is suppressed before resampling
+    pub noise_suppression: bool,
+
+    /// Gain and automatic gain control applied to captured audio
+    pub gain: GainConfig,
+
+    /// Client-side voice activity detection applied before streaming
+    pub vad: VadConfig,
+
+    /// Keep the microphone open between sessions and prepend this much
+    /// audio (in milliseconds) from before the session started
+    ///
+    /// Applied by the session controller, which owns the
+    /// [`WarmMicrophone`](crate::audio::WarmMicrophone). None opens the
+    /// microphone for each session only.
+    pub warm_pre_roll_ms: Option<u64>,
+
+    /// Record the session's audio and transcripts (None disables archiving)
+    ///
+    /// Not set by [`from_config`](Self::from_config): the archive directory
+    /// defaults to the app data dir, which the session layer resolves.
+    pub archive: Option<ArchiveOptions>,
+}
+
+impl TranscriptionOptions {
+    /// Create options with the given API key and default parameters
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            base_url: DEFAULT_BASE_URL.to_string(),
+            model_id: "scribe_v2_realtime".to_string(),
+            language_code: None,
+            include_timestamps: false,
+            vad_commit_strategy: None,
+            input_device_id: None,
+            input_device_name: None,
+            preferred_device_ids: Vec::new(),
+            failover: FailoverPolicy::default(),
+            reconnect: ReconnectPolicy::default(),
+            commit_timeout_ms: DEFAULT_COMMIT_TIMEOUT_MS,
+            channel_mode: ChannelMode::Downmix,
+            noise_suppression: false,
+            gain: GainConfig::default(),
+            vad: VadConfig::disabled(),
+            warm_pre_roll_ms: None,
+            archive: None,
+        }
+    }
+
+    /// Build options from the application configuration
+    ///
+    /// Empty strings (as saved by the settings UI) are treated as unset.
+    pub fn from_config(config: &AppConfig) -> Self {
+        let non_empty = |value: &Option<String>| value.clone().filter(|v| !v.is_empty());
+
+        let model_id = if config.api.model_id.is_empty() {
+            "scribe_v2_realtime".to_string()
+        } else {
+            config.api.model_id.clone()
+        };
+
+        let base_url = if config.api.base_url.trim().is_empty() {
+            DEFAULT_BASE_URL.to_string()
+        } else {
+            config.api.base_url.trim().to_string()
+        };
+
+        let mut gain = GainConfig::new(config.audio.gain);
+        if config.audio.agc_enabled {
+            gain = gain.with_agc(config.audio.agc_target_level);
+        }
+
+        let failover = if config.audio.device_failover {
+            FailoverPolicy::to_default().with_devices(
+                config
+                    .audio
+                    .preferred_device_ids
+                    .iter()
+                    .filter(|id| !id.is_empty())
+                    .cloned()
+                    .collect(),
+            )
+        } else {
+            FailoverPolicy::default()
+        };
+
+        let mut vad = VadConfig::new(config.audio.silence_threshold)
+            .with_hangover_ms(config.audio.vad_hangover_ms)
+            .with_pre_roll_ms(config.audio.vad_pre_roll_ms);
+        vad.enabled = config.audio.vad_enabled;
+        if config.behavior.auto_stop_silence_secs > 0 {
+            vad = vad.with_auto_stop_ms(config.behavior.auto_stop_silence_secs * 1000);
+        }
+
+        Self {
+            api_key: config.api.api_key.clone(),
+            base_url,
+            model_id,
+            language_code: non_empty(&config.api.language_code),
+            include_timestamps: config.api.include_timestamps,
+            vad_commit_strategy: non_empty(&config.api.vad_commit_strategy),
+            input_device_id: non_empty(&config.audio.input_device_id),
+            input_device_name: non_empty(&config.audio.input_device_name),
+            preferred_device_ids: config.audio.preferred_device_ids.clone(),
+            failover,
+            reconnect: ReconnectPolicy::default(),
+            commit_timeout_ms: config.api.commit_timeout_ms,
+            channel_mode: ChannelMode::from_channel(config.audio.input_channel),
+            noise_suppression: config.audio.noise_suppression,
+            gain,
+            vad,
+            warm_pre_roll_ms: config
+                .audio
+                .warm_microphone
+                .then_some(config.audio.warm_pre_roll_ms),
+            archive: None,
+        }
+    }
+
+    /// Set the API key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = api_key.into();
+        self
+    }
+
+    /// Set the WebSocket endpoint URL
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Set the model ID
+    pub fn with_model(mut self, model_id: impl Into<String>) -> Self {
+        self.model_id = model_id.into();
+        self
+    }
+
+    /// Set the language code
+    pub fn with_language(mut self, language_code: impl Into<String>) -> Self {
+        self.language_code = Some(language_code.into());
+        self
+    }
+
+    /// Request word-level timestamps
+    pub fn with_timestamps(mut self) -> Self {
+        self.include_timestamps = true;
+        self
+    }
+
+    /// Set the VAD commit strategy
+    pub fn with_vad_strategy(mut self, strategy: impl Into<String>) -> Self {
+        self.vad_commit_strategy = Some(strategy.into());
+        self
+    }
+
+    /// Set the audio input device
+    pub fn with_input_device(mut self, device_id: impl Into<String>) -> Self {
+        self.input_device_id = Some(device_id.into());
+        self
+    }
+
+    /// Set the devices (IDs or names) to use if the input device is missing
+    pub fn with_preferred_devices(mut self, devices: Vec<String>) -> Self {
+        self.preferred_device_ids = devices;
+        self
+    }
+
+    /// Set the devices to switch to if the input device is lost
+    pub fn with_failover(mut self, policy: FailoverPolicy) -> Self {
+        self.failover = policy;
+        self
+    }
+
+    /// Set the reconnection policy
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect = policy;
+        self
+    }
+
+    /// Set how long `stop` waits for the final committed transcript
+    pub fn with_commit_timeout(mut self, commit_timeout_ms: u64) -> Self {
+        self.commit_timeout_ms = commit_timeout_ms;
+        self
+    }
+
+    /// Set how multi-channel input devices are reduced to mono
+    pub fn with_channel_mode(mut self, mode: ChannelMode) -> Self {
+        self.channel_mode = mode;
+        self
+    }
+
+    /// Suppress background noise before resampling
+    pub fn with_noise_suppression(mut self) -> Self {
+        self.noise_suppression = true;
+        self
+    }
+
+    /// Set the gain settings
+    pub fn with_gain(mut self, gain: GainConfig) -> Self {
+        self.gain = gain;
+        self
+    }
+
+    /// Set the voice activity detection settings
+    pub fn with_vad(mut self, vad: VadConfig) -> Self {
+        self.vad = vad;
+        self
+    }
+
+    /// Keep the microphone open between sessions with the given pre-roll
+    pub fn with_warm_microphone(mut self, pre_roll_ms: u64) -> Self {
+        self.warm_pre_roll_ms = Some(pre_roll_ms);
+        self
+    }
+
+    /// Archive the session's audio and transcripts
+    pub fn with_archive(mut self, archive: ArchiveOptions) -> Self {
+        self.archive = Some(archive);
+        self
+    }
+
+    /// Build the selector that matches the saved input device against the
+    /// devices currently available
+    ///
+    /// Sessions open `input_device_id` as is; callers that want rename-tolerant
+    /// matching resolve the selector first and pass the chosen device's ID.
+    pub fn device_selector(&self) -> DeviceSelector {
+        DeviceSelector::new(self.input_device_id.as_deref())
+            .with_name(self.input_device_name.as_deref())
+            .with_preferred(self.preferred_device_ids.clone())
+    }
+
+    /// Build the WebSocket connection configuration for these options
+    ///
+    /// # Arguments
+    /// * `sample_rate` - Sample rate of the audio that will be streamed
+    pub fn connection_config(&self, sample_rate: u32) -> ConnectionConfig {
+        let mut config = ConnectionConfig::new(sample_rate)
+            .with_base_url(self.base_url.clone())
+            .with_model(self.model_id.clone())
+            .with_commit_timeout(self.commit_timeout_ms);
+        config.language_code = self.language_code.clone();
+        config.include_timestamps = self.include_timestamps;
+        config.vad_commit_strategy = self.vad_commit_strategy.clone();
+        config
+    }
+}
+
 /// Complete transcription session
 ///
 /// Manages the entire lifecycle of a speech-to-text session, including:
-/// - Audio capture from microphone
+/// - Audio capture from the microphone or another [`AudioSource`]
 /// - Audio processing (resampling, encoding)
 /// - WebSocket communication with transcription service
+/// - Reconnection with audio replay when the connection drops
 /// - Event callbacks for transcription results
 ///
 /// # Example
@@ -65,22 +412,30 @@ pub enum TranscriptEvent {
 ///     // Let it run for a while
 ///     tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
 ///
-///     session.stop().await.unwrap();
+///     if let Some(text) = session.stop().await.unwrap() {
+///         println!("Full text: {}", text);
+///     }
 /// }
 /// ```
 pub struct TranscriptionSession {
     /// Audio pipeline handle
     audio_pipeline: AudioPipeline,
 
-    /// Sender task handle
-    sender_handle: Option<JoinHandle<Result<(), NetworkError>>>,
-
-    /// Receiver task handle
-    receiver_handle: Option<JoinHandle<Result<(), NetworkError>>>,
+    /// Connection task handle (sends audio, receives messages, reconnects)
+    connection_handle: Option<JoinHandle<Result<(), NetworkError>>>,
 
     /// Event handler task handle
     event_handler_handle: Option<JoinHandle<()>>,
 
+    /// VAD, input level and device event forwarding task handle
+    audio_event_handle: Option<JoinHandle<()>>,
+
+    /// Committed transcript segments received so far
+    committed_segments: Arc<Mutex<Vec<String>>>,
+
+    /// Archive of this session, finished on stop
+    archive: Arc<Mutex<Option<SessionArchive>>>,
+
     /// Whether the session is running
     is_running: bool,
 }
@@ -89,9 +444,10 @@ impl TranscriptionSession {
     /// Start a new transcription session
     ///
     /// This method:
-    /// 1. Establishes WebSocket connection to the transcription service
-    /// 2. Starts audio capture and processing
-    /// 3. Spawns tasks for concurrent send/receive operations
+    /// 1. Starts audio capture and processing
+    /// 2. Establishes WebSocket connection to the transcription service;
+    ///    audio captured meanwhile is queued and sent once connected
+    /// 3. Spawns the connection task, which reconnects if the socket drops
     /// 4. Sets up event handling with the provided callback
     ///
     /// # Arguments
@@ -119,11 +475,104 @@ impl TranscriptionSession {
     where
         F: Fn(TranscriptEvent) + Send + Sync + 'static,
     {
-        info!("Starting transcription session");
+        Self::start_with_options(&TranscriptionOptions::new(api_key), on_event).await
+    }
+
+    /// Start a new transcription session with explicit options
+    ///
+    /// Same as [`TranscriptionSession::start`], but the capture device and
+    /// Scribe parameters are taken from `options` instead of the defaults.
+    ///
+    /// The device is watched by a [`SupervisedCapture`]: if it is lost, the
+    /// session switches devices as `options.failover` allows and reports
+    /// [`TranscriptEvent::DeviceSwitched`], or reports
+    /// [`TranscriptEvent::DeviceLost`] and ends like a finite source.
+    ///
+    /// # Arguments
+    /// * `options` - Session options, typically built with
+    ///   [`TranscriptionOptions::from_config`]
+    /// * `on_event` - Callback function for transcription events
+    ///
+    /// # Errors
+    /// Returns error if connection fails or audio setup fails
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raflow_lib::transcription::{TranscriptionOptions, TranscriptionSession};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let options = TranscriptionOptions::new("api-key").with_language("en");
+    ///     let session = TranscriptionSession::start_with_options(&options, |event| {
+    ///         println!("Event: {:?}", event);
+    ///     }).await.unwrap();
+    /// }
+    /// ```
+    pub async fn start_with_options<F>(
+        options: &TranscriptionOptions,
+        on_event: F,
+    ) -> Result<Self, TranscriptionError>
+    where
+        F: Fn(TranscriptEvent) + Send + Sync + 'static,
+    {
+        let capture = SupervisedCapture::new(options.input_device_id.as_deref())
+            .map_err(|e| TranscriptionError::AudioError(e.to_string()))?
+            .with_failover(options.failover.clone());
+
+        Self::start_with_source(options, Box::new(capture), on_event).await
+    }
+
+    /// Start a new transcription session that reads audio from `source`
+    ///
+    /// Same as [`TranscriptionSession::start_with_options`], but audio comes
+    /// from the given source instead of the capture device in `options`. When
+    /// a finite source runs out, the last speech is committed and the session
+    /// ends with [`TranscriptEvent::Closed`]; call [`stop`](Self::stop) to
+    /// collect the text.
+    ///
+    /// # Errors
+    /// Returns error if connection fails or the source cannot be started
+    ///
+    /// # Example
+    /// ```no_run
+    /// use raflow_lib::audio::{FileSource, Pacing};
+    /// use raflow_lib::transcription::{TranscriptionOptions, TranscriptionSession};
+    ///
+    /// #[tokio::main]
+    /// async fn main() {
+    ///     let options = TranscriptionOptions::new("api-key");
+    ///     let source = FileSource::open_wav("recording.wav").unwrap().with_pacing(Pacing::Fast);
+    ///     let session = TranscriptionSession::start_with_source(&options, Box::new(source), |event| {
+    ///         println!("Event: {:?}", event);
+    ///     }).await.unwrap();
+    /// }
+    /// ```
+    pub async fn start_with_source<F>(
+        options: &TranscriptionOptions,
+        source: Box<dyn AudioSource>,
+        on_event: F,
+    ) -> Result<Self, TranscriptionError>
+    where
+        F: Fn(TranscriptEvent) + Send + Sync + 'static,
+    {
+        info!(
+            model = %options.model_id,
+            language = ?options.language_code,
+            "Starting transcription session"
+        );
 
         // 1. Create audio pipeline
-        let mut audio_pipeline = AudioPipeline::new(None)
-            .map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+        let (vad_tx, mut vad_rx) = mpsc::channel::<VadEvent>(16);
+        let (level_tx, mut level_rx) = mpsc::channel::<AudioLevel>(16);
+        let (device_tx, mut device_rx) = mpsc::channel::<DeviceEvent>(8);
+        let mut audio_pipeline = AudioPipeline::from_source(source)
+            .with_channel_mode(options.channel_mode)
+            .with_noise_suppression(options.noise_suppression)
+            .with_gain(options.gain.clone())
+            .with_vad(options.vad.clone())
+            .with_vad_events(vad_tx)
+            .with_level_events(level_tx)
+            .with_device_events(device_tx);
 
         let input_rate = audio_pipeline.input_sample_rate();
         let output_rate = audio_pipeline.output_sample_rate();
@@ -132,56 +581,125 @@ impl TranscriptionSession {
             input_rate, output_rate
         );
 
-        // 2. Establish WebSocket connection
-        let config = ConnectionConfig::new(output_rate);
-        let connection = ScribeConnection::connect(api_key, &config)
-            .await
-            .map_err(TranscriptionError::NetworkError)?;
-
-        info!("WebSocket connection established");
-
-        // 3. Split connection into read/write halves
-        let (writer, reader) = connection.split();
+        // Record the processed audio if archiving is enabled; a failure here
+        // must not keep the user from dictating
+        let mut archive = None;
+        if let Some(archive_options) = &options.archive {
+            match SessionArchive::begin(
+                archive_options,
+                &options.model_id,
+                options.language_code.as_deref(),
+                output_rate,
+            ) {
+                Ok((session_archive, recorder)) => {
+                    audio_pipeline = audio_pipeline.with_recorder(recorder);
+                    archive = Some(session_archive);
+                }
+                Err(e) => warn!("Session archive disabled: {}", e),
+            }
+        }
 
-        // 4. Create channels for communication
+        // 2. Start audio pipeline before connecting: chunks captured during
+        // the handshake queue up in the audio channel (up to 10s) and are
+        // sent in order once the connection is up
         let (audio_tx, audio_rx) = mpsc::channel::<String>(100);
-        let (msg_tx, mut msg_rx) = mpsc::channel::<ServerMessage>(100);
-
-        // 5. Start audio pipeline
-        audio_pipeline
-            .start(audio_tx)
-            .await
-            .map_err(|e| TranscriptionError::AudioError(e.to_string()))?;
+        if let Err(e) = audio_pipeline.start(audio_tx).await {
+            if let Some(archive) = archive {
+                archive.discard();
+            }
+            return Err(TranscriptionError::AudioError(e.to_string()));
+        }
 
         info!("Audio pipeline started");
 
-        // 6. Spawn sender task
-        let sender_handle = tokio::spawn(async move {
-            debug!("Sender task starting");
-            let result = sender_task(writer, audio_rx).await;
-            debug!("Sender task completed: {:?}", result);
-            result
-        });
+        // 3. Establish WebSocket connection
+        let config = options.connection_config(output_rate);
+        let connection = match ScribeConnection::connect(&options.api_key, &config).await {
+            Ok(connection) => connection,
+            Err(e) => {
+                audio_pipeline.stop().await;
+                if let Some(archive) = archive {
+                    archive.discard();
+                }
+                return Err(TranscriptionError::NetworkError(e));
+            }
+        };
+
+        info!("WebSocket connection established");
 
-        // 7. Spawn receiver task
-        let receiver_handle = tokio::spawn(async move {
-            debug!("Receiver task starting");
-            let result = receiver_task(reader, msg_tx).await;
-            debug!("Receiver task completed: {:?}", result);
+        // 4. Create channel for connection events
+        let (event_tx, mut event_rx) = mpsc::channel::<ConnectionEvent>(100);
+
+        // 5. Spawn connection task
+        let api_key = options.api_key.clone();
+        let policy = options.reconnect.clone();
+        let connection_handle = tokio::spawn(async move {
+            debug!("Connection task starting");
+            let result =
+                reconnecting_task(connection, api_key, config, policy, audio_rx, event_tx).await;
+            debug!("Connection task completed: {:?}", result);
             result
         });
 
-        // 8. Spawn event handler task
+        // 6. Spawn event handler task
         let on_event = Arc::new(on_event);
+        let on_audio_event = Arc::clone(&on_event);
+        let committed_segments = Arc::new(Mutex::new(Vec::new()));
+        let segments = Arc::clone(&committed_segments);
+        let archive = Arc::new(Mutex::new(archive));
+        let archive_events = Arc::clone(&archive);
+        let reconnect_enabled = options.reconnect.is_enabled();
         let event_handler_handle = tokio::spawn(async move {
             debug!("Event handler starting");
 
-            while let Some(msg) = msg_rx.recv().await {
+            let mut session_started = false;
+
+            while let Some(connection_event) = event_rx.recv().await {
+                let msg = match connection_event {
+                    ConnectionEvent::Message(msg) => msg,
+                    ConnectionEvent::Reconnecting {
+                        attempt,
+                        delay,
+                        reason,
+                    } => {
+                        warn!(
+                            "Connection lost ({}), reconnecting in {:?} (attempt {})",
+                            reason, delay, attempt
+                        );
+                        on_event(TranscriptEvent::Reconnecting { attempt });
+                        continue;
+                    }
+                    ConnectionEvent::Reconnected {
+                        attempts,
+                        replayed_chunks,
+                    } => {
+                        info!(
+                            "Reconnected after {} attempt(s), {} chunks replayed",
+                            attempts, replayed_chunks
+                        );
+                        on_event(TranscriptEvent::Reconnected);
+                        continue;
+                    }
+                    ConnectionEvent::ReconnectFailed { attempts, error } => {
+                        error!("Reconnection failed after {} attempt(s): {}", attempts, error);
+                        on_event(TranscriptEvent::Error {
+                            message: format!("Connection lost: {}", error),
+                        });
+                        continue;
+                    }
+                };
+
                 debug!("Received server message: {:?}", std::mem::discriminant(&msg));
 
                 let event = match msg {
+                    ServerMessage::SessionStarted { session_id, .. } if session_started => {
+                        // A reconnected connection starts a new server session
+                        debug!("Resumed on server session: {}", session_id);
+                        continue;
+                    }
                     ServerMessage::SessionStarted { session_id, .. } => {
                         info!("Session started: {}", session_id);
+                        session_started = true;
                         TranscriptEvent::SessionStarted { session_id }
                     }
                     ServerMessage::PartialTranscript { text } => {
@@ -190,10 +708,16 @@ impl TranscriptionSession {
                     }
                     ServerMessage::CommittedTranscript { text } => {
                         info!("Committed transcript: {}", text);
+                        if let Ok(mut segments) = segments.lock() {
+                            segments.push(text.clone());
+                        }
                         TranscriptEvent::Committed { text }
                     }
                     ServerMessage::CommittedTranscriptWithTimestamps { text, .. } => {
                         info!("Committed transcript with timestamps: {}", text);
+                        if let Ok(mut segments) = segments.lock() {
+                            segments.push(text.clone());
+                        }
                         TranscriptEvent::Committed { text }
                     }
                     ServerMessage::InputError { error_message } => {
@@ -202,8 +726,37 @@ impl TranscriptionSession {
                             message: error_message,
                         }
                     }
+                    message @ (ServerMessage::AuthError { .. }
+                    | ServerMessage::QuotaExceeded { .. }
+                    | ServerMessage::RateLimited { .. }
+                    | ServerMessage::SessionTimeLimitExceeded { .. }) => {
+                        let Some(error) = message.to_network_error() else {
+                            continue;
+                        };
+
+                        // Retryable errors are followed by a reconnection, if enabled
+                        if error.is_retryable() && reconnect_enabled {
+                            warn!("Server error, expecting reconnection: {}", error);
+                            continue;
+                        }
+
+                        error!("Server error: {}", error);
+                        TranscriptEvent::Error {
+                            message: error.to_string(),
+                        }
+                    }
+                    ServerMessage::Unknown { message_type, .. } => {
+                        debug!("Ignoring unknown server message: {}", message_type);
+                        continue;
+                    }
                 };
 
+                if let Ok(mut archive) = archive_events.lock()
+                    && let Some(archive) = archive.as_mut()
+                {
+                    archive.record(&event);
+                }
+
                 // Call the user's callback
                 on_event(event);
             }
@@ -212,13 +765,80 @@ impl TranscriptionSession {
             on_event(TranscriptEvent::Closed);
         });
 
+        // 7. Spawn VAD, level and device event task (ends when the audio
+        // pipeline stops)
+        let audio_event_handle = tokio::spawn(async move {
+            let mut vad_open = true;
+            let mut level_open = true;
+            let mut device_open = true;
+
+            loop {
+                let event = tokio::select! {
+                    vad_event = vad_rx.recv(), if vad_open => match vad_event {
+                        Some(VadEvent::SpeechStarted) => TranscriptEvent::SpeechStarted,
+                        Some(VadEvent::SpeechEnded) => TranscriptEvent::SpeechEnded,
+                        Some(VadEvent::SilenceTimeout { silence_ms }) => {
+                            info!("No speech for {} ms", silence_ms);
+                            TranscriptEvent::SilenceTimeout { silence_ms }
+                        }
+                        None => {
+                            vad_open = false;
+                            continue;
+                        }
+                    },
+                    level = level_rx.recv(), if level_open => match level {
+                        Some(level) => TranscriptEvent::AudioLevel { level },
+                        None => {
+                            level_open = false;
+                            continue;
+                        }
+                    },
+                    device_event = device_rx.recv(), if device_open => match device_event {
+                        Some(DeviceEvent::Lost(error)) => {
+                            error!("Audio device lost: {}", error);
+                            TranscriptEvent::DeviceLost {
+                                message: error.to_string(),
+                            }
+                        }
+                        Some(DeviceEvent::Switched { error, device }) => {
+                            warn!("Audio device lost ({}), switched to {}", error, device);
+                            TranscriptEvent::DeviceSwitched {
+                                device,
+                                message: error.to_string(),
+                            }
+                        }
+                        Some(DeviceEvent::Error(error)) if error.is_device_lost() => {
+                            error!("Audio device lost: {}", error);
+                            TranscriptEvent::DeviceLost {
+                                message: error.to_string(),
+                            }
+                        }
+                        Some(DeviceEvent::Error(error)) => {
+                            error!("Audio stream error: {}", error);
+                            TranscriptEvent::Error {
+                                message: error.to_string(),
+                            }
+                        }
+                        None => {
+                            device_open = false;
+                            continue;
+                        }
+                    },
+                    else => break,
+                };
+                on_audio_event(event);
+            }
+        });
+
         info!("Transcription session started successfully");
 
         Ok(Self {
             audio_pipeline,
-            sender_handle: Some(sender_handle),
-            receiver_handle: Some(receiver_handle),
+            connection_handle: Some(connection_handle),
             event_handler_handle: Some(event_handler_handle),
+            audio_event_handle: Some(audio_event_handle),
+            committed_segments,
+            archive,
             is_running: true,
         })
     }
@@ -227,8 +847,14 @@ impl TranscriptionSession {
     ///
     /// This method:
     /// 1. Stops audio capture
-    /// 2. Waits for all tasks to complete
-    /// 3. Cleans up resources
+    /// 2. Commits pending speech and waits (up to the commit timeout) for the
+    ///    final committed transcript before closing the connection
+    /// 3. Waits for all tasks to complete
+    /// 4. Archives the recording and transcripts (if archiving is enabled)
+    ///
+    /// # Returns
+    /// All committed segments of the session joined into one text, or `None`
+    /// if nothing was committed
     ///
     /// # Errors
     /// Returns error if tasks fail to complete cleanly
@@ -237,13 +863,14 @@ impl TranscriptionSession {
     /// ```no_run
     /// # use raflow_lib::transcription::TranscriptionSession;
     /// # async fn example(mut session: TranscriptionSession) {
-    /// session.stop().await.unwrap();
+    /// let text = session.stop().await.unwrap();
+    /// println!("Final text: {:?}", text);
     /// # }
     /// ```
-    pub async fn stop(&mut self) -> Result<(), TranscriptionError> {
+    pub async fn stop(&mut self) -> Result<Option<String>, TranscriptionError> {
         if !self.is_running {
             warn!("Session already stopped");
-            return Ok(());
+            return Ok(None);
         }
 
         info!("Stopping transcription session");
@@ -252,25 +879,20 @@ impl TranscriptionSession {
         self.audio_pipeline.stop().await;
         info!("Audio pipeline stopped");
 
-        // 2. Wait for sender task to complete
-        if let Some(handle) = self.sender_handle.take() {
-            match handle.await {
-                Ok(Ok(())) => debug!("Sender task completed successfully"),
-                Ok(Err(e)) => warn!("Sender task completed with error: {}", e),
-                Err(e) => error!("Sender task panicked: {}", e),
-            }
+        if let Some(handle) = self.audio_event_handle.take() {
+            let _ = handle.await;
         }
 
-        // 3. Wait for receiver task to complete
-        if let Some(handle) = self.receiver_handle.take() {
+        // 2. Wait for connection task to complete
+        if let Some(handle) = self.connection_handle.take() {
             match handle.await {
-                Ok(Ok(())) => debug!("Receiver task completed successfully"),
-                Ok(Err(e)) => warn!("Receiver task completed with error: {}", e),
-                Err(e) => error!("Receiver task panicked: {}", e),
+                Ok(Ok(())) => debug!("Connection task completed successfully"),
+                Ok(Err(e)) => warn!("Connection task completed with error: {}", e),
+                Err(e) => error!("Connection task panicked: {}", e),
             }
         }
 
-        // 4. Wait for event handler to complete
+        // 3. Wait for event handler to complete
         if let Some(handle) = self.event_handler_handle.take() {
             match handle.await {
                 Ok(()) => debug!("Event handler completed successfully"),
@@ -281,7 +903,36 @@ impl TranscriptionSession {
         self.is_running = false;
         info!("Transcription session stopped");
 
-        Ok(())
+        let text = self.take_committed_text();
+
+        // 4. Archive the recording (finalized when the pipeline stopped)
+        let archive = self
+            .archive
+            .lock()
+            .ok()
+            .and_then(|mut archive| archive.take());
+        if let Some(archive) = archive
+            && let Err(e) = archive.finish(text.clone())
+        {
+            warn!("Failed to archive session: {}", e);
+        }
+
+        Ok(text)
+    }
+
+    /// Take the committed segments received so far, joined into one text
+    fn take_committed_text(&self) -> Option<String> {
+        let segments = match self.committed_segments.lock() {
+            Ok(mut segments) => std::mem::take(&mut *segments),
+            Err(_) => return None,
+        };
+
+        let text = join_segments(&segments);
+        if text.is_empty() {
+            None
+        } else {
+            Some(text)
+        }
     }
 
     /// Check if the session is running
@@ -290,6 +941,17 @@ impl TranscriptionSession {
     }
 }
 
+/// Join committed segments into one text
+///
+/// Segments are joined as by [`append_transcript`].
+pub fn join_segments(segments: &[String]) -> String {
+    let mut text = String::new();
+    for segment in segments {
+        append_transcript(&mut text, segment);
+    }
+    text
+}
+
 /// Errors that can occur during transcription
 #[derive(Debug, thiserror::Error)]
 pub enum TranscriptionError {
@@ -325,11 +987,26 @@ mod tests {
             TranscriptEvent::Error {
                 message: "test error".to_string(),
             },
+            TranscriptEvent::Reconnecting { attempt: 1 },
+            TranscriptEvent::Reconnected,
+            TranscriptEvent::SpeechStarted,
+            TranscriptEvent::SpeechEnded,
+            TranscriptEvent::SilenceTimeout { silence_ms: 5000 },
+            TranscriptEvent::AudioLevel {
+                level: AudioLevel::default(),
+            },
+            TranscriptEvent::DeviceLost {
+                message: "Audio device disconnected: USB Mic".to_string(),
+            },
+            TranscriptEvent::DeviceSwitched {
+                device: "Built-in Microphone".to_string(),
+                message: "Audio device disconnected: USB Mic".to_string(),
+            },
             TranscriptEvent::Closed,
         ];
 
         // Verify all variants can be created
-        assert_eq!(events.len(), 5);
+        assert_eq!(events.len(), 13);
     }
 
     #[test]
@@ -348,6 +1025,187 @@ mod tests {
         assert_ne!(event1, event3);
     }
 
+    #[test]
+    fn test_transcription_options_new() {
+        let options = TranscriptionOptions::new("key");
+
+        assert_eq!(options.api_key, "key");
+        assert_eq!(options.base_url, DEFAULT_BASE_URL);
+        assert_eq!(options.model_id, "scribe_v2_realtime");
+        assert_eq!(options.language_code, None);
+        assert!(!options.include_timestamps);
+        assert_eq!(options.vad_commit_strategy, None);
+        assert_eq!(options.input_device_id, None);
+        assert_eq!(options.input_device_name, None);
+        assert!(options.preferred_device_ids.is_empty());
+        assert_eq!(options.failover, FailoverPolicy::default());
+        assert_eq!(options.reconnect, ReconnectPolicy::default());
+        assert_eq!(options.commit_timeout_ms, DEFAULT_COMMIT_TIMEOUT_MS);
+        assert_eq!(options.channel_mode, ChannelMode::Downmix);
+        assert!(!options.noise_suppression);
+        assert_eq!(options.gain, GainConfig::default());
+        assert_eq!(options.vad, VadConfig::disabled());
+        assert_eq!(options.warm_pre_roll_ms, None);
+        assert_eq!(options.archive, None);
+    }
+
+    #[test]
+    fn test_transcription_options_from_config() {
+        let mut config = AppConfig::default();
+        config.api.api_key = "config-key".to_string();
+        config.api.base_url = "ws://localhost:9000/realtime".to_string();
+        config.api.language_code = Some("en".to_string());
+        config.api.include_timestamps = true;
+        config.api.vad_commit_strategy = Some("auto".to_string());
+        config.api.commit_timeout_ms = 500;
+        config.audio.input_device_id = Some("USB Mic".to_string());
+        config.audio.input_device_name = Some("USB Microphone".to_string());
+        config.audio.preferred_device_ids = vec!["Headset".to_string(), String::new()];
+        config.audio.input_channel = Some(1);
+        config.audio.device_failover = true;
+        config.audio.noise_suppression = true;
+        config.audio.gain = 1.5;
+        config.audio.agc_enabled = true;
+        config.audio.agc_target_level = 0.2;
+        config.audio.silence_threshold = 0.05;
+        config.audio.vad_enabled = true;
+        config.audio.vad_hangover_ms = 400;
+        config.audio.vad_pre_roll_ms = 200;
+        config.audio.warm_microphone = true;
+        config.audio.warm_pre_roll_ms = 750;
+        config.behavior.auto_stop_silence_secs = 8;
+
+        let options = TranscriptionOptions::from_config(&config);
+
+        assert_eq!(options.api_key, "config-key");
+        assert_eq!(options.base_url, "ws://localhost:9000/realtime");
+        assert_eq!(options.language_code, Some("en".to_string()));
+        assert!(options.include_timestamps);
+        assert_eq!(options.vad_commit_strategy, Some("auto".to_string()));
+        assert_eq!(options.input_device_id, Some("USB Mic".to_string()));
+        assert_eq!(
+            options.input_device_name,
+            Some("USB Microphone".to_string())
+        );
+        assert_eq!(
+            options.preferred_device_ids,
+            vec!["Headset".to_string(), String::new()]
+        );
+        assert_eq!(
+            options.device_selector(),
+            DeviceSelector::new(Some("USB Mic"))
+                .with_name(Some("USB Microphone"))
+                .with_preferred(vec!["Headset".to_string()])
+        );
+        assert_eq!(
+            options.failover,
+            FailoverPolicy::to_default().with_devices(vec!["Headset".to_string()])
+        );
+        assert_eq!(options.commit_timeout_ms, 500);
+        assert_eq!(options.channel_mode, ChannelMode::Select(1));
+        assert!(options.noise_suppression);
+        assert_eq!(options.gain, GainConfig::new(1.5).with_agc(0.2));
+        assert!(options.vad.enabled);
+        assert_eq!(options.vad.threshold, 0.05);
+        assert_eq!(options.vad.hangover_ms, 400);
+        assert_eq!(options.vad.pre_roll_ms, 200);
+        assert_eq!(options.vad.auto_stop_silence_ms, Some(8000));
+        assert_eq!(options.warm_pre_roll_ms, Some(750));
+    }
+
+    #[test]
+    fn test_transcription_options_vad_disabled() {
+        // Silence filtering is opt-in; the silence timeout works without it
+        let mut config = AppConfig::default();
+        let options = TranscriptionOptions::from_config(&config);
+
+        assert!(!options.vad.enabled);
+        assert_eq!(options.vad.auto_stop_silence_ms, None);
+        assert_eq!(options.warm_pre_roll_ms, None);
+
+        config.behavior.auto_stop_silence_secs = 5;
+        let options = TranscriptionOptions::from_config(&config);
+        assert!(!options.vad.enabled);
+        assert_eq!(options.vad.auto_stop_silence_ms, Some(5000));
+    }
+
+    #[test]
+    fn test_transcription_options_ignore_empty_values() {
+        let mut config = AppConfig::default();
+        config.api.language_code = Some(String::new());
+        config.api.model_id = String::new();
+        config.api.base_url = "  ".to_string();
+        config.audio.input_device_id = Some(String::new());
+        config.audio.input_device_name = Some(String::new());
+
+        let options = TranscriptionOptions::from_config(&config);
+
+        assert_eq!(options.base_url, DEFAULT_BASE_URL);
+        assert_eq!(options.language_code, None);
+        assert_eq!(options.model_id, "scribe_v2_realtime");
+        assert_eq!(options.input_device_id, None);
+        assert_eq!(options.input_device_name, None);
+    }
+
+    #[test]
+    fn test_transcription_options_connection_config() {
+        let options = TranscriptionOptions::new("key")
+            .with_base_url("ws://127.0.0.1:9000")
+            .with_model("custom_model")
+            .with_language("zh")
+            .with_timestamps()
+            .with_vad_strategy("auto")
+            .with_commit_timeout(750);
+
+        let config = options.connection_config(16000);
+
+        assert_eq!(config.sample_rate, 16000);
+        assert_eq!(config.base_url, "ws://127.0.0.1:9000");
+        assert_eq!(config.model_id, "custom_model");
+        assert_eq!(config.language_code, Some("zh".to_string()));
+        assert!(config.include_timestamps);
+        assert_eq!(config.vad_commit_strategy, Some("auto".to_string()));
+        assert_eq!(config.commit_timeout_ms, 750);
+    }
+
+    #[test]
+    fn test_transcription_options_reconnect_policy() {
+        let options =
+            TranscriptionOptions::new("key").with_reconnect_policy(ReconnectPolicy::disabled());
+
+        assert!(!options.reconnect.is_enabled());
+    }
+
+    #[test]
+    fn test_transcription_options_archive() {
+        let retention = RetentionPolicy::default().with_max_recordings(5);
+        let options = TranscriptionOptions::new("key")
+            .with_archive(ArchiveOptions::new("/tmp/recordings").with_retention(retention));
+
+        let archive = options.archive.unwrap();
+        assert_eq!(archive.dir, std::path::PathBuf::from("/tmp/recordings"));
+        assert_eq!(archive.retention.max_recordings, Some(5));
+    }
+
+    #[test]
+    fn test_join_segments_latin() {
+        let segments = vec!["Hello world.".to_string(), " How are you?".to_string()];
+        assert_eq!(join_segments(&segments), "Hello world. How are you?");
+    }
+
+    #[test]
+    fn test_join_segments_cjk() {
+        let segments = vec!["你好。".to_string(), "今天天气不错".to_string()];
+        assert_eq!(join_segments(&segments), "你好。今天天气不错");
+    }
+
+    #[test]
+    fn test_join_segments_skips_empty() {
+        let segments = vec!["".to_string(), "  ".to_string(), "ok".to_string()];
+        assert_eq!(join_segments(&segments), "ok");
+        assert_eq!(join_segments(&[]), "");
+    }
+
     #[test]
     fn test_transcript_event_clone() {
         let event = TranscriptEvent::Committed {
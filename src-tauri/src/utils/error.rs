@@ -96,6 +96,12 @@ pub enum ErrorCode {
     NetworkProtocolError,
     /// 连接超时
     NetworkTimeout,
+    /// 用量配额已用尽
+    NetworkQuotaExceeded,
+    /// 请求过于频繁
+    NetworkRateLimited,
+    /// 会话达到时长上限
+    NetworkSessionTimeout,
 
     // 输入错误 (3xxx)
     /// 权限被拒绝
@@ -198,6 +204,9 @@ impl AppError {
             AppError::Network(NetworkError::AuthenticationFailed) => ErrorCode::NetworkAuthFailed,
             AppError::Network(NetworkError::ProtocolError(_)) => ErrorCode::NetworkProtocolError,
             AppError::Network(NetworkError::Timeout(_)) => ErrorCode::NetworkTimeout,
+            AppError::Network(NetworkError::QuotaExceeded(_)) => ErrorCode::NetworkQuotaExceeded,
+            AppError::Network(NetworkError::RateLimited(_)) => ErrorCode::NetworkRateLimited,
+            AppError::Network(NetworkError::SessionTimeout(_)) => ErrorCode::NetworkSessionTimeout,
             AppError::Network(_) => ErrorCode::NetworkConnectionFailed,
 
             // 输入错误
@@ -265,6 +274,15 @@ impl AppError {
             AppError::Network(NetworkError::ConnectionClosed) => {
                 "连接已断开，请重试".to_string()
             }
+            AppError::Network(NetworkError::QuotaExceeded(_)) => {
+                "语音识别用量已用尽".to_string()
+            }
+            AppError::Network(NetworkError::RateLimited(_)) => {
+                "请求过于频繁，请稍后重试".to_string()
+            }
+            AppError::Network(NetworkError::SessionTimeout(_)) => {
+                "单次会话时长已达上限且未能重新连接，请重新开始录音".to_string()
+            }
             AppError::Network(_) => {
                 "网络错误，请检查网络连接".to_string()
             }
@@ -366,6 +384,12 @@ impl AppError {
             AppError::Network(NetworkError::ConnectionFailed(_)) => {
                 Some("请检查网络连接，或稍后重试".to_string())
             }
+            AppError::Network(NetworkError::QuotaExceeded(_)) => {
+                Some("请在 ElevenLabs 控制台查看用量或升级套餐".to_string())
+            }
+            AppError::Network(NetworkError::RateLimited(_)) => {
+                Some("请等待片刻后再开始录音".to_string())
+            }
             AppError::Input(InputError::PermissionDenied) => {
                 Some("请在系统设置中为 RaFlow 开启辅助功能权限".to_string())
             }
@@ -437,6 +461,22 @@ mod tests {
         assert_eq!(err.code(), ErrorCode::InputPermissionDenied);
     }
 
+    #[test]
+    fn test_server_error_codes() {
+        let err = AppError::Network(NetworkError::QuotaExceeded("quota".to_string()));
+        assert_eq!(err.code(), ErrorCode::NetworkQuotaExceeded);
+        assert!(err.recovery_hint().is_some());
+
+        let err = AppError::Network(NetworkError::RateLimited("slow".to_string()));
+        assert_eq!(err.code(), ErrorCode::NetworkRateLimited);
+
+        let err = AppError::Network(NetworkError::SessionTimeout("limit".to_string()));
+        assert_eq!(err.code(), ErrorCode::NetworkSessionTimeout);
+
+        let json = serde_json::to_string(&ErrorCode::NetworkQuotaExceeded).unwrap();
+        assert_eq!(json, "\"NETWORK_QUOTA_EXCEEDED\"");
+    }
+
     #[test]
     fn test_user_message() {
         let err = AppError::Audio(AudioError::DeviceNotFound);
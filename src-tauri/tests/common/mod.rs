@@ -0,0 +1,12 @@
+//! Helpers shared by the mock-server integration tests
+
+use base64::{Engine, engine::general_purpose::STANDARD};
+
+/// Base64-encoded 100ms chunk of 16 kHz PCM with the given amplitude
+pub fn pcm_chunk(amplitude: i16) -> String {
+    let bytes: Vec<u8> = (0..1600)
+        .map(|i| if i % 2 == 0 { amplitude } else { -amplitude })
+        .flat_map(|s: i16| s.to_le_bytes())
+        .collect();
+    STANDARD.encode(bytes)
+}
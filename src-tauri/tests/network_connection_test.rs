@@ -23,6 +23,7 @@ fn test_connection_config_creation() {
     assert_eq!(config.language_code, None);
     assert!(!config.include_timestamps);
     assert_eq!(config.timeout_ms, 10000);
+    assert_eq!(config.commit_timeout_ms, 2000);
 
     println!("\n✓ Default configuration created correctly");
 }
@@ -36,7 +37,8 @@ fn test_connection_config_builder_pattern() {
         .with_language("zh")
         .with_timestamps()
         .with_vad_strategy("auto")
-        .with_timeout(5000);
+        .with_timeout(5000)
+        .with_commit_timeout(1500);
 
     println!("Custom configuration:");
     println!("  Model ID: {}", config.model_id);
@@ -50,6 +52,7 @@ fn test_connection_config_builder_pattern() {
     assert!(config.include_timestamps);
     assert_eq!(config.vad_commit_strategy, Some("auto".to_string()));
     assert_eq!(config.timeout_ms, 5000);
+    assert_eq!(config.commit_timeout_ms, 1500);
 
     println!("\n✓ Builder pattern works correctly");
 }
@@ -318,3 +321,21 @@ fn test_config_immutability() {
     println!("Modified config language: {:?}", config2.language_code);
     println!("\n✓ Builder pattern doesn't mutate original");
 }
+
+#[test]
+fn test_connection_config_custom_endpoint() {
+    println!("\n=== Custom Endpoint Test ===");
+
+    let config = ConnectionConfig::new(16000)
+        .with_base_url("ws://localhost:8765/v1/speech-to-text/realtime")
+        .with_language("zh");
+
+    let url = config.build_url().unwrap();
+    println!("Custom URL:\n{}", url);
+
+    assert!(url.starts_with("ws://localhost:8765/v1/speech-to-text/realtime?"));
+    assert!(url.contains("language_code=zh"));
+    assert_eq!(config.host_header().unwrap(), "localhost:8765");
+
+    println!("\n✓ Custom endpoint handled correctly");
+}
@@ -0,0 +1,201 @@
+//! Integration tests for graceful stop: commit, wait for the final transcript, then close
+//!
+//! Run with: `cargo test --features mock-server --test network_finalize_test`
+
+#![cfg(feature = "mock-server")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::pcm_chunk;
+use raflow_lib::network::mock::{MockFault, MockScribeServer, MockServerConfig};
+use raflow_lib::network::{
+    ConnectionConfig, ConnectionEvent, ReconnectPolicy, ScribeConnection, reconnecting_task,
+};
+use tokio::sync::mpsc;
+
+/// Stream `chunks`, close the audio channel and collect every event until the task ends
+async fn stream_and_stop(
+    config: ConnectionConfig,
+    chunks: Vec<String>,
+) -> (Vec<ConnectionEvent>, Duration) {
+    let connection = ScribeConnection::connect("key", &config).await.unwrap();
+
+    let (audio_tx, audio_rx) = mpsc::channel(100);
+    let (event_tx, mut event_rx) = mpsc::channel(100);
+
+    let handle = tokio::spawn(reconnecting_task(
+        connection,
+        "key".to_string(),
+        config,
+        ReconnectPolicy::disabled(),
+        audio_rx,
+        event_tx,
+    ));
+
+    for chunk in chunks {
+        audio_tx.send(chunk).await.unwrap();
+    }
+
+    // Give the server time to send partials before stopping
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let stopped_at = Instant::now();
+    drop(audio_tx);
+
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("task did not finish")
+        .unwrap()
+        .unwrap();
+    let elapsed = stopped_at.elapsed();
+
+    let mut events = Vec::new();
+    while let Some(event) = event_rx.recv().await {
+        events.push(event);
+    }
+
+    (events, elapsed)
+}
+
+fn committed_texts(events: &[ConnectionEvent]) -> Vec<&str> {
+    events
+        .iter()
+        .filter_map(|event| match event {
+            ConnectionEvent::Message(message) if message.is_committed() => message.text(),
+            _ => None,
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_stop_commits_pending_speech_before_close() {
+    let config = MockServerConfig::new()
+        .with_transcript("the final words")
+        .with_chunks_per_token(1)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let (events, _) = stream_and_stop(
+        server.connection_config(16000),
+        vec![pcm_chunk(1000), pcm_chunk(1000)],
+    )
+    .await;
+
+    assert_eq!(committed_texts(&events), vec!["the final"]);
+
+    let sessions = server.sessions();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].commits, 1);
+    assert!(sessions[0].close_received);
+    assert_eq!(sessions[0].committed, vec!["the final"]);
+}
+
+#[tokio::test]
+async fn test_stop_without_pending_speech_closes_immediately() {
+    let config = MockServerConfig::new()
+        .with_transcript("hello")
+        .with_chunks_per_token(1);
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    // The segment is committed automatically, nothing is pending at stop
+    let connection = server.connection_config(16000).with_commit_timeout(3000);
+    let (events, elapsed) = stream_and_stop(connection, vec![pcm_chunk(1000)]).await;
+
+    assert_eq!(committed_texts(&events), vec!["hello"]);
+    assert!(elapsed < Duration::from_secs(1));
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].commits, 0);
+    assert!(sessions[0].close_received);
+}
+
+#[tokio::test]
+async fn test_stop_gives_up_waiting_after_commit_timeout() {
+    // A partial arrives but the server never commits it: the chunks are
+    // below the speech threshold, so the scripted segment is never revealed
+    let config = MockServerConfig::new()
+        .with_min_amplitude(i16::MAX)
+        .without_auto_commit()
+        .with_fault(MockFault::Raw {
+            after_chunks: 1,
+            payload: r#"{"message_type":"partial_transcript","text":"lost"}"#.to_string(),
+        });
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let connection = server.connection_config(16000).with_commit_timeout(300);
+    let (events, elapsed) = stream_and_stop(connection, vec![pcm_chunk(1000)]).await;
+
+    assert!(committed_texts(&events).is_empty());
+    assert!(elapsed >= Duration::from_millis(300));
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].commits, 1);
+    assert!(sessions[0].close_received);
+}
+
+#[tokio::test]
+async fn test_stop_commits_audio_without_partial() {
+    // Speech at the very end has not produced a partial transcript yet
+    let config = MockServerConfig::new()
+        .with_transcript("late words")
+        .with_chunks_per_token(10)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let connection = server.connection_config(16000).with_commit_timeout(300);
+    let (events, _) = stream_and_stop(connection, vec![pcm_chunk(1000), pcm_chunk(1000)]).await;
+
+    assert!(events.iter().all(|event| !matches!(
+        event,
+        ConnectionEvent::Message(message) if message.is_partial()
+    )));
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].commits, 1);
+    assert!(sessions[0].close_received);
+}
+
+#[tokio::test]
+async fn test_stop_with_zero_commit_timeout_keeps_final_transcript() {
+    let config = MockServerConfig::new()
+        .with_transcript("the final words")
+        .with_chunks_per_token(1)
+        .without_auto_commit()
+        .with_commit_delay(200);
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let connection = server.connection_config(16000).with_commit_timeout(0);
+    let (events, _) = stream_and_stop(connection, vec![pcm_chunk(1000), pcm_chunk(1000)]).await;
+
+    assert_eq!(committed_texts(&events), vec!["the final"]);
+    assert!(server.sessions()[0].close_received);
+}
+
+#[tokio::test]
+async fn test_stop_without_audio_is_not_committed() {
+    let config = MockServerConfig::new().without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let (events, elapsed) = stream_and_stop(server.connection_config(16000), Vec::new()).await;
+
+    assert!(committed_texts(&events).is_empty());
+    assert!(elapsed < Duration::from_secs(1));
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].commits, 0);
+    assert!(sessions[0].close_received);
+}
+
+#[tokio::test]
+async fn test_stop_uses_configured_sample_rate() {
+    let config = MockServerConfig::new()
+        .with_transcript("hi")
+        .with_chunks_per_token(1);
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    stream_and_stop(server.connection_config(24000), vec![pcm_chunk(1000)]).await;
+
+    assert_eq!(server.sessions()[0].sample_rates, vec![24000]);
+}
@@ -0,0 +1,419 @@
+//! Integration tests against the local mock Scribe server
+//!
+//! These tests exercise the real WebSocket client (`ScribeConnection`, sender and
+//! receiver tasks) without network access or an API key.
+//!
+//! Run with: `cargo test --features mock-server --test network_mock_test`
+
+#![cfg(feature = "mock-server")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::pcm_chunk;
+use raflow_lib::network::mock::{MockFault, MockScribeServer, MockServerConfig};
+use raflow_lib::network::tasks::{receiver_task, sender_task, OutgoingCommand};
+use raflow_lib::network::{
+    CommitMessage, ConnectionConfig, InputAudioChunk, NetworkError, ScribeConnection,
+    ServerMessage,
+};
+use tokio::sync::mpsc;
+
+/// Receive the next message with a timeout
+async fn next_message(conn: &mut ScribeConnection) -> ServerMessage {
+    tokio::time::timeout(Duration::from_secs(5), conn.recv())
+        .await
+        .expect("timed out waiting for mock server")
+        .expect("receive failed")
+        .expect("connection closed")
+}
+
+#[tokio::test]
+async fn test_mock_session_started() {
+    let server = MockScribeServer::start(MockServerConfig::new()).await.unwrap();
+    let config = server.connection_config(16000).with_language("en");
+
+    let mut conn = ScribeConnection::connect("test-key", &config).await.unwrap();
+
+    match next_message(&mut conn).await {
+        ServerMessage::SessionStarted { session_id, config } => {
+            assert_eq!(session_id, "mock-session-1");
+            let config = config.unwrap();
+            assert_eq!(config.sample_rate, 16000);
+            assert_eq!(config.language_code, Some("en".to_string()));
+        }
+        other => panic!("Expected session_started, got {:?}", other),
+    }
+
+    let sessions = server.sessions();
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].api_key, Some("test-key".to_string()));
+    assert_eq!(sessions[0].query.get("language_code"), Some(&"en".to_string()));
+    assert_eq!(sessions[0].host, Some(server.local_addr().to_string()));
+}
+
+#[tokio::test]
+async fn test_mock_partial_and_committed_transcripts() {
+    let config = MockServerConfig::new()
+        .with_transcript("hello brave new world")
+        .with_chunks_per_token(1);
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let conn = ScribeConnection::connect("key", &server.connection_config(16000))
+        .await
+        .unwrap();
+    let (writer, reader) = conn.split();
+
+    let (audio_tx, audio_rx) = mpsc::channel(16);
+    let (msg_tx, mut msg_rx) = mpsc::channel(16);
+
+    let sender = tokio::spawn(sender_task(writer, audio_rx, 16000));
+    let receiver = tokio::spawn(receiver_task(reader, msg_tx));
+
+    for _ in 0..4 {
+        audio_tx
+            .send(OutgoingCommand::Audio(pcm_chunk(1000)))
+            .await
+            .unwrap();
+    }
+
+    let mut partials = Vec::new();
+    let mut committed = None;
+
+    while committed.is_none() {
+        let message = tokio::time::timeout(Duration::from_secs(5), msg_rx.recv())
+            .await
+            .expect("timed out waiting for transcripts")
+            .expect("receiver stopped");
+
+        match message {
+            ServerMessage::PartialTranscript { text } => partials.push(text),
+            ServerMessage::CommittedTranscript { text } => committed = Some(text),
+            _ => {}
+        }
+    }
+
+    assert_eq!(
+        partials,
+        vec!["hello", "hello brave", "hello brave new", "hello brave new world"]
+    );
+    assert_eq!(committed.as_deref(), Some("hello brave new world"));
+
+    drop(audio_tx);
+    sender.await.unwrap().unwrap();
+    receiver.await.unwrap().unwrap();
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].audio_chunks, 4);
+    assert_eq!(sessions[0].samples, 4 * 1600);
+    assert_eq!(sessions[0].committed, vec!["hello brave new world"]);
+}
+
+#[tokio::test]
+async fn test_mock_sender_commands() {
+    let config = MockServerConfig::new()
+        .with_transcripts(["one two", "three"])
+        .with_chunks_per_token(1)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let connection = server.connection_config(24000);
+    let conn = ScribeConnection::connect("key", &connection).await.unwrap();
+    let (writer, reader) = conn.split();
+
+    let (command_tx, command_rx) = mpsc::channel(16);
+    let (msg_tx, mut msg_rx) = mpsc::channel(16);
+
+    let sender = tokio::spawn(sender_task(writer, command_rx, connection.sample_rate));
+    let receiver = tokio::spawn(receiver_task(reader, msg_tx));
+
+    let commands = vec![
+        OutgoingCommand::UpdateContext("earlier text".to_string()),
+        OutgoingCommand::Audio(pcm_chunk(1000)),
+        OutgoingCommand::Audio(pcm_chunk(1000)),
+        OutgoingCommand::Commit,
+        OutgoingCommand::Audio(pcm_chunk(1000)),
+        OutgoingCommand::Close,
+    ];
+    for command in commands {
+        command_tx.send(command).await.unwrap();
+    }
+
+    // The sender stops after the close command even though the channel is open
+    tokio::time::timeout(Duration::from_secs(5), sender)
+        .await
+        .expect("sender did not stop after close")
+        .unwrap()
+        .unwrap();
+
+    let mut committed = Vec::new();
+    while let Some(message) = msg_rx.recv().await {
+        if let ServerMessage::CommittedTranscript { text } = message {
+            committed.push(text);
+        }
+    }
+    receiver.await.unwrap().unwrap();
+
+    // The close message commits the last segment
+    assert_eq!(committed, vec!["one two", "three"]);
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].sample_rates, vec![24000]);
+    assert_eq!(sessions[0].previous_texts, vec!["earlier text"]);
+    assert_eq!(sessions[0].commits, 1);
+    assert!(sessions[0].close_received);
+}
+
+#[tokio::test]
+async fn test_mock_silence_produces_no_transcript() {
+    let config = MockServerConfig::new()
+        .with_chunks_per_token(1)
+        .with_min_amplitude(500)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let mut conn = ScribeConnection::connect("key", &server.connection_config(16000))
+        .await
+        .unwrap();
+    assert!(next_message(&mut conn).await.session_id().is_some());
+
+    conn.send(&InputAudioChunk::new(pcm_chunk(10)).with_sample_rate(16000))
+        .await
+        .unwrap();
+    conn.send(&CommitMessage::new()).await.unwrap();
+
+    // Speech after silence is still transcribed
+    conn.send(&InputAudioChunk::new(pcm_chunk(2000))).await.unwrap();
+    conn.send(&CommitMessage::new()).await.unwrap();
+
+    assert_eq!(
+        next_message(&mut conn).await,
+        ServerMessage::PartialTranscript {
+            text: "hello".to_string()
+        }
+    );
+    assert_eq!(
+        next_message(&mut conn).await,
+        ServerMessage::CommittedTranscript {
+            text: "hello".to_string()
+        }
+    );
+
+    let sessions = server.sessions();
+    assert_eq!(sessions[0].commits, 2);
+    assert_eq!(sessions[0].sample_rates, vec![16000]);
+}
+
+#[tokio::test]
+async fn test_mock_cjk_transcript() {
+    let config = MockServerConfig::new()
+        .with_transcript("你好世界")
+        .with_chunks_per_token(1);
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let mut conn = ScribeConnection::connect("key", &server.connection_config(16000))
+        .await
+        .unwrap();
+    next_message(&mut conn).await;
+
+    for _ in 0..4 {
+        conn.send(&InputAudioChunk::new(pcm_chunk(1000))).await.unwrap();
+    }
+
+    let mut committed = None;
+    while committed.is_none() {
+        if let ServerMessage::CommittedTranscript { text } = next_message(&mut conn).await {
+            committed = Some(text);
+        }
+    }
+
+    assert_eq!(committed.as_deref(), Some("你好世界"));
+}
+
+#[tokio::test]
+async fn test_mock_timestamps() {
+    let config = MockServerConfig::new()
+        .with_transcript("hello world")
+        .with_chunks_per_token(1);
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let connection = server.connection_config(16000).with_timestamps();
+    let mut conn = ScribeConnection::connect("key", &connection).await.unwrap();
+    next_message(&mut conn).await;
+
+    for _ in 0..2 {
+        conn.send(&InputAudioChunk::new(pcm_chunk(1000))).await.unwrap();
+    }
+
+    loop {
+        match next_message(&mut conn).await {
+            ServerMessage::CommittedTranscriptWithTimestamps { text, words, .. } => {
+                assert_eq!(text, "hello world");
+                assert_eq!(words.len(), 2);
+                assert!((words[1].end - 0.2).abs() < 1e-6);
+                break;
+            }
+            ServerMessage::PartialTranscript { .. } => continue,
+            other => panic!("Unexpected message: {:?}", other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_mock_injected_input_error() {
+    let config = MockServerConfig::new().with_fault(MockFault::InputError {
+        after_chunks: 1,
+        message: "Audio format not supported".to_string(),
+    });
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let mut conn = ScribeConnection::connect("key", &server.connection_config(16000))
+        .await
+        .unwrap();
+    next_message(&mut conn).await;
+
+    conn.send(&InputAudioChunk::new(pcm_chunk(0))).await.unwrap();
+
+    let message = next_message(&mut conn).await;
+    assert!(message.is_error());
+    assert_eq!(message.error_message(), Some("Audio format not supported"));
+}
+
+#[tokio::test]
+async fn test_mock_unknown_message_is_forwarded() {
+    let config = MockServerConfig::new()
+        .with_transcript("still works")
+        .with_chunks_per_token(1)
+        .with_fault(MockFault::Raw {
+            after_chunks: 1,
+            payload: r#"{"message_type":"brand_new_event","detail":{"x":1}}"#.to_string(),
+        });
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let conn = ScribeConnection::connect("key", &server.connection_config(16000))
+        .await
+        .unwrap();
+    let (writer, reader) = conn.split();
+
+    let (command_tx, command_rx) = mpsc::channel(16);
+    let (msg_tx, mut msg_rx) = mpsc::channel(16);
+
+    let sender = tokio::spawn(sender_task(writer, command_rx, 16000));
+    let receiver = tokio::spawn(receiver_task(reader, msg_tx));
+
+    for _ in 0..2 {
+        command_tx
+            .send(OutgoingCommand::Audio(pcm_chunk(1000)))
+            .await
+            .unwrap();
+    }
+    drop(command_tx);
+
+    let mut unknown = None;
+    let mut committed = None;
+    while let Some(message) = msg_rx.recv().await {
+        match message {
+            ServerMessage::Unknown { message_type, raw } => {
+                assert_eq!(raw["detail"]["x"], 1);
+                unknown = Some(message_type);
+            }
+            ServerMessage::CommittedTranscript { text } => committed = Some(text),
+            _ => {}
+        }
+    }
+
+    // The receiver keeps running after the unknown message
+    assert_eq!(unknown.as_deref(), Some("brand_new_event"));
+    assert_eq!(committed.as_deref(), Some("still works"));
+    sender.await.unwrap().unwrap();
+    receiver.await.unwrap().unwrap();
+}
+
+#[tokio::test]
+async fn test_mock_disconnect_fault_fires_once() {
+    let config = MockServerConfig::new().with_fault(MockFault::Disconnect { after_chunks: 1 });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let connection = server.connection_config(16000);
+
+    let mut conn = ScribeConnection::connect("key", &connection).await.unwrap();
+    next_message(&mut conn).await;
+    conn.send(&InputAudioChunk::new(pcm_chunk(0))).await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), conn.recv())
+        .await
+        .expect("timed out waiting for disconnect");
+    assert!(!matches!(result, Ok(Some(_))));
+
+    // A second connection is healthy
+    let mut conn = ScribeConnection::connect("key", &connection).await.unwrap();
+    assert!(next_message(&mut conn).await.session_id().is_some());
+    assert_eq!(server.sessions().len(), 2);
+}
+
+#[tokio::test]
+async fn test_mock_rejects_wrong_api_key() {
+    let server = MockScribeServer::start(MockServerConfig::new().with_api_key("secret"))
+        .await
+        .unwrap();
+    let connection = server.connection_config(16000);
+
+    let result = ScribeConnection::connect("wrong", &connection).await;
+    assert!(matches!(result, Err(NetworkError::AuthenticationFailed)));
+
+    assert!(ScribeConnection::connect("secret", &connection).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_mock_rejected_handshake() {
+    let config = MockServerConfig::new().with_fault(MockFault::RejectHandshake { status: 429 });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let connection = server.connection_config(16000);
+
+    let result = ScribeConnection::connect("key", &connection).await;
+    assert!(matches!(result, Err(NetworkError::ConnectionFailed(_))));
+
+    // The fault only applies to the first handshake
+    assert!(ScribeConnection::connect("key", &connection).await.is_ok());
+}
+
+#[tokio::test]
+async fn test_mock_close_commits_pending_text() {
+    let config = MockServerConfig::new()
+        .with_transcript("one two three")
+        .with_chunks_per_token(1)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let mut conn = ScribeConnection::connect("key", &server.connection_config(16000))
+        .await
+        .unwrap();
+    next_message(&mut conn).await;
+
+    conn.send(&InputAudioChunk::new(pcm_chunk(1000))).await.unwrap();
+    conn.send(&InputAudioChunk::new(pcm_chunk(1000))).await.unwrap();
+    conn.send(&raflow_lib::network::CloseMessage::new())
+        .await
+        .unwrap();
+
+    let mut committed = None;
+    while let Ok(Some(message)) = conn.recv().await {
+        if let ServerMessage::CommittedTranscript { text } = message {
+            committed = Some(text);
+        }
+    }
+
+    assert_eq!(committed.as_deref(), Some("one two"));
+    assert!(server.sessions()[0].close_received);
+}
+
+#[tokio::test]
+async fn test_mock_url_is_plain_websocket() {
+    let server = MockScribeServer::start(MockServerConfig::new()).await.unwrap();
+
+    assert!(server.url().starts_with("ws://127.0.0.1:"));
+    let config: ConnectionConfig = server.connection_config(16000);
+    assert_eq!(config.host_header().unwrap(), server.local_addr().to_string());
+
+    server.shutdown().await;
+}
@@ -0,0 +1,426 @@
+//! Integration tests for automatic reconnection with audio replay
+//!
+//! Run with: `cargo test --features mock-server --test network_reconnect_test`
+
+#![cfg(feature = "mock-server")]
+
+mod common;
+
+use std::time::Duration;
+
+use common::pcm_chunk;
+use raflow_lib::network::mock::{MockFault, MockScribeServer, MockServerConfig};
+use raflow_lib::network::{
+    reconnecting_task, ConnectionEvent, NetworkError, ReconnectPolicy, ScribeConnection,
+    ServerMessage,
+};
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+
+/// Fast policy so tests do not wait on real backoff delays
+fn fast_policy() -> ReconnectPolicy {
+    ReconnectPolicy::default().with_delays(10, 50)
+}
+
+/// Connect to the mock and spawn the reconnecting task
+async fn start(
+    server: &MockScribeServer,
+    policy: ReconnectPolicy,
+) -> (
+    mpsc::Sender<String>,
+    mpsc::Receiver<ConnectionEvent>,
+    JoinHandle<Result<(), NetworkError>>,
+) {
+    let config = server.connection_config(16000);
+    let connection = ScribeConnection::connect("key", &config).await.unwrap();
+
+    let (audio_tx, audio_rx) = mpsc::channel(100);
+    let (event_tx, event_rx) = mpsc::channel(100);
+
+    let handle = tokio::spawn(reconnecting_task(
+        connection,
+        "key".to_string(),
+        config,
+        policy,
+        audio_rx,
+        event_tx,
+    ));
+
+    (audio_tx, event_rx, handle)
+}
+
+/// Wait for the next event matching `predicate`
+async fn wait_for(
+    events: &mut mpsc::Receiver<ConnectionEvent>,
+    predicate: impl Fn(&ConnectionEvent) -> bool,
+) -> ConnectionEvent {
+    loop {
+        let event = tokio::time::timeout(Duration::from_secs(5), events.recv())
+            .await
+            .expect("timed out waiting for event")
+            .expect("event channel closed");
+
+        if predicate(&event) {
+            return event;
+        }
+    }
+}
+
+fn committed(event: &ConnectionEvent) -> Option<&str> {
+    match event {
+        ConnectionEvent::Message(message) if message.is_committed() => message.text(),
+        _ => None,
+    }
+}
+
+#[tokio::test]
+async fn test_reconnect_replays_unacknowledged_audio() {
+    // "one two" is committed after 2 chunks, then the socket drops after
+    // the 3rd chunk, which belongs to the second segment
+    let config = MockServerConfig::new()
+        .with_transcripts(["one two", "three four"])
+        .with_chunks_per_token(1)
+        .with_fault(MockFault::Disconnect { after_chunks: 3 });
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    for _ in 0..2 {
+        audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    }
+
+    let first = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&first), Some("one two"));
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    let reconnecting =
+        wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnecting { .. })).await;
+    assert!(matches!(
+        reconnecting,
+        ConnectionEvent::Reconnecting { attempt: 1, .. }
+    ));
+
+    let reconnected =
+        wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnected { .. })).await;
+    assert_eq!(
+        reconnected,
+        ConnectionEvent::Reconnected {
+            attempts: 1,
+            replayed_chunks: 1
+        }
+    );
+
+    // One more live chunk completes the second segment
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    let second = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&second), Some("three four"));
+
+    drop(audio_tx);
+    handle.await.unwrap().unwrap();
+
+    let sessions = server.sessions();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[1].audio_chunks, 2);
+    assert_eq!(sessions[1].sample_rates, vec![16000]);
+    assert_eq!(sessions[1].previous_texts, vec!["one two"]);
+}
+
+#[tokio::test]
+async fn test_reconnect_sends_context_without_replay() {
+    // "one two" is committed by the 2nd chunk, then the socket drops with
+    // nothing left to replay
+    let config = MockServerConfig::new()
+        .with_transcripts(["one two", "three four"])
+        .with_chunks_per_token(1)
+        .with_fault(MockFault::Disconnect { after_chunks: 2 });
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    for _ in 0..2 {
+        audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    }
+
+    let first = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&first), Some("one two"));
+
+    let reconnected =
+        wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnected { .. })).await;
+    assert_eq!(
+        reconnected,
+        ConnectionEvent::Reconnected {
+            attempts: 1,
+            replayed_chunks: 0
+        }
+    );
+
+    for _ in 0..2 {
+        audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    }
+
+    let second = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&second), Some("three four"));
+
+    drop(audio_tx);
+    handle.await.unwrap().unwrap();
+
+    // The context goes out with the first live chunk on the new link
+    let sessions = server.sessions();
+    assert_eq!(sessions.len(), 2);
+    assert_eq!(sessions[1].audio_chunks, 2);
+    assert_eq!(sessions[1].previous_texts, vec!["one two"]);
+}
+
+#[tokio::test]
+async fn test_reconnect_replays_all_audio_without_commit() {
+    let config = MockServerConfig::new()
+        .with_transcript("alpha beta gamma delta")
+        .with_chunks_per_token(1)
+        .with_fault(MockFault::Disconnect { after_chunks: 1 });
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    for _ in 0..4 {
+        audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    }
+
+    let reconnected =
+        wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnected { .. })).await;
+    let ConnectionEvent::Reconnected {
+        replayed_chunks, ..
+    } = reconnected
+    else {
+        unreachable!();
+    };
+    assert_eq!(replayed_chunks, 4);
+
+    let event = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&event), Some("alpha beta gamma delta"));
+
+    drop(audio_tx);
+    handle.await.unwrap().unwrap();
+
+    // No transcript was committed before the drop, so there is no context
+    let sessions = server.sessions();
+    assert!(sessions.last().unwrap().previous_texts.is_empty());
+}
+
+#[tokio::test]
+async fn test_reconnect_gives_up_after_max_attempts() {
+    let config = MockServerConfig::new()
+        .with_fault(MockFault::Disconnect { after_chunks: 1 })
+        .with_api_key("key");
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, mut events, handle) = start(&server, fast_policy().with_max_attempts(2)).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnecting { .. })).await;
+
+    // Take the server down so every attempt fails
+    server.shutdown().await;
+
+    let failed = wait_for(&mut events, |e| {
+        matches!(e, ConnectionEvent::ReconnectFailed { .. })
+    })
+    .await;
+    assert!(matches!(
+        failed,
+        ConnectionEvent::ReconnectFailed { attempts: 2, .. }
+    ));
+
+    assert!(handle.await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_reconnect_with_wrong_key_is_not_retried() {
+    let config = MockServerConfig::new()
+        .with_api_key("secret")
+        .with_fault(MockFault::Disconnect { after_chunks: 1 });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let connection_config = server.connection_config(16000);
+
+    let connection = ScribeConnection::connect("secret", &connection_config)
+        .await
+        .unwrap();
+    let (audio_tx, audio_rx) = mpsc::channel(100);
+    let (event_tx, mut events) = mpsc::channel(100);
+
+    // The key used for reconnection is different from the one used initially
+    let handle = tokio::spawn(reconnecting_task(
+        connection,
+        "revoked".to_string(),
+        connection_config,
+        fast_policy(),
+        audio_rx,
+        event_tx,
+    ));
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    let failed = wait_for(&mut events, |e| {
+        matches!(e, ConnectionEvent::ReconnectFailed { .. })
+    })
+    .await;
+    assert!(matches!(
+        failed,
+        ConnectionEvent::ReconnectFailed { attempts: 1, .. }
+    ));
+    assert!(matches!(
+        handle.await.unwrap(),
+        Err(NetworkError::AuthenticationFailed)
+    ));
+}
+
+#[tokio::test]
+async fn test_quota_exceeded_is_not_retried() {
+    let config = MockServerConfig::new()
+        .with_fault(MockFault::Raw {
+            after_chunks: 1,
+            payload: r#"{"message_type":"quota_exceeded","error":"Quota exceeded"}"#.to_string(),
+        })
+        .with_fault(MockFault::Disconnect { after_chunks: 1 });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    let error = wait_for(&mut events, |e| {
+        matches!(e, ConnectionEvent::Message(message) if message.is_error())
+    })
+    .await;
+    assert!(matches!(
+        error,
+        ConnectionEvent::Message(ServerMessage::QuotaExceeded { .. })
+    ));
+
+    let result = tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("task did not end")
+        .unwrap();
+    assert!(matches!(result, Err(NetworkError::QuotaExceeded(_))));
+
+    while let Ok(event) = events.try_recv() {
+        assert!(!matches!(event, ConnectionEvent::Reconnecting { .. }));
+    }
+    assert_eq!(server.sessions().len(), 1);
+}
+
+#[tokio::test]
+async fn test_rate_limited_reconnects() {
+    let config = MockServerConfig::new()
+        .with_transcript("after the limit")
+        .with_chunks_per_token(1)
+        .with_fault(MockFault::Raw {
+            after_chunks: 1,
+            payload: r#"{"message_type":"rate_limited","error":"Too many requests"}"#.to_string(),
+        })
+        .with_fault(MockFault::Disconnect { after_chunks: 1 });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnected { .. })).await;
+
+    for _ in 0..2 {
+        audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    }
+    let event = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&event), Some("after the limit"));
+
+    drop(audio_tx);
+    handle.await.unwrap().unwrap();
+    assert_eq!(server.sessions().len(), 2);
+}
+
+#[tokio::test]
+async fn test_session_time_limit_reconnects_without_disconnect() {
+    // The server reports the limit but keeps the socket open
+    let config = MockServerConfig::new()
+        .with_transcript("next session")
+        .with_chunks_per_token(1)
+        .with_fault(MockFault::Raw {
+            after_chunks: 1,
+            payload: r#"{"message_type":"session_time_limit_exceeded","error":"Limit"}"#
+                .to_string(),
+        });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    wait_for(&mut events, |e| matches!(e, ConnectionEvent::Reconnected { .. })).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    let event = wait_for(&mut events, |e| committed(e).is_some()).await;
+    assert_eq!(committed(&event), Some("next session"));
+
+    drop(audio_tx);
+    handle.await.unwrap().unwrap();
+    assert_eq!(server.sessions().len(), 2);
+}
+
+#[tokio::test]
+async fn test_rate_limited_without_reconnect_returns_error() {
+    let config = MockServerConfig::new().with_fault(MockFault::Raw {
+        after_chunks: 1,
+        payload: r#"{"message_type":"rate_limited","error":"Too many requests"}"#.to_string(),
+    });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, _events, handle) = start(&server, ReconnectPolicy::disabled()).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("task did not end")
+        .unwrap();
+    assert!(matches!(result, Err(NetworkError::RateLimited(_))));
+    assert_eq!(server.sessions().len(), 1);
+}
+
+#[tokio::test]
+async fn test_reconnect_disabled_ends_stream() {
+    let config = MockServerConfig::new().with_fault(MockFault::Disconnect { after_chunks: 1 });
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, mut events, handle) = start(&server, ReconnectPolicy::disabled()).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+
+    let result = tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .expect("task did not end")
+        .unwrap();
+    assert!(matches!(result, Err(NetworkError::ConnectionFailed(_))));
+
+    while let Ok(event) = events.try_recv() {
+        assert!(matches!(
+            event,
+            ConnectionEvent::Message(ServerMessage::SessionStarted { .. })
+        ));
+    }
+    assert_eq!(server.sessions().len(), 1);
+}
+
+#[tokio::test]
+async fn test_normal_close_does_not_reconnect() {
+    let config = MockServerConfig::new()
+        .with_transcript("hello")
+        .with_chunks_per_token(1);
+    let server = MockScribeServer::start(config).await.unwrap();
+    let (audio_tx, mut events, handle) = start(&server, fast_policy()).await;
+
+    audio_tx.send(pcm_chunk(1000)).await.unwrap();
+    wait_for(&mut events, |e| committed(e).is_some()).await;
+
+    drop(audio_tx);
+    handle.await.unwrap().unwrap();
+
+    while let Some(event) = events.recv().await {
+        assert!(!matches!(event, ConnectionEvent::Reconnecting { .. }));
+    }
+    assert_eq!(server.sessions().len(), 1);
+}
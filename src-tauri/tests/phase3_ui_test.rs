@@ -210,10 +210,12 @@ fn test_api_config_default() {
 fn test_api_config_serialization() {
     let config = ApiConfig {
         api_key: "sk-test".to_string(),
+        base_url: "ws://127.0.0.1:9000/realtime".to_string(),
         model_id: "scribe_v2_realtime".to_string(),
         language_code: Some("ja".to_string()),
         include_timestamps: true,
         vad_commit_strategy: Some("auto".to_string()),
+        commit_timeout_ms: 1500,
     };
 
     let json = serde_json::to_string(&config).unwrap();
@@ -222,7 +224,9 @@ fn test_api_config_serialization() {
 
     let deserialized: ApiConfig = serde_json::from_str(&json).unwrap();
     assert_eq!(config.api_key, deserialized.api_key);
+    assert_eq!(config.base_url, deserialized.base_url);
     assert_eq!(config.language_code, deserialized.language_code);
+    assert_eq!(config.commit_timeout_ms, deserialized.commit_timeout_ms);
 }
 
 // ==================== AudioConfig 测试 ====================
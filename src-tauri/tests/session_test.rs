@@ -342,9 +342,14 @@ fn test_raflow_session_methods_exist() {
             let _result = RaFlowSession::start(app, "api-key", config).await;
         }
 
-        // stop 方法（async）
+        // stop 方法（async），返回本次会话的最终文本
         async fn _stop(session: &mut RaFlowSession) {
-            let _result = session.stop().await;
+            let _result: Result<Option<String>, _> = session.stop().await;
+        }
+
+        // cancel 方法（async）
+        async fn _cancel(session: &mut RaFlowSession) {
+            let _result = session.cancel().await;
         }
 
         // is_running 方法
@@ -376,6 +381,90 @@ fn test_raflow_session_methods_exist() {
     }
 }
 
+#[test]
+fn test_session_controller_methods_exist() {
+    // 验证 SessionController 的方法签名存在
+    use raflow_lib::session::{SessionConfig, SessionController, SessionControllerError};
+    use raflow_lib::transcription::TranscriptionOptions;
+
+    fn _verify_methods(app: &tauri::AppHandle) {
+        // new 方法
+        fn _new(app: &tauri::AppHandle) -> SessionController {
+            SessionController::new(app.clone())
+        }
+
+        // start_session / start_from_config 方法（async）
+        async fn _start(controller: &SessionController) {
+            let options = TranscriptionOptions::new("api-key");
+            let _result = controller
+                .start_session(options, SessionConfig::default())
+                .await;
+            let _result = controller.start_from_config().await;
+        }
+
+        // stop_session 方法（async）
+        async fn _stop(controller: &SessionController) {
+            let _result: Result<Option<String>, SessionControllerError> =
+                controller.stop_session().await;
+        }
+
+        // cancel_session 方法（async）
+        async fn _cancel(controller: &SessionController) {
+            let _result = controller.cancel_session().await;
+        }
+
+        // is_active 方法
+        fn _is_active(controller: &SessionController) {
+            let _active = controller.is_active();
+        }
+
+        let _ = app;
+    }
+}
+
+#[test]
+fn test_session_controller_is_send_sync() {
+    // 控制器需要注册为 Tauri 状态并在热键回调中共享
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<raflow_lib::session::SessionController>();
+}
+
+#[test]
+fn test_session_config_from_behavior_config() {
+    use raflow_lib::state::BehaviorConfig;
+
+    let behavior = BehaviorConfig {
+        injection_strategy: InjectionStrategy::ClipboardOnly,
+        auto_inject: false,
+        ..Default::default()
+    };
+    let config = SessionConfig::from(&behavior);
+
+    assert_eq!(config.injection_strategy, InjectionStrategy::ClipboardOnly);
+    assert!(!config.auto_inject);
+    assert_eq!(config.auto_threshold, behavior.auto_threshold);
+}
+
+#[test]
+fn test_session_event_cancelled_tagged_format() {
+    let event = SessionEvent::Cancelled;
+    let json = serde_json::to_string(&event).unwrap();
+
+    assert!(json.contains(r#""type":"Cancelled""#));
+}
+
+#[test]
+fn test_session_event_reconnecting_tagged_format() {
+    let event = SessionEvent::Reconnecting { attempt: 2 };
+    let json = serde_json::to_string(&event).unwrap();
+
+    assert!(json.contains(r#""type":"Reconnecting""#));
+    assert!(json.contains(r#""attempt":2"#));
+
+    let json = serde_json::to_string(&SessionEvent::Reconnected).unwrap();
+    assert!(json.contains(r#""type":"Reconnected""#));
+}
+
 // ==================== 状态集成测试 ====================
 
 #[test]
@@ -320,6 +320,12 @@ async fn test_app_state_helper_methods() {
         Some("hello")
     );
 
+    let reconnecting = AppState::reconnecting(3);
+    assert_eq!(reconnecting.name(), "Reconnecting");
+    assert!(reconnecting.is_reconnecting());
+    assert!(!reconnecting.is_recording());
+    assert_eq!(reconnecting.reconnect_attempt(), Some(3));
+
     let error = AppState::error("test error");
     assert_eq!(error.name(), "Error");
     assert!(error.is_error());
@@ -3,6 +3,7 @@
 /// This test file validates the complete transcription session,
 /// integrating audio pipeline and network communication.
 
+use raflow_lib::audio::AudioLevel;
 use raflow_lib::transcription::{TranscriptEvent, TranscriptionSession};
 use std::sync::{Arc, Mutex};
 use tokio::sync::mpsc;
@@ -301,6 +302,21 @@ fn test_event_pattern_matching() {
         TranscriptEvent::Error {
             message: "e1".to_string(),
         },
+        TranscriptEvent::Reconnecting { attempt: 1 },
+        TranscriptEvent::Reconnected,
+        TranscriptEvent::SpeechStarted,
+        TranscriptEvent::SpeechEnded,
+        TranscriptEvent::SilenceTimeout { silence_ms: 5000 },
+        TranscriptEvent::AudioLevel {
+            level: AudioLevel::measure(&[0.5, -0.5]),
+        },
+        TranscriptEvent::DeviceLost {
+            message: "d1".to_string(),
+        },
+        TranscriptEvent::DeviceSwitched {
+            device: "d2".to_string(),
+            message: "d1".to_string(),
+        },
         TranscriptEvent::Closed,
     ];
 
@@ -312,6 +328,16 @@ fn test_event_pattern_matching() {
             TranscriptEvent::Partial { text } => format!("Partial: {}", text),
             TranscriptEvent::Committed { text } => format!("Committed: {}", text),
             TranscriptEvent::Error { message } => format!("Error: {}", message),
+            TranscriptEvent::Reconnecting { attempt } => format!("Reconnecting: {}", attempt),
+            TranscriptEvent::Reconnected => "Reconnected".to_string(),
+            TranscriptEvent::SpeechStarted => "Speech started".to_string(),
+            TranscriptEvent::SpeechEnded => "Speech ended".to_string(),
+            TranscriptEvent::SilenceTimeout { silence_ms } => {
+                format!("Silence timeout: {} ms", silence_ms)
+            }
+            TranscriptEvent::AudioLevel { level } => format!("Level: {:.2}", level.rms),
+            TranscriptEvent::DeviceLost { message } => format!("Device lost: {}", message),
+            TranscriptEvent::DeviceSwitched { device, .. } => format!("Switched to: {}", device),
             TranscriptEvent::Closed => "Closed".to_string(),
         };
 
@@ -362,9 +388,9 @@ async fn test_e2e_transcription_with_real_api() {
     tokio::time::sleep(tokio::time::Duration::from_secs(10)).await;
 
     println!("Stopping session...");
-    session.stop().await.unwrap();
+    let final_text = session.stop().await.unwrap();
 
-    println!("Session stopped");
+    println!("Session stopped, final text: {:?}", final_text);
 
     // Check results
     let captured = events.lock().unwrap();
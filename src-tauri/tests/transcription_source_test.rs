@@ -0,0 +1,143 @@
+//! Headless end-to-end tests: synthetic and file audio sources through the
+//! full transcription session against the local mock Scribe server
+//!
+//! Run with: `cargo test --features mock-server --test transcription_source_test`
+
+#![cfg(feature = "mock-server")]
+
+use std::io::Cursor;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use raflow_lib::audio::{AudioSource, FileSource, Pacing, PcmFormat, Signal, SyntheticSource};
+use raflow_lib::network::mock::{MockScribeServer, MockServerConfig};
+use raflow_lib::transcription::{
+    ArchiveOptions, ArchiveSidecar, TranscriptEvent, TranscriptionOptions, TranscriptionSession,
+};
+use tokio::sync::mpsc;
+
+fn options(server: &MockScribeServer) -> TranscriptionOptions {
+    TranscriptionOptions::new("test-key").with_base_url(server.url())
+}
+
+/// Run a session on `source` until it closes and return its events and final text
+async fn transcribe(
+    options: TranscriptionOptions,
+    source: Box<dyn AudioSource>,
+) -> (Vec<TranscriptEvent>, Option<String>) {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let (closed_tx, mut closed_rx) = mpsc::unbounded_channel();
+    let recorded = Arc::clone(&events);
+
+    let mut session = TranscriptionSession::start_with_source(&options, source, move |event| {
+        if event == TranscriptEvent::Closed {
+            let _ = closed_tx.send(());
+        }
+        recorded.lock().unwrap().push(event);
+    })
+    .await
+    .unwrap();
+
+    // The session closes by itself once the source is exhausted
+    tokio::time::timeout(Duration::from_secs(10), closed_rx.recv())
+        .await
+        .expect("session did not close after the source ended");
+
+    let text = session.stop().await.unwrap();
+    let events = events.lock().unwrap().clone();
+    (events, text)
+}
+
+#[tokio::test]
+async fn test_synthetic_source_transcription() {
+    // Commit only when the stream is finished, so the script is not repeated
+    let config = MockServerConfig::new()
+        .with_transcript("hello world")
+        .with_chunks_per_token(3)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let source = SyntheticSource::new(48000)
+        .with_segment(
+            Signal::Tone {
+                frequency: 440.0,
+                amplitude: 0.5,
+            },
+            Duration::from_millis(800),
+        )
+        .with_segment(Signal::Silence, Duration::from_millis(200))
+        .with_pacing(Pacing::Fast);
+
+    let (events, text) = transcribe(options(&server), Box::new(source)).await;
+
+    assert!(events.contains(&TranscriptEvent::SessionStarted {
+        session_id: "mock-session-1".to_string()
+    }));
+    assert!(events.contains(&TranscriptEvent::Committed {
+        text: "hello world".to_string()
+    }));
+    assert_eq!(events.last(), Some(&TranscriptEvent::Closed));
+    assert_eq!(text, Some("hello world".to_string()));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_raw_pcm_source_transcription() {
+    let config = MockServerConfig::new()
+        .with_transcript("from a file")
+        .with_chunks_per_token(3)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    // One second of a 16 kHz square wave, as a raw s16le stream
+    let pcm: Vec<u8> = (0..16000)
+        .map(|i| if (i / 20) % 2 == 0 { 8000i16 } else { -8000 })
+        .flat_map(|s| s.to_le_bytes())
+        .collect();
+    let source = FileSource::from_raw_reader(Cursor::new(pcm), PcmFormat::new(16000, 1))
+        .with_pacing(Pacing::Fast);
+
+    let (_, text) = transcribe(options(&server), Box::new(source)).await;
+
+    assert_eq!(text, Some("from a file".to_string()));
+
+    server.shutdown().await;
+}
+
+#[tokio::test]
+async fn test_session_archive() {
+    let config = MockServerConfig::new()
+        .with_transcript("archived")
+        .with_chunks_per_token(3)
+        .without_auto_commit();
+    let server = MockScribeServer::start(config).await.unwrap();
+
+    let dir = std::env::temp_dir().join(format!("raflow-archive-e2e-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let source = SyntheticSource::new(16000)
+        .with_segment(Signal::Noise { amplitude: 0.3 }, Duration::from_millis(500))
+        .with_pacing(Pacing::Fast);
+    let options = options(&server).with_archive(ArchiveOptions::new(&dir));
+
+    let (_, text) = transcribe(options, Box::new(source)).await;
+    assert_eq!(text, Some("archived".to_string()));
+
+    // The recording and sidecar are named by the server's session ID
+    let wav = std::fs::read(dir.join("mock-session-1.wav")).unwrap();
+    assert_eq!(&wav[0..4], b"RIFF");
+    assert!(wav.len() > 44 + 7000 * 2, "{} bytes", wav.len());
+
+    let json = std::fs::read_to_string(dir.join("mock-session-1.json")).unwrap();
+    let sidecar: ArchiveSidecar = serde_json::from_str(&json).unwrap();
+    assert_eq!(sidecar.session_id, Some("mock-session-1".to_string()));
+    assert_eq!(sidecar.audio_file, "mock-session-1.wav");
+    assert_eq!(sidecar.sample_rate, 16000);
+    assert!(!sidecar.partials.is_empty());
+    assert_eq!(sidecar.committed.last().unwrap().text, "archived");
+    assert_eq!(sidecar.text, Some("archived".to_string()));
+
+    std::fs::remove_dir_all(&dir).unwrap();
+    server.shutdown().await;
+}
@@ -9,6 +9,7 @@ const statusConfig: Record<OverlayStatus, { label: string; className: string }>
   idle: { label: "Ready", className: "status-idle" },
   connecting: { label: "Connecting", className: "status-connecting" },
   recording: { label: "Recording", className: "status-recording" },
+  reconnecting: { label: "Reconnecting", className: "status-reconnecting" },
   processing: { label: "Processing", className: "status-processing" },
   injecting: { label: "Injecting", className: "status-injecting" },
   error: { label: "Error", className: "status-error" },
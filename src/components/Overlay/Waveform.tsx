@@ -2,10 +2,11 @@ import { useMemo, useEffect, useState } from "react";
 
 interface WaveformProps {
   level: number; // 0-1
+  clipping?: boolean;
   active: boolean;
 }
 
-export function Waveform({ level, active }: WaveformProps) {
+export function Waveform({ level, clipping = false, active }: WaveformProps) {
   const [animatedLevel, setAnimatedLevel] = useState(0);
 
   // Smooth animation for audio level
@@ -34,7 +35,12 @@ export function Waveform({ level, active }: WaveformProps) {
   }, [animatedLevel]);
 
   return (
-    <div className={`waveform ${active ? "waveform-active" : ""}`}>
+    <div
+      className={`waveform ${active ? "waveform-active" : ""} ${
+        active && clipping ? "waveform-clipping" : ""
+      }`}
+      title={active && clipping ? "Input is clipping" : undefined}
+    >
       {bars.map((height, i) => (
         <div
           key={i}
@@ -4,13 +4,21 @@ import { Waveform } from "./Waveform";
 import { TranscriptDisplay } from "./TranscriptDisplay";
 import { StatusIndicator } from "./StatusIndicator";
 
-export type OverlayStatus = "idle" | "connecting" | "recording" | "processing" | "injecting" | "error";
+export type OverlayStatus =
+  | "idle"
+  | "connecting"
+  | "recording"
+  | "reconnecting"
+  | "processing"
+  | "injecting"
+  | "error";
 
 interface OverlayState {
   status: OverlayStatus;
   partialText: string;
   finalText: string;
   audioLevel: number;
+  isClipping: boolean;
   errorMessage: string;
   isTranscribing: boolean;
 }
@@ -28,7 +36,10 @@ interface PartialTranscriptEvent {
 }
 
 interface AudioLevelEvent {
+  rms: number;
+  peak: number;
   level: number;
+  clipping: boolean;
 }
 
 interface SessionEventPayload {
@@ -47,6 +58,7 @@ export function Overlay() {
     partialText: "",
     finalText: "",
     audioLevel: 0,
+    isClipping: false,
     errorMessage: "",
     isTranscribing: false,
   });
@@ -59,6 +71,8 @@ export function Overlay() {
         return "connecting";
       case "recording":
         return "recording";
+      case "reconnecting":
+        return "reconnecting";
       case "processing":
         return "processing";
       case "injecting":
@@ -134,6 +148,20 @@ export function Overlay() {
             }
             break;
 
+          case "Reconnecting":
+            setState((prev) => ({
+              ...prev,
+              status: "reconnecting",
+            }));
+            break;
+
+          case "Reconnected":
+            setState((prev) => ({
+              ...prev,
+              status: "recording",
+            }));
+            break;
+
           case "TextInjected":
           case "TextCopied":
             setState((prev) => ({
@@ -143,6 +171,7 @@ export function Overlay() {
             break;
 
           case "Stopped":
+          case "Cancelled":
             setState((prev) => ({
               ...prev,
               status: "idle",
@@ -168,6 +197,7 @@ export function Overlay() {
         setState((prev) => ({
           ...prev,
           audioLevel: event.payload.level,
+          isClipping: event.payload.clipping,
         }));
       })
     );
@@ -181,6 +211,8 @@ export function Overlay() {
           partialText: "",
           finalText: "",
           errorMessage: "",
+          audioLevel: 0,
+          isClipping: false,
         }));
       })
     );
@@ -202,7 +234,11 @@ export function Overlay() {
     <div className="overlay">
       <div className="overlay-header">
         <StatusIndicator status={state.status} isTranscribing={state.isTranscribing} />
-        <Waveform level={state.audioLevel} active={state.status === "recording"} />
+        <Waveform
+          level={state.audioLevel}
+          clipping={state.isClipping}
+          active={state.status === "recording"}
+        />
       </div>
       <div className="overlay-content">
         <TranscriptDisplay
@@ -1,29 +1,67 @@
 import { useState, useEffect, useCallback } from "react";
 import { invoke } from "@tauri-apps/api/core";
+import { listen } from "@tauri-apps/api/event";
 
 interface AppConfig {
   api: ApiConfig;
   audio: AudioConfig;
   hotkeys: HotkeyConfig;
   behavior: BehaviorConfig;
+  archive: ArchiveConfig;
+  processing: ProcessingConfig;
+  voice_commands: VoiceCommandConfig;
 }
 
 interface ApiConfig {
   api_key: string;
+  base_url: string;
   model_id: string;
   language_code: string | null;
   include_timestamps: boolean;
   vad_commit_strategy: string | null;
+  commit_timeout_ms: number;
 }
 
 interface AudioConfig {
   input_device_id: string | null;
   input_device_name: string | null;
+  preferred_device_ids: string[];
+  input_channel: number | null;
+  device_failover: boolean;
   gain: number;
+  agc_enabled: boolean;
+  agc_target_level: number;
   noise_suppression: boolean;
   silence_threshold: number;
+  vad_enabled: boolean;
+  vad_hangover_ms: number;
+  vad_pre_roll_ms: number;
+  warm_microphone: boolean;
+  warm_pre_roll_ms: number;
 }
 
+interface AudioDevice {
+  id: string;
+  name: string;
+  is_default: boolean;
+  sample_rates: number[];
+}
+
+type MatchKind = "exact" | "name" | "fuzzy" | "preferred" | "default";
+
+interface DeviceMatch {
+  device: AudioDevice;
+  kind: MatchKind;
+}
+
+const MATCH_KIND_LABELS: Record<MatchKind, string> = {
+  exact: "selected device",
+  name: "matched by name",
+  fuzzy: "closest name match",
+  preferred: "preferred device",
+  default: "system default",
+};
+
 interface HotkeyConfig {
   push_to_talk: string;
   cancel: string;
@@ -40,9 +78,102 @@ interface BehaviorConfig {
   auto_start: boolean;
   minimize_to_tray: boolean;
   processing_timeout_secs: number;
+  auto_stop_silence_secs: number;
+  disfluency: DisfluencyLevel;
+}
+
+type DisfluencyLevel = "off" | "light" | "strict";
+
+interface ArchiveConfig {
+  enabled: boolean;
+  directory: string | null;
+  max_recordings: number;
+  max_age_days: number;
+  max_total_mb: number;
+}
+
+type ProcessingStage =
+  | "trim"
+  | "disfluency"
+  | "whitespace"
+  | "replacements"
+  | "cjk"
+  | "trailing_punctuation"
+  | "capitalization";
+
+type PunctuationWidth = "keep" | "full_width" | "half_width";
+
+type ScriptConversion = "keep" | "simplified" | "traditional";
+
+interface CjkOptions {
+  punctuation: PunctuationWidth;
+  spacing: boolean;
+  script: ScriptConversion;
+}
+
+type MatchMode = "literal" | "whole_word" | "regex";
+
+interface ReplacementRule {
+  pattern: string;
+  replacement: string;
+  mode: MatchMode;
+  case_sensitive: boolean;
+  enabled: boolean;
 }
 
-type TabId = "api" | "audio" | "hotkeys" | "behavior";
+interface ProcessingConfig {
+  enabled: boolean;
+  stages: ProcessingStage[];
+  trailing_punctuation: "keep" | "remove" | "ensure";
+  capitalization: "keep" | "sentence" | "lower";
+  replacements: ReplacementRule[];
+  cjk: Record<string, CjkOptions>;
+}
+
+type KeyAction = "enter" | "tab" | "backspace" | "select_all" | "delete_that" | "undo";
+
+type VoiceAction = KeyAction | { insert: string };
+
+interface VoiceCommand {
+  phrase: string;
+  action: VoiceAction;
+}
+
+interface VoiceCommandConfig {
+  enabled: boolean;
+  commands: VoiceCommand[];
+}
+
+const KEY_ACTION_LABELS: Record<KeyAction, string> = {
+  enter: "Press Enter",
+  tab: "Press Tab",
+  backspace: "Press Backspace",
+  select_all: "Select all",
+  delete_that: "Delete previous text",
+  undo: "Undo",
+};
+
+function describeAction(action: VoiceAction): string {
+  return typeof action === "string" ? KEY_ACTION_LABELS[action] : `Insert "${action.insert}"`;
+}
+
+const MATCH_MODE_LABELS: Record<MatchMode, string> = {
+  literal: "Anywhere",
+  whole_word: "Whole word",
+  regex: "Regex",
+};
+
+const STAGE_LABELS: Record<ProcessingStage, string> = {
+  trim: "Trim leading and trailing spaces",
+  disfluency: "Remove filler words and repetitions",
+  whitespace: "Collapse repeated spaces",
+  replacements: "Replacement dictionary",
+  cjk: "Chinese/Japanese punctuation, spacing and script",
+  trailing_punctuation: "Trailing punctuation",
+  capitalization: "Capitalization",
+};
+
+type TabId = "api" | "audio" | "hotkeys" | "behavior" | "text" | "archive";
 
 interface TabConfig {
   id: TabId;
@@ -54,6 +185,8 @@ const tabs: TabConfig[] = [
   { id: "audio", label: "Audio" },
   { id: "hotkeys", label: "Hotkeys" },
   { id: "behavior", label: "Behavior" },
+  { id: "text", label: "Text" },
+  { id: "archive", label: "Recordings" },
 ];
 
 export function Settings() {
@@ -164,6 +297,24 @@ export function Settings() {
             onChange={(key, value) => updateConfig("behavior", key, value)}
           />
         )}
+        {activeTab === "text" && (
+          <>
+            <TextSettings
+              config={config.processing}
+              onChange={(key, value) => updateConfig("processing", key, value)}
+            />
+            <VoiceCommandSettings
+              config={config.voice_commands}
+              onChange={(key, value) => updateConfig("voice_commands", key, value)}
+            />
+          </>
+        )}
+        {activeTab === "archive" && (
+          <ArchiveSettings
+            config={config.archive}
+            onChange={(key, value) => updateConfig("archive", key, value)}
+          />
+        )}
       </main>
 
       <footer className="settings-footer">
@@ -208,6 +359,20 @@ function ApiSettings({ config, onChange }: SectionProps<ApiConfig>) {
         </p>
       </div>
 
+      <div className="form-group">
+        <label htmlFor="base-url">Endpoint URL</label>
+        <input
+          id="base-url"
+          type="text"
+          value={config.base_url}
+          onChange={(e) => onChange("base_url", e.target.value)}
+          placeholder="wss://api.elevenlabs.io/v1/speech-to-text/realtime"
+        />
+        <p className="form-help">
+          WebSocket endpoint (wss:// or ws://). Leave empty to use ElevenLabs.
+        </p>
+      </div>
+
       <div className="form-group">
         <label htmlFor="language">Language</label>
         <select
@@ -236,15 +401,143 @@ function ApiSettings({ config, onChange }: SectionProps<ApiConfig>) {
           <span>Include word timestamps</span>
         </label>
       </div>
+
+      <div className="form-group">
+        <label htmlFor="commit-timeout">Final Transcript Timeout</label>
+        <div className="range-group">
+          <input
+            id="commit-timeout"
+            type="range"
+            min="500"
+            max="5000"
+            step="250"
+            value={config.commit_timeout_ms}
+            onChange={(e) => onChange("commit_timeout_ms", parseInt(e.target.value))}
+          />
+          <span className="range-value">{config.commit_timeout_ms} ms</span>
+        </div>
+        <p className="form-help">
+          How long to wait for the last words after releasing the hotkey.
+        </p>
+      </div>
     </section>
   );
 }
 
 function AudioSettings({ config, onChange }: SectionProps<AudioConfig>) {
+  const [devices, setDevices] = useState<AudioDevice[]>([]);
+  const [selected, setSelected] = useState<DeviceMatch | null>(null);
+
+  // Load the device list and follow plug/unplug events
+  useEffect(() => {
+    const loadSelected = () =>
+      invoke<DeviceMatch | null>("get_selected_input_device")
+        .then(setSelected)
+        .catch(() => setSelected(null));
+
+    invoke<AudioDevice[]>("list_audio_devices")
+      .then(setDevices)
+      .catch(() => setDevices([]));
+    loadSelected();
+
+    const unlisten = listen<AudioDevice[]>("audio:devices_changed", (event) => {
+      setDevices(event.payload);
+      loadSelected();
+    });
+    return () => {
+      unlisten.then((fn) => fn());
+    };
+  }, []);
+
+  const selectedMissing =
+    config.input_device_id !== null && !devices.some((d) => d.id === config.input_device_id);
+
   return (
     <section className="settings-section">
       <h2>Audio Settings</h2>
 
+      <div className="form-group">
+        <label htmlFor="input-device">Input Device</label>
+        <select
+          id="input-device"
+          value={config.input_device_id ?? ""}
+          onChange={(e) => {
+            const device = devices.find((d) => d.id === e.target.value);
+            onChange("input_device_id", device ? device.id : null);
+            onChange("input_device_name", device ? device.name : null);
+          }}
+        >
+          <option value="">System default</option>
+          {devices.map((device) => (
+            <option key={device.id} value={device.id}>
+              {device.name}
+              {device.is_default ? " (default)" : ""}
+            </option>
+          ))}
+          {selectedMissing && (
+            <option value={config.input_device_id!}>
+              {config.input_device_name || config.input_device_id} (disconnected)
+            </option>
+          )}
+        </select>
+        {selected && (
+          <p className="form-help">
+            In use: {selected.device.name} ({MATCH_KIND_LABELS[selected.kind]})
+          </p>
+        )}
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="preferred-devices">Preferred Devices</label>
+        <textarea
+          id="preferred-devices"
+          rows={3}
+          value={config.preferred_device_ids.join("\n")}
+          onChange={(e) => onChange("preferred_device_ids", e.target.value.split("\n"))}
+          placeholder="One device name per line"
+        />
+        <p className="form-help">
+          Tried in order when the selected device can't be found, even after a rename, and when
+          it is disconnected while recording if switching devices is enabled.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.device_failover}
+            onChange={(e) => onChange("device_failover", e.target.checked)}
+          />
+          <span>Switch devices if the microphone is disconnected</span>
+        </label>
+        <p className="form-help">
+          Keeps recording on another device instead of ending the session. Preferred devices are
+          tried in order, then the system default.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="input-channel">Input Channel</label>
+        <select
+          id="input-channel"
+          value={config.input_channel ?? ""}
+          onChange={(e) =>
+            onChange("input_channel", e.target.value === "" ? null : parseInt(e.target.value))
+          }
+        >
+          <option value="">Mix all channels</option>
+          {Array.from({ length: 8 }, (_, i) => (
+            <option key={i} value={i}>
+              Channel {i + 1}
+            </option>
+          ))}
+        </select>
+        <p className="form-help">
+          For multi-channel audio interfaces, pick the input your microphone is plugged into.
+        </p>
+      </div>
+
       <div className="form-group">
         <label htmlFor="gain">Volume Gain</label>
         <div className="range-group">
@@ -259,6 +552,38 @@ function AudioSettings({ config, onChange }: SectionProps<AudioConfig>) {
           />
           <span className="range-value">{config.gain.toFixed(1)}x</span>
         </div>
+        <p className="form-help">Changes take effect on the next recording.</p>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.agc_enabled}
+            onChange={(e) => onChange("agc_enabled", e.target.checked)}
+          />
+          <span>Automatic gain control</span>
+        </label>
+        <p className="form-help">
+          Boosts quiet microphones towards the target level and limits peaks to avoid clipping.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="agc-target">AGC Target Level</label>
+        <div className="range-group">
+          <input
+            id="agc-target"
+            type="range"
+            min="0.05"
+            max="0.3"
+            step="0.01"
+            value={config.agc_target_level}
+            disabled={!config.agc_enabled}
+            onChange={(e) => onChange("agc_target_level", parseFloat(e.target.value))}
+          />
+          <span className="range-value">{(config.agc_target_level * 100).toFixed(0)}%</span>
+        </div>
       </div>
 
       <div className="form-group">
@@ -277,6 +602,39 @@ function AudioSettings({ config, onChange }: SectionProps<AudioConfig>) {
         </div>
       </div>
 
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.vad_enabled}
+            onChange={(e) => onChange("vad_enabled", e.target.checked)}
+          />
+          <span>Skip silence while recording</span>
+        </label>
+        <p className="form-help">
+          Audio below the silence threshold is not streamed, reducing bandwidth and API usage.
+          Off by default: with a quiet microphone, soft speech may be dropped.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="vad-hangover">Speech Hangover</label>
+        <div className="range-group">
+          <input
+            id="vad-hangover"
+            type="range"
+            min="200"
+            max="2000"
+            step="100"
+            value={config.vad_hangover_ms}
+            disabled={!config.vad_enabled}
+            onChange={(e) => onChange("vad_hangover_ms", parseInt(e.target.value))}
+          />
+          <span className="range-value">{config.vad_hangover_ms} ms</span>
+        </div>
+        <p className="form-help">How long to keep streaming after you stop speaking.</p>
+      </div>
+
       <div className="form-group">
         <label className="checkbox-label">
           <input
@@ -284,8 +642,45 @@ function AudioSettings({ config, onChange }: SectionProps<AudioConfig>) {
             checked={config.noise_suppression}
             onChange={(e) => onChange("noise_suppression", e.target.checked)}
           />
-          <span>Enable noise suppression (experimental)</span>
+          <span>Enable noise suppression</span>
         </label>
+        <p className="form-help">
+          Learns steady background noise (fans, office hum) and removes it. Takes effect on the
+          next recording.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.warm_microphone}
+            onChange={(e) => onChange("warm_microphone", e.target.checked)}
+          />
+          <span>Keep microphone warm</span>
+        </label>
+        <p className="form-help">
+          Keeps the microphone open between recordings so the first word is never cut off. Your
+          operating system will show the microphone as in use.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="warm-pre-roll">Pre-Roll</label>
+        <div className="range-group">
+          <input
+            id="warm-pre-roll"
+            type="range"
+            min="100"
+            max="2000"
+            step="100"
+            value={config.warm_pre_roll_ms}
+            disabled={!config.warm_microphone}
+            onChange={(e) => onChange("warm_pre_roll_ms", parseInt(e.target.value))}
+          />
+          <span className="range-value">{config.warm_pre_roll_ms} ms</span>
+        </div>
+        <p className="form-help">Audio from just before the hotkey press that is included.</p>
       </div>
     </section>
   );
@@ -393,6 +788,616 @@ function BehaviorSettings({ config, onChange }: SectionProps<BehaviorConfig>) {
           <span>Minimize to system tray</span>
         </label>
       </div>
+
+      <div className="form-group">
+        <label htmlFor="auto-stop-silence">Auto-Stop After Silence</label>
+        <div className="range-group">
+          <input
+            id="auto-stop-silence"
+            type="range"
+            min="0"
+            max="60"
+            step="5"
+            value={config.auto_stop_silence_secs}
+            onChange={(e) => onChange("auto_stop_silence_secs", parseInt(e.target.value))}
+          />
+          <span className="range-value">
+            {config.auto_stop_silence_secs === 0 ? "Off" : `${config.auto_stop_silence_secs} s`}
+          </span>
+        </div>
+        <p className="form-help">
+          Stop and transcribe automatically when nothing is said for this long.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="disfluency">Filler Words</label>
+        <select
+          id="disfluency"
+          value={config.disfluency}
+          onChange={(e) => onChange("disfluency", e.target.value as DisfluencyLevel)}
+        >
+          <option value="off">Keep as transcribed</option>
+          <option value="light">Remove "um", "uh", "嗯" and repeated words</option>
+          <option value="strict">Also remove "you know", "那个" and stutters</option>
+        </select>
+        <p className="form-help">
+          Text inside quotes is never changed. Strict mode may also merge intentional
+          repetitions such as "研究研究".
+        </p>
+      </div>
+    </section>
+  );
+}
+
+function TextSettings({ config, onChange }: SectionProps<ProcessingConfig>) {
+  const allStages = Object.keys(STAGE_LABELS) as ProcessingStage[];
+  const disabledStages = allStages.filter((stage) => !config.stages.includes(stage));
+
+  const toggleStage = (stage: ProcessingStage, enabled: boolean) => {
+    onChange(
+      "stages",
+      enabled ? [...config.stages, stage] : config.stages.filter((s) => s !== stage)
+    );
+  };
+
+  const moveUp = (index: number) => {
+    const stages = [...config.stages];
+    [stages[index - 1], stages[index]] = [stages[index], stages[index - 1]];
+    onChange("stages", stages);
+  };
+
+  return (
+    <section className="settings-section">
+      <h2>Text Processing</h2>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.enabled}
+            onChange={(e) => onChange("enabled", e.target.checked)}
+          />
+          <span>Clean up text before inserting it</span>
+        </label>
+        <p className="form-help">
+          Applied to the final transcript before it is typed, pasted or copied.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label>Steps (run top to bottom)</label>
+        <ul className="stage-list">
+          {config.stages.map((stage, index) => (
+            <li key={stage} className="stage-row">
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked
+                  disabled={!config.enabled}
+                  onChange={() => toggleStage(stage, false)}
+                />
+                <span>{STAGE_LABELS[stage]}</span>
+              </label>
+              <button
+                type="button"
+                className="stage-move"
+                disabled={!config.enabled || index === 0}
+                onClick={() => moveUp(index)}
+                aria-label={`Move ${STAGE_LABELS[stage]} up`}
+              >
+                ↑
+              </button>
+            </li>
+          ))}
+          {disabledStages.map((stage) => (
+            <li key={stage} className="stage-row">
+              <label className="checkbox-label">
+                <input
+                  type="checkbox"
+                  checked={false}
+                  disabled={!config.enabled}
+                  onChange={() => toggleStage(stage, true)}
+                />
+                <span>{STAGE_LABELS[stage]}</span>
+              </label>
+            </li>
+          ))}
+        </ul>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="trailing-punctuation">Trailing Punctuation</label>
+        <select
+          id="trailing-punctuation"
+          value={config.trailing_punctuation}
+          disabled={!config.enabled}
+          onChange={(e) =>
+            onChange(
+              "trailing_punctuation",
+              e.target.value as ProcessingConfig["trailing_punctuation"]
+            )
+          }
+        >
+          <option value="keep">Keep as transcribed</option>
+          <option value="remove">Remove final period or comma</option>
+          <option value="ensure">Always end with a period</option>
+        </select>
+        <p className="form-help">Question and exclamation marks are always kept.</p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="capitalization">Capitalization</label>
+        <select
+          id="capitalization"
+          value={config.capitalization}
+          disabled={!config.enabled}
+          onChange={(e) =>
+            onChange("capitalization", e.target.value as ProcessingConfig["capitalization"])
+          }
+        >
+          <option value="keep">Keep as transcribed</option>
+          <option value="sentence">Capitalize each sentence</option>
+          <option value="lower">All lowercase</option>
+        </select>
+      </div>
+
+      <ReplacementEditor
+        rules={config.replacements}
+        disabled={!config.enabled}
+        onChange={(rules) => onChange("replacements", rules)}
+      />
+
+      <CjkEditor
+        options={config.cjk}
+        disabled={!config.enabled}
+        onChange={(options) => onChange("cjk", options)}
+      />
+    </section>
+  );
+}
+
+interface CjkEditorProps {
+  options: Record<string, CjkOptions>;
+  disabled: boolean;
+  onChange: (options: Record<string, CjkOptions>) => void;
+}
+
+function CjkEditor({ options, disabled, onChange }: CjkEditorProps) {
+  const [language, setLanguage] = useState("");
+
+  const update = (code: string, value: CjkOptions) => {
+    onChange({ ...options, [code]: value });
+  };
+
+  const remove = (code: string) => {
+    onChange(Object.fromEntries(Object.entries(options).filter(([c]) => c !== code)));
+  };
+
+  const add = () => {
+    const code = language.trim().toLowerCase();
+    if (!code || code in options) return;
+    onChange({ ...options, [code]: { punctuation: "full_width", spacing: true, script: "keep" } });
+    setLanguage("");
+  };
+
+  return (
+    <div className="form-group">
+      <label>Chinese / Japanese Text</label>
+      <ul className="stage-list">
+        {Object.entries(options).map(([code, value]) => (
+          <li key={code} className="stage-row rule-form">
+            <span>{code}</span>
+            <select
+              value={value.punctuation}
+              disabled={disabled}
+              onChange={(e) =>
+                update(code, { ...value, punctuation: e.target.value as PunctuationWidth })
+              }
+              aria-label={`Punctuation for ${code}`}
+            >
+              <option value="keep">Keep punctuation</option>
+              <option value="full_width">Full-width punctuation</option>
+              <option value="half_width">Half-width punctuation</option>
+            </select>
+            <select
+              value={value.script}
+              disabled={disabled}
+              onChange={(e) =>
+                update(code, { ...value, script: e.target.value as ScriptConversion })
+              }
+              aria-label={`Script for ${code}`}
+            >
+              <option value="keep">Keep script</option>
+              <option value="simplified">Simplified</option>
+              <option value="traditional">Traditional</option>
+            </select>
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={value.spacing}
+                disabled={disabled}
+                onChange={(e) => update(code, { ...value, spacing: e.target.checked })}
+              />
+              <span>Space around Latin</span>
+            </label>
+            <button
+              type="button"
+              className="stage-move"
+              disabled={disabled}
+              onClick={() => remove(code)}
+              aria-label={`Remove ${code}`}
+            >
+              ×
+            </button>
+          </li>
+        ))}
+      </ul>
+      <div className="rule-form">
+        <input
+          type="text"
+          placeholder="Language code (e.g. zh-tw)"
+          value={language}
+          disabled={disabled}
+          onChange={(e) => setLanguage(e.target.value)}
+        />
+        <button
+          type="button"
+          className="stage-move"
+          disabled={disabled || !language.trim()}
+          onClick={add}
+        >
+          Add
+        </button>
+      </div>
+      <p className="form-help">
+        Settings are chosen by the transcription language; "auto" is used when the language is
+        detected automatically. Only text next to Chinese or Japanese characters is changed.
+      </p>
+    </div>
+  );
+}
+
+interface ReplacementEditorProps {
+  rules: ReplacementRule[];
+  disabled: boolean;
+  onChange: (rules: ReplacementRule[]) => void;
+}
+
+function ReplacementEditor({ rules, disabled, onChange }: ReplacementEditorProps) {
+  const [draft, setDraft] = useState<ReplacementRule>({
+    pattern: "",
+    replacement: "",
+    mode: "whole_word",
+    case_sensitive: false,
+    enabled: true,
+  });
+  const [sample, setSample] = useState("");
+  const [preview, setPreview] = useState("");
+  const [ruleError, setRuleError] = useState<string | null>(null);
+
+  // Preview the unsaved rules against the sample text
+  useEffect(() => {
+    if (!sample) {
+      setPreview("");
+      return;
+    }
+    invoke<string>("preview_replacements", { text: sample, rules })
+      .then((result) => {
+        setPreview(result);
+        setRuleError(null);
+      })
+      .catch((e) => setRuleError(String(e)));
+  }, [sample, rules]);
+
+  const addRule = async () => {
+    try {
+      // Validates the pattern without touching the saved config
+      await invoke<string>("preview_replacements", { text: "", rules: [draft] });
+      onChange([...rules, draft]);
+      setDraft({ ...draft, pattern: "", replacement: "" });
+      setRuleError(null);
+    } catch (e) {
+      setRuleError(String(e));
+    }
+  };
+
+  const updateRule = (index: number, rule: ReplacementRule) => {
+    onChange(rules.map((r, i) => (i === index ? rule : r)));
+  };
+
+  return (
+    <div className="form-group">
+      <label>Replacement Dictionary</label>
+      <ul className="stage-list">
+        {rules.map((rule, index) => (
+          <li key={index} className="stage-row">
+            <label className="checkbox-label">
+              <input
+                type="checkbox"
+                checked={rule.enabled}
+                disabled={disabled}
+                onChange={(e) => updateRule(index, { ...rule, enabled: e.target.checked })}
+              />
+              <span>
+                {rule.pattern} → {rule.replacement} ({MATCH_MODE_LABELS[rule.mode]}
+                {rule.case_sensitive ? ", case sensitive" : ""})
+              </span>
+            </label>
+            <button
+              type="button"
+              className="stage-move"
+              disabled={disabled}
+              onClick={() => onChange(rules.filter((_, i) => i !== index))}
+              aria-label={`Remove ${rule.pattern}`}
+            >
+              ×
+            </button>
+          </li>
+        ))}
+      </ul>
+
+      <div className="rule-form">
+        <input
+          type="text"
+          placeholder="Heard as"
+          value={draft.pattern}
+          disabled={disabled}
+          onChange={(e) => setDraft({ ...draft, pattern: e.target.value })}
+        />
+        <input
+          type="text"
+          placeholder="Replace with"
+          value={draft.replacement}
+          disabled={disabled}
+          onChange={(e) => setDraft({ ...draft, replacement: e.target.value })}
+        />
+        <select
+          value={draft.mode}
+          disabled={disabled}
+          onChange={(e) => setDraft({ ...draft, mode: e.target.value as MatchMode })}
+        >
+          {(Object.keys(MATCH_MODE_LABELS) as MatchMode[]).map((mode) => (
+            <option key={mode} value={mode}>
+              {MATCH_MODE_LABELS[mode]}
+            </option>
+          ))}
+        </select>
+        <button
+          type="button"
+          className="stage-move"
+          disabled={disabled || !draft.pattern}
+          onClick={addRule}
+        >
+          Add
+        </button>
+      </div>
+      <label className="checkbox-label">
+        <input
+          type="checkbox"
+          checked={draft.case_sensitive}
+          disabled={disabled}
+          onChange={(e) => setDraft({ ...draft, case_sensitive: e.target.checked })}
+        />
+        <span>Case sensitive</span>
+      </label>
+      <p className="form-help">
+        Rules run top to bottom. Regex replacements can use $1 or {"${name}"} for captured
+        groups.
+      </p>
+
+      <label htmlFor="replacement-preview">Try it</label>
+      <input
+        id="replacement-preview"
+        type="text"
+        placeholder="Type a sentence to preview"
+        value={sample}
+        onChange={(e) => setSample(e.target.value)}
+      />
+      {preview && <p className="form-help">Result: {preview}</p>}
+      {ruleError && <p className="form-help rule-error">{ruleError}</p>}
+    </div>
+  );
+}
+
+function VoiceCommandSettings({ config, onChange }: SectionProps<VoiceCommandConfig>) {
+  const [phrase, setPhrase] = useState("");
+  const [action, setAction] = useState<KeyAction | "insert">("enter");
+  const [insertText, setInsertText] = useState("");
+
+  const addCommand = () => {
+    const command: VoiceCommand = {
+      phrase: phrase.trim(),
+      action: action === "insert" ? { insert: insertText } : action,
+    };
+    onChange("commands", [...config.commands, command]);
+    setPhrase("");
+    setInsertText("");
+  };
+
+  return (
+    <section className="settings-section">
+      <h2>Voice Commands</h2>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.enabled}
+            onChange={(e) => onChange("enabled", e.target.checked)}
+          />
+          <span>Treat spoken commands as edits</span>
+        </label>
+        <p className="form-help">
+          Say "new line", "period" or "delete that" (or "换行", "句号", "删掉这句") on their own,
+          as a separate sentence, instead of having the words typed.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label>Phrases</label>
+        <ul className="stage-list">
+          {config.commands.map((command, index) => (
+            <li key={index} className="stage-row">
+              <span>
+                {command.phrase} → {describeAction(command.action)}
+              </span>
+              <button
+                type="button"
+                className="stage-move"
+                disabled={!config.enabled}
+                onClick={() =>
+                  onChange(
+                    "commands",
+                    config.commands.filter((_, i) => i !== index)
+                  )
+                }
+                aria-label={`Remove ${command.phrase}`}
+              >
+                ×
+              </button>
+            </li>
+          ))}
+        </ul>
+
+        <div className="rule-form">
+          <input
+            type="text"
+            placeholder="Spoken phrase"
+            value={phrase}
+            disabled={!config.enabled}
+            onChange={(e) => setPhrase(e.target.value)}
+          />
+          <select
+            value={action}
+            disabled={!config.enabled}
+            onChange={(e) => setAction(e.target.value as KeyAction | "insert")}
+          >
+            {(Object.keys(KEY_ACTION_LABELS) as KeyAction[]).map((key) => (
+              <option key={key} value={key}>
+                {KEY_ACTION_LABELS[key]}
+              </option>
+            ))}
+            <option value="insert">Insert text</option>
+          </select>
+          {action === "insert" && (
+            <input
+              type="text"
+              placeholder="Text to insert"
+              value={insertText}
+              disabled={!config.enabled}
+              onChange={(e) => setInsertText(e.target.value)}
+            />
+          )}
+          <button
+            type="button"
+            className="stage-move"
+            disabled={
+              !config.enabled || !phrase.trim() || (action === "insert" && !insertText)
+            }
+            onClick={addCommand}
+          >
+            Add
+          </button>
+        </div>
+        <p className="form-help">
+          "Delete previous text" removes the last inserted text with Backspace, so it only works
+          while the cursor is still right after it.
+        </p>
+      </div>
+    </section>
+  );
+}
+
+function ArchiveSettings({ config, onChange }: SectionProps<ArchiveConfig>) {
+  return (
+    <section className="settings-section">
+      <h2>Recording Archive</h2>
+
+      <div className="form-group">
+        <label className="checkbox-label">
+          <input
+            type="checkbox"
+            checked={config.enabled}
+            onChange={(e) => onChange("enabled", e.target.checked)}
+          />
+          <span>Save each recording with its transcript</span>
+        </label>
+        <p className="form-help">
+          Stores the audio as a WAV file next to a JSON file with the partial and final
+          transcripts. Useful for reporting misrecognitions.
+        </p>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="archive-directory">Folder</label>
+        <input
+          id="archive-directory"
+          type="text"
+          value={config.directory || ""}
+          disabled={!config.enabled}
+          onChange={(e) => onChange("directory", e.target.value || null)}
+          placeholder="Default (app data folder)"
+        />
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="archive-max-recordings">Keep At Most</label>
+        <div className="range-group">
+          <input
+            id="archive-max-recordings"
+            type="range"
+            min="0"
+            max="2000"
+            step="50"
+            value={config.max_recordings}
+            disabled={!config.enabled}
+            onChange={(e) => onChange("max_recordings", parseInt(e.target.value))}
+          />
+          <span className="range-value">
+            {config.max_recordings === 0 ? "No limit" : `${config.max_recordings} recordings`}
+          </span>
+        </div>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="archive-max-age">Delete After</label>
+        <div className="range-group">
+          <input
+            id="archive-max-age"
+            type="range"
+            min="0"
+            max="365"
+            step="1"
+            value={config.max_age_days}
+            disabled={!config.enabled}
+            onChange={(e) => onChange("max_age_days", parseInt(e.target.value))}
+          />
+          <span className="range-value">
+            {config.max_age_days === 0 ? "Never" : `${config.max_age_days} days`}
+          </span>
+        </div>
+      </div>
+
+      <div className="form-group">
+        <label htmlFor="archive-max-size">Size Limit</label>
+        <div className="range-group">
+          <input
+            id="archive-max-size"
+            type="range"
+            min="0"
+            max="10240"
+            step="256"
+            value={config.max_total_mb}
+            disabled={!config.enabled}
+            onChange={(e) => onChange("max_total_mb", parseInt(e.target.value))}
+          />
+          <span className="range-value">
+            {config.max_total_mb === 0 ? "No limit" : `${config.max_total_mb} MB`}
+          </span>
+        </div>
+        <p className="form-help">Oldest recordings are deleted first when a limit is reached.</p>
+      </div>
     </section>
   );
 }